@@ -1,10 +1,15 @@
+extern crate std;
+use std::path::{Path, PathBuf};
+
 extern crate structopt;
 use structopt::StructOpt;
 
-const DEFAULT_MAX_ACTIONS: &str = "8";
-const DEFAULT_MAX_FILLED_CELLS: &str = "7";
-const DEFAULT_MAX_WAITING_TASKS: &str = "2";
-const DEFAULT_THREADS_NUMBER: &str = "4";
+use liriodendron::error::Error;
+use liriodendron::template::Legend;
+
+use crate::config::Config;
+use crate::task::generation::archive::StreamFormat;
+use crate::task::generation::sink::{Output, STDOUT_PATH};
 
 /// StructOpt struct
 #[derive(StructOpt)]
@@ -20,32 +25,79 @@ pub struct Args {
     edit grids and generate textures")]
   user_interface: bool,
 
+  /// Number of textures generated from imported grids without the user
+  /// interface
+  #[structopt(short = "n", long = "generate", help = "Generates this number \
+    of textures from imported grids without the user interface")]
+  generate: Option<u32>,
+
+  /// Path where generated textures are streamed instead of a new directory
+  #[structopt(short = "o", long = "output", parse(from_os_str),
+    help = "Writes generated textures to a file, a named pipe or - for the \
+    standard output instead of a new directory")]
+  output: Option<PathBuf>,
+
+  /// Format of textures written to <i>output</i>
+  #[structopt(long = "stream-format", help = "Set the format of textures \
+    written to the output: images written one after the other, a tar or a \
+    zip archive [default: tar]")]
+  stream_format: Option<String>,
+
+  /// Format of the archive written by each generation instead of a new
+  /// directory
+  #[structopt(long = "archive", help = "Writes each generation in a new tar \
+    or zip archive with a manifest instead of a new directory")]
+  archive: Option<String>,
+
+  /// Images read as templates of the first grids
+  #[structopt(short = "i", long = "import", parse(from_os_str),
+    help = "Imports images or text masks as grids. Each image, animation \
+    frame or text mask becomes a grid")]
+  import: Vec<PathBuf>,
+
+  /// Maps the colors of imported images to pixels
+  #[structopt(short = "l", long = "legend", help = "Set the colors of \
+    imported images read as body, border, bodyborder and bodyempty pixels. \
+    Other colors are specific colors [default: ffff00=body,ff0000=border,\
+    0000ff=bodyborder,00ff00=bodyempty]")]
+  legend: Option<String>,
+
   /// Set the maximum number of saved actions in the <i>actions</i> member
   /// of <i>log</i>
-  #[structopt(short = "a", long = "max-actions",
-    default_value = DEFAULT_MAX_ACTIONS, help = "Set the maximum number of \
-    saved actions in the log")]
-  max_actions: u32,
-
-  /// Set the maximum number of saved filled cells in the <i>state</i> member
-  /// of <i>log</i> grids
-  #[structopt(short = "c", long = "max-filled-cells",
-    default_value = DEFAULT_MAX_FILLED_CELLS, help = "Set the maximum number \
-    of brushed cells on the grids")]
-  max_filled_cells: u32,
+  #[structopt(short = "a", long = "max-actions", help = "Set the maximum \
+    number of saved actions in the log [default: 14]")]
+  max_actions: Option<u32>,
+
+  /// Set the number of filled cells in the <i>state</i> member of <i>log</i>
+  /// grids above which the user is warned
+  #[structopt(short = "c", long = "max-filled-cells", help = "Set the number \
+    of brushed cells on the grids above which a memory warning is displayed \
+    [default: 7]")]
+  max_filled_cells: Option<u32>,
+
+  /// Set the maximum number of grids in the <i>names</i> member of <i>log</i>
+  /// grids
+  #[structopt(short = "g", long = "max-grids", help = "Set the maximum \
+    number of grids in the workspace [default: 10]")]
+  max_grids: Option<u32>,
 
   /// Set the maximum number of waiting tasks in the <i>tasksmanager</i>'s
   /// tasks queue
-  #[structopt(short = "w", long = "max-waiting-tasks",
-    default_value = DEFAULT_MAX_WAITING_TASKS, help = "Set the maximum \
-    number of possible waiting tasks")]
-  max_waiting_tasks: u32,
+  #[structopt(short = "w", long = "max-waiting-tasks", help = "Set the \
+    maximum number of possible waiting tasks [default: 2]")]
+  max_waiting_tasks: Option<u32>,
 
   /// Set the number of threads in the <i>tasksmanager</i>'s threadpool
-  #[structopt(short = "t", long = "threads-number",
-    default_value = DEFAULT_THREADS_NUMBER, help = "Set the maximum number \
-    of running tasks threads")]
-  threads_number: usize,
+  #[structopt(short = "t", long = "threads-number", help = "Set the maximum \
+    number of running tasks threads [default: 4]")]
+  threads_number: Option<u32>,
+
+  /// Configuration file read instead of the one of the user's configuration
+  /// directory
+  #[structopt(long = "config", parse(from_os_str), help = "Reads defaults, \
+    limits and key bindings from this file instead of \
+    $XDG_CONFIG_HOME/liriodendron/config")]
+  config: Option<PathBuf>,
 }
 
 impl Args {
@@ -53,40 +105,79 @@ impl Args {
     self.user_interface
   }
 
-  pub fn get_threads_number(&self) -> Result<usize, std::io::Error> {
-    if (self.threads_number > 8) || (self.threads_number < 1) {
-      Err(std::io::Error::new(std::io::ErrorKind::InvalidInput,
-        "threads number argument must be in this interval [1; 8]."))
-    } else {
-      Ok(self.threads_number)
-    }
+  pub fn get_config(&self) -> Option<&Path> {
+    self.config.as_deref()
   }
 
-  pub fn get_max_waiting_tasks(&self) -> Result<usize, std::io::Error> {
-    if (self.max_waiting_tasks > 7) || (self.max_waiting_tasks < 2) {
-      Err(std::io::Error::new(std::io::ErrorKind::InvalidInput,
-        "maximum waiting tasks argument must be in this interval [2; 7]."))
-    } else {
-      Ok(2usize.pow(self.max_waiting_tasks))
-    }
+  pub fn get_generate(&self, config: &Config) -> Option<Result<u32, Error>> {
+    self.generate.map(|number| {
+      config.limits.generate.check(number, "generate argument")
+    })
   }
 
-  pub fn get_max_actions(&self) -> Result<usize, std::io::Error> {
-    if (self.max_actions > 12) || (self.max_actions < 5) {
-      Err(std::io::Error::new(std::io::ErrorKind::InvalidInput,
-        "maximum actions argument must be in this interval [5; 12]."))
-    } else {
-      Ok(2usize.pow(self.max_actions))
+  /// The standard output can not be used with the user interface, which
+  /// draws on it
+  pub fn get_output(&self, config: &Config) -> Result<Output, Error> {
+    let format = StreamFormat::parse(self.stream_format.as_deref()
+      .unwrap_or(&config.defaults.stream_format))?;
+    match (&self.output, &self.archive) {
+      (Some(_), Some(_)) => Err(Error::InvalidArgument(String::from(
+        "output and archive arguments can not be used together."))),
+      (None, Some(archive)) => match StreamFormat::parse(archive)? {
+        StreamFormat::Images => Err(Error::InvalidArgument(String::from(
+          "archive argument must be tar or zip."))),
+        archive => Ok(Output::Archive(archive)),
+      },
+      (None, None) => Ok(Output::Directory),
+      (Some(path), _) if self.user_interface &&
+        (path.as_os_str() == STDOUT_PATH) =>
+        Err(Error::InvalidArgument(String::from(
+          "output argument can not be the standard output with the user \
+          interface."))),
+      (Some(path), _) => Ok(Output::Stream(path.clone(), format)),
     }
   }
 
-  pub fn get_max_filled_cells(&self) -> Result<usize, std::io::Error> {
-    if (self.max_filled_cells > 16) || (self.max_filled_cells < 7) {
-      Err(std::io::Error::new(std::io::ErrorKind::InvalidInput,
-        "maximum filled cells argument must be in this interval [7; 16]."))
-    } else {
-      Ok(2usize.pow(self.max_filled_cells))
-    }
+  pub fn get_threads_number(&self, config: &Config) -> Result<usize, Error> {
+    config.limits.threads_number.check(self.threads_number
+      .unwrap_or(config.defaults.threads_number), "threads number argument")
+      .map(|threads_number| threads_number as usize)
+  }
+
+  pub fn get_max_waiting_tasks(&self, config: &Config)
+    -> Result<usize, Error> {
+      config.limits.max_waiting_tasks.check(self.max_waiting_tasks
+        .unwrap_or(config.defaults.max_waiting_tasks),
+        "maximum waiting tasks argument")
+        .map(|max_waiting_tasks| 2usize.pow(max_waiting_tasks))
+  }
+
+  pub fn get_max_actions(&self, config: &Config) -> Result<usize, Error> {
+    config.limits.max_actions.check(self.max_actions
+      .unwrap_or(config.defaults.max_actions), "maximum actions argument")
+      .map(|max_actions| 2usize.pow(max_actions))
+  }
+
+  pub fn get_max_filled_cells(&self, config: &Config)
+    -> Result<usize, Error> {
+      config.limits.max_filled_cells.check(self.max_filled_cells
+        .unwrap_or(config.defaults.max_filled_cells),
+        "maximum filled cells argument")
+        .map(|max_filled_cells| 2usize.pow(max_filled_cells))
+  }
+
+  pub fn get_import(&self) -> &[PathBuf] {
+    &self.import
+  }
+
+  pub fn get_legend(&self, config: &Config) -> Result<Legend, Error> {
+    Legend::parse(self.legend.as_deref().unwrap_or(&config.defaults.legend))
+  }
+
+  pub fn get_max_grids(&self, config: &Config) -> Result<usize, Error> {
+    config.limits.max_grids.check(self.max_grids
+      .unwrap_or(config.defaults.max_grids), "maximum grids argument")
+      .map(|max_grids| max_grids as usize)
   }
 }
 
@@ -98,10 +189,18 @@ mod tests {
   fn it_uses_ui() {
     let args = Args {
       user_interface: true,
-      max_actions: DEFAULT_MAX_ACTIONS.parse::<u32>().unwrap(),
-      max_filled_cells: DEFAULT_MAX_FILLED_CELLS.parse::<u32>().unwrap(),
-      max_waiting_tasks: DEFAULT_MAX_WAITING_TASKS.parse::<u32>().unwrap(),
-      threads_number: DEFAULT_THREADS_NUMBER.parse::<usize>().unwrap(),
+      generate: None,
+      output: None,
+      stream_format: None,
+      archive: None,
+      import: Vec::new(),
+      legend: None,
+      max_actions: None,
+      max_filled_cells: None,
+      max_grids: None,
+      max_waiting_tasks: None,
+      threads_number: None,
+      config: None,
     };
     assert!(args.is_ui_used())
   }
@@ -110,11 +209,118 @@ mod tests {
   fn it_does_not_use_ui() {
     let args = Args {
       user_interface: false,
-      max_actions: DEFAULT_MAX_ACTIONS.parse::<u32>().unwrap(),
-      max_filled_cells: DEFAULT_MAX_FILLED_CELLS.parse::<u32>().unwrap(),
-      max_waiting_tasks: DEFAULT_MAX_WAITING_TASKS.parse::<u32>().unwrap(),
-      threads_number: DEFAULT_THREADS_NUMBER.parse::<usize>().unwrap(),
+      generate: None,
+      output: None,
+      stream_format: None,
+      archive: None,
+      import: Vec::new(),
+      legend: None,
+      max_actions: None,
+      max_filled_cells: None,
+      max_grids: None,
+      max_waiting_tasks: None,
+      threads_number: None,
+      config: None,
     };
     assert!(!args.is_ui_used())
   }
+
+  #[test]
+  fn it_parses_the_default_legend() {
+    let args = Args {
+      user_interface: true,
+      generate: None,
+      output: None,
+      stream_format: None,
+      archive: None,
+      import: Vec::new(),
+      legend: None,
+      max_actions: None,
+      max_filled_cells: None,
+      max_grids: None,
+      max_waiting_tasks: None,
+      threads_number: None,
+      config: None,
+    };
+    assert!(args.get_legend(&Config::default()).is_ok())
+  }
+
+  #[test]
+  fn it_does_not_stream_to_the_standard_output_with_ui() {
+    let mut args = Args {
+      user_interface: true,
+      generate: Some(3),
+      output: Some(PathBuf::from(STDOUT_PATH)),
+      stream_format: None,
+      archive: None,
+      import: Vec::new(),
+      legend: None,
+      max_actions: None,
+      max_filled_cells: None,
+      max_grids: None,
+      max_waiting_tasks: None,
+      threads_number: None,
+      config: None,
+    };
+    let config = Config::default();
+    let ui_output = args.get_output(&config);
+    args.user_interface = false;
+    assert!(ui_output.is_err() && (args.get_output(&config).unwrap() ==
+      Output::Stream(PathBuf::from(STDOUT_PATH), StreamFormat::Tar)));
+  }
+
+  #[test]
+  fn it_writes_archives_instead_of_directories() {
+    let mut args = Args {
+      user_interface: true,
+      generate: None,
+      output: None,
+      stream_format: None,
+      archive: Some(String::from("zip")),
+      import: Vec::new(),
+      legend: None,
+      max_actions: None,
+      max_filled_cells: None,
+      max_grids: None,
+      max_waiting_tasks: None,
+      threads_number: None,
+      config: None,
+    };
+    let config = Config::default();
+    let zip_output = args.get_output(&config).unwrap();
+    args.archive = Some(String::from("images"));
+    let images_output = args.get_output(&config);
+    args.archive = Some(String::from("tar"));
+    args.output = Some(PathBuf::from("textures.tar"));
+    assert!((zip_output == Output::Archive(StreamFormat::Zip)) &&
+      images_output.is_err() && args.get_output(&config).is_err());
+  }
+
+  #[test]
+  fn it_falls_back_on_the_defaults_and_limits_of_the_config() {
+    let mut args = Args {
+      user_interface: true,
+      generate: None,
+      output: None,
+      stream_format: None,
+      archive: None,
+      import: Vec::new(),
+      legend: None,
+      max_actions: None,
+      max_filled_cells: None,
+      max_grids: None,
+      max_waiting_tasks: None,
+      threads_number: None,
+      config: None,
+    };
+    let config = Config::parse("[defaults]\nthreads_number = 2\n\
+      [limits]\nthreads_number = [1; 3]\n").unwrap();
+    let default_threads_number = args.get_threads_number(&config).unwrap();
+    args.threads_number = Some(3);
+    let threads_number = args.get_threads_number(&config).unwrap();
+    args.threads_number = Some(4);
+    assert!((default_threads_number == 2) && (threads_number == 3) &&
+      args.get_threads_number(&config).is_err() &&
+      args.get_threads_number(&Config::default()).is_ok());
+  }
 }
@@ -0,0 +1,432 @@
+//! Defaults, limits and key bindings read from the user's configuration file
+
+extern crate std;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use liriodendron::error::{Error, Result};
+
+use crate::task::generation::MAX_NUMBER_GENERATIONS;
+use crate::userinterface::theme::Theme;
+
+/// Module for the keys bound to the actions of the editing views
+pub mod keys;
+
+/// Directory of the configuration file in the user's configuration directory
+const CONFIG_DIRECTORY: &str = "liriodendron";
+const CONFIG_FILE: &str = "config";
+
+const DEFAULT_LEGEND: &str =
+  "ffff00=body,ff0000=border,0000ff=bodyborder,00ff00=bodyempty";
+const DEFAULT_MAX_ACTIONS: u32 = 14;
+const DEFAULT_MAX_FILLED_CELLS: u32 = 7;
+const DEFAULT_MAX_GRIDS: u32 = 10;
+const DEFAULT_MAX_WAITING_TASKS: u32 = 2;
+const DEFAULT_STREAM_FORMAT: &str = "tar";
+const DEFAULT_THREADS_NUMBER: u32 = 4;
+const DEFAULT_THEME: &str = "default";
+
+/// Prefix of the sections defining custom themes, such as <i>[theme.dark]</i>
+const THEME_SECTION: &str = "theme.";
+
+/// Closed interval of the values accepted by an argument
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Interval {
+  pub min: u32,
+  pub max: u32,
+}
+
+impl Interval {
+
+  /// Return an error naming <i>argument</i> if <i>value</i> is outside of
+  /// the interval
+  pub fn check(&self, value: u32, argument: &str) -> Result<u32> {
+    if (value > self.max) || (value < self.min) {
+      Err(Error::InvalidArgument(format!(
+        "{} must be in this interval [{}; {}].", argument, self.min,
+        self.max)))
+    } else {
+      Ok(value)
+    }
+  }
+
+  /// Parses an interval written as <i>[min; max]</i>
+  fn parse(name: &str, value: &str) -> Result<Interval> {
+    let bounds = value.strip_prefix('[').and_then(|value|
+      value.strip_suffix(']')).map(|value| value.split(';')
+        .map(|bound| bound.trim().parse::<u32>()).collect::<Vec<_>>());
+    match bounds.as_deref() {
+      Some([Ok(min), Ok(max)]) if min <= max => Ok(Interval {
+        min: *min,
+        max: *max,
+      }),
+      _ => Err(Error::InvalidArgument(format!(
+        "{} must be an interval such as [1; 8].", name))),
+    }
+  }
+}
+
+/// Intervals of the command line arguments. They can only be narrowed by
+/// the configuration file.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Limits {
+  pub generate: Interval,
+  pub threads_number: Interval,
+
+  /// Interval of the power of two of the maximum number of waiting tasks
+  pub max_waiting_tasks: Interval,
+
+  /// Interval of the power of two of the maximum number of saved actions
+  pub max_actions: Interval,
+
+  /// Interval of the power of two of the maximum number of filled cells
+  pub max_filled_cells: Interval,
+
+  pub max_grids: Interval,
+}
+
+impl Default for Limits {
+  fn default() -> Limits {
+    Limits {
+      generate: Interval { min: 1, max: MAX_NUMBER_GENERATIONS },
+      threads_number: Interval { min: 1, max: 8 },
+      max_waiting_tasks: Interval { min: 2, max: 7 },
+      max_actions: Interval { min: 5, max: 20 },
+      max_filled_cells: Interval { min: 7, max: 16 },
+      max_grids: Interval { min: 1, max: 64 },
+    }
+  }
+}
+
+impl Limits {
+  fn set(&mut self, name: &str, value: &str) -> Result<()> {
+    let interval = Interval::parse(name, value)?;
+    match name {
+      "generate" => self.generate = interval,
+      "threads_number" => self.threads_number = interval,
+      "max_waiting_tasks" => self.max_waiting_tasks = interval,
+      "max_actions" => self.max_actions = interval,
+      "max_filled_cells" => self.max_filled_cells = interval,
+      "max_grids" => self.max_grids = interval,
+      _ => return Err(Error::InvalidArgument(format!(
+        "{} is not a limit.", name))),
+    }
+    Ok(())
+  }
+}
+
+/// Values used when an argument or a setting is not given
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Defaults {
+  pub legend: String,
+  pub stream_format: String,
+  pub threads_number: u32,
+  pub max_waiting_tasks: u32,
+  pub max_actions: u32,
+  pub max_filled_cells: u32,
+  pub max_grids: u32,
+
+  /// Name of a built-in or custom theme of the user interface
+  pub theme: String,
+
+  /// When <i>true</i>, grids hidden in the user interface are not generated
+  pub exclude_hidden_grids: bool,
+
+  /// Settings of the editing tools and of the textures, each tool keeps its
+  /// own default when they are not set
+  pub grid_size: Option<u16>,
+  pub pixel_ratio: Option<u16>,
+  pub body_empty_ratio: Option<u8>,
+  pub body_border_ratio: Option<u8>,
+  pub generation_number: Option<u32>,
+}
+
+impl Default for Defaults {
+  fn default() -> Defaults {
+    Defaults {
+      legend: String::from(DEFAULT_LEGEND),
+      stream_format: String::from(DEFAULT_STREAM_FORMAT),
+      threads_number: DEFAULT_THREADS_NUMBER,
+      max_waiting_tasks: DEFAULT_MAX_WAITING_TASKS,
+      max_actions: DEFAULT_MAX_ACTIONS,
+      max_filled_cells: DEFAULT_MAX_FILLED_CELLS,
+      max_grids: DEFAULT_MAX_GRIDS,
+      theme: String::from(DEFAULT_THEME),
+      exclude_hidden_grids: false,
+      grid_size: None,
+      pixel_ratio: None,
+      body_empty_ratio: None,
+      body_border_ratio: None,
+      generation_number: None,
+    }
+  }
+}
+
+impl Defaults {
+  fn set(&mut self, name: &str, value: &str) -> Result<()> {
+    match name {
+      "legend" => self.legend = String::from(value),
+      "stream_format" => self.stream_format = String::from(value),
+      "threads_number" => self.threads_number = parse_number(name, value)?,
+      "max_waiting_tasks" =>
+        self.max_waiting_tasks = parse_number(name, value)?,
+      "max_actions" => self.max_actions = parse_number(name, value)?,
+      "max_filled_cells" =>
+        self.max_filled_cells = parse_number(name, value)?,
+      "max_grids" => self.max_grids = parse_number(name, value)?,
+      "theme" => self.theme = String::from(value),
+      "exclude_hidden_grids" =>
+        self.exclude_hidden_grids = value.parse::<bool>().map_err(|_|
+          Error::InvalidArgument(format!(
+            "{} must be true or false.", name)))?,
+      "grid_size" => self.grid_size = Some(parse_number(name, value)?),
+      "pixel_ratio" => self.pixel_ratio = Some(parse_number(name, value)?),
+      "body_empty_ratio" =>
+        self.body_empty_ratio = Some(parse_number(name, value)?),
+      "body_border_ratio" =>
+        self.body_border_ratio = Some(parse_number(name, value)?),
+      "generation_number" =>
+        self.generation_number = Some(parse_number(name, value)?),
+      _ => return Err(Error::InvalidArgument(format!(
+        "{} is not a default.", name))),
+    }
+    Ok(())
+  }
+}
+
+fn parse_number<T: FromStr>(name: &str, value: &str) -> Result<T> {
+  value.parse::<T>().map_err(|_| Error::InvalidArgument(format!(
+    "{} must be a positive number.", name)))
+}
+
+/// Settings of the user, read from
+/// <i>$XDG_CONFIG_HOME/liriodendron/config</i>
+///
+/// The file is made of <i>name = value</i> lines in the <i>[defaults]</i>,
+/// <i>[limits]</i> and <i>[keys]</i> sections, and in <i>[theme.name]</i>
+/// sections defining custom themes. Lines starting with <i>#</i> are
+/// comments.
+#[derive(Default)]
+pub struct Config {
+  pub defaults: Defaults,
+  pub limits: Limits,
+  pub keys: keys::Keys,
+
+  /// Theme called by the <i>theme</i> default
+  pub theme: Theme,
+
+  /// Custom themes, in order of definition
+  themes: Vec<(String, Theme)>,
+}
+
+impl Config {
+
+  /// Reads the configuration file at <i>path</i>, or the one of the user's
+  /// configuration directory. The built-in settings are used when the
+  /// latter does not exist.
+  pub fn load(path: Option<&Path>) -> Result<Config> {
+    let (path, is_required) = match path {
+      Some(path) => (path.to_path_buf(), true),
+      None => match get_path() {
+        Some(path) => (path, false),
+        None => return Ok(Config::default()),
+      },
+    };
+    match fs::read_to_string(&path) {
+      Ok(text) => Config::parse(&text),
+      Err(error) if !is_required &&
+        (error.kind() == io::ErrorKind::NotFound) => Ok(Config::default()),
+      Err(error) => Err(Error::from(error)),
+    }.map_err(|error| error.context(&path.display().to_string()))
+  }
+
+  pub fn parse(text: &str) -> Result<Config> {
+    let mut config = Config::default();
+    let mut section = String::new();
+    for (index, line) in text.lines().enumerate() {
+      let line = line.trim();
+      if !line.is_empty() && !line.starts_with('#') {
+        config.parse_line(&mut section, line).map_err(|error|
+          error.context(&format!("line {}", index + 1)))?;
+      }
+    }
+    config.theme = config.get_theme(&config.defaults.theme)?;
+    config.check()?;
+    Ok(config)
+  }
+
+  /// Returns the custom or built-in theme called <i>name</i>
+  fn get_theme(&self, name: &str) -> Result<Theme> {
+    self.themes.iter().rev().find(|(theme_name, _)| theme_name == name)
+      .map(|&(_, theme)| theme).or_else(|| Theme::get_builtin(name))
+      .ok_or_else(|| Error::InvalidArgument(format!(
+        "{} is not a theme.", name)))
+  }
+
+  /// Sets a color of the last custom theme, or replaces all of them by the
+  /// ones of the <i>base</i> theme
+  fn set_theme(&mut self, name: &str, value: &str) -> Result<()> {
+    let theme = if name == "base" {
+      self.get_theme(value)?
+    } else {
+      let mut theme = self.themes.last().unwrap().1;
+      theme.set(name, value)?;
+      theme
+    };
+    self.themes.last_mut().unwrap().1 = theme;
+    Ok(())
+  }
+
+  fn parse_line(&mut self, section: &mut String, line: &str) -> Result<()> {
+    if let Some(name) = line.strip_prefix('[')
+      .and_then(|line| line.strip_suffix(']')) {
+        return match name.trim() {
+          "defaults" | "limits" | "keys" => {
+            *section = String::from(name.trim());
+            Ok(())
+          },
+          name if name.len() > THEME_SECTION.len() &&
+            name.starts_with(THEME_SECTION) => {
+              *section = String::from(name);
+              self.themes.push((String::from(&name[THEME_SECTION.len()..]),
+                Theme::default()));
+              Ok(())
+          },
+          name => Err(Error::InvalidArgument(format!(
+            "{} is not a section.", name))),
+        }
+    }
+    let (name, value) = match line.find('=') {
+      Some(position) =>
+        (line[..position].trim(), line[position + 1..].trim()),
+      None => return Err(Error::InvalidArgument(String::from(
+        "a setting must be written as name = value."))),
+    };
+    match section.as_str() {
+      "defaults" => self.defaults.set(name, value),
+      "limits" => self.limits.set(name, value),
+      "keys" => self.keys.bind(name, value),
+      theme if theme.starts_with(THEME_SECTION) =>
+        self.set_theme(name, value),
+      _ => Err(Error::InvalidArgument(format!(
+        "{} is outside of a section.", name))),
+    }
+  }
+
+  /// Return an error if a limit is wider than the built-in one, if a
+  /// default is outside of its limit or if a key is bound twice
+  fn check(&self) -> Result<()> {
+    let built_in = Limits::default();
+    let defaults = &self.defaults;
+    let limits = [
+      ("generate", self.limits.generate, built_in.generate,
+        defaults.generation_number),
+      ("threads_number", self.limits.threads_number,
+        built_in.threads_number, Some(defaults.threads_number)),
+      ("max_waiting_tasks", self.limits.max_waiting_tasks,
+        built_in.max_waiting_tasks, Some(defaults.max_waiting_tasks)),
+      ("max_actions", self.limits.max_actions, built_in.max_actions,
+        Some(defaults.max_actions)),
+      ("max_filled_cells", self.limits.max_filled_cells,
+        built_in.max_filled_cells, Some(defaults.max_filled_cells)),
+      ("max_grids", self.limits.max_grids, built_in.max_grids,
+        Some(defaults.max_grids)),
+    ];
+    for &(name, limit, built_in_limit, default) in limits.iter() {
+      built_in_limit.check(limit.min, &format!("{} limit", name))?;
+      built_in_limit.check(limit.max, &format!("{} limit", name))?;
+      if let Some(default) = default {
+        limit.check(default, &format!("{} default", name))?;
+      }
+    }
+    self.keys.check()
+  }
+}
+
+/// Path of the configuration file in <i>$XDG_CONFIG_HOME</i>, or in
+/// <i>$HOME/.config</i> when it is not set
+fn get_path() -> Option<PathBuf> {
+  env::var_os("XDG_CONFIG_HOME").map(PathBuf::from)
+    .filter(|directory| directory.is_absolute())
+    .or_else(|| env::var_os("HOME")
+      .map(|home| PathBuf::from(home).join(".config")))
+    .map(|directory| directory.join(CONFIG_DIRECTORY).join(CONFIG_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+  use self::keys::{Action, Context};
+
+  extern crate crossterm;
+  use crossterm::event::{KeyCode, KeyEvent};
+
+  extern crate tui;
+  use tui::style::Color;
+
+  use liriodendron::utils::FullPixel;
+
+  #[test]
+  fn it_reads_defaults_limits_and_keys() {
+    let config = Config::parse("# Liriodendron\n\n[defaults]\n\
+      threads_number = 2\ngrid_size = 16\nlegend = ff0000=body\n\
+      [limits]\nthreads_number = [1; 2]\n[keys]\nundo = y\nadd_grid = =\n")
+      .unwrap();
+    assert!((config.defaults.threads_number == 2) &&
+      (config.defaults.grid_size == Some(16)) &&
+      (config.defaults.legend == "ff0000=body") &&
+      config.defaults.pixel_ratio.is_none() &&
+      (config.limits.threads_number == Interval { min: 1, max: 2 }) &&
+      (config.limits.max_grids == Limits::default().max_grids) &&
+      (config.keys.get_action(Context::Edit,
+        KeyEvent::from(KeyCode::Char('y'))) == Some(Action::Undo)) &&
+      (config.keys.get_action(Context::Edit,
+        KeyEvent::from(KeyCode::Char('='))) == Some(Action::AddGrid)));
+  }
+
+  #[test]
+  fn it_tells_the_line_of_a_wrong_setting() {
+    let unknown = Config::parse("[defaults]\n\nthreads = 2\n");
+    let outside = Config::parse("threads_number = 2\n");
+    let interval = Config::parse("[limits]\nmax_grids = [8; 2]\n");
+    assert!(unknown.is_err() && outside.is_err() && interval.is_err() &&
+      unknown.err().unwrap().to_string().starts_with("line 3: ") &&
+      (outside.err().unwrap().exit_code() == 2));
+  }
+
+  #[test]
+  fn it_only_narrows_the_limits_around_the_defaults() {
+    let wider = Config::parse("[limits]\nthreads_number = [1; 16]\n");
+    let default_outside = Config::parse("[limits]\nthreads_number = [1; 2]\n");
+    let narrower = Config::parse("[defaults]\nmax_grids = 4\n\
+      [limits]\nmax_grids = [2; 4]\n");
+    assert!(wider.is_err() && default_outside.is_err() && narrower.is_ok());
+  }
+
+  #[test]
+  fn it_selects_a_custom_theme_based_on_another_one() {
+    let config = Config::parse("[theme.dark]\nbase = 16colors\n\
+      body = #ffff40\n[theme.darker]\nbase = dark\nborder = white\n\
+      [defaults]\ntheme = darker\n").unwrap();
+    let builtin = Config::parse("[defaults]\ntheme = colorblind\n").unwrap();
+    let (body, border) = (config.theme.get_pixel_color(FullPixel::Body),
+      config.theme.get_pixel_color(FullPixel::Border));
+    assert!((body == Color::LightYellow) && (border == Color::White) &&
+      (config.theme.get_workspace_color() == Color::Gray) &&
+      (builtin.theme == Theme::get_builtin("colorblind").unwrap()) &&
+      Config::parse("[defaults]\ntheme = light\n").is_err() &&
+      Config::parse("[theme.light]\nbody = orange\n").is_err());
+  }
+
+  #[test]
+  fn it_needs_the_given_configuration_file() {
+    let path = std::env::temp_dir().join("liriodendron_missing_config");
+    let error = Config::load(Some(&path)).err().unwrap();
+    assert!((error.exit_code() == 5) &&
+      error.to_string().starts_with(&path.display().to_string()));
+  }
+}
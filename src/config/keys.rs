@@ -0,0 +1,348 @@
+extern crate crossterm;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+extern crate std;
+use std::collections::HashMap;
+
+use liriodendron::error::{Error, Result};
+
+/// Actions of the editing views which can be bound to keys
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Action {
+  CursorLeft,
+  CursorDown,
+  CursorUp,
+  CursorRight,
+  CursorBrush,
+  CursorErase,
+  CursorCyclePixel,
+  Exit,
+  PreviousBrush,
+  NextBrush,
+  SetBrushColor,
+  Export,
+  Generate,
+  Tasks,
+  IncrementHeight,
+  DecrementHeight,
+  IncrementWidth,
+  DecrementWidth,
+  ToggleCursor,
+  NextGrid,
+  PreviousGrid,
+  SwitchView,
+  RenameGrid,
+  ResizeGrid,
+  Undo,
+  Redo,
+  History,
+  AddGrid,
+  DeleteGrid,
+  ScrollUp,
+  ScrollDown,
+  ScrollLeft,
+  ScrollRight,
+  ClearGrid,
+  SwitchGridsOrder,
+  IncrementBodyEmptyRatio,
+  DecrementBodyEmptyRatio,
+  IncrementBodyBorderRatio,
+  DecrementBodyBorderRatio,
+  SetTextureFormat,
+  IncrementPixelRatio,
+  DecrementPixelRatio,
+  ZoomIn,
+  ZoomOut,
+  ToggleOnionSkin,
+  ToggleComposite,
+  ToggleVisibility,
+  ToggleLock,
+}
+
+/// Set of actions reachable at the same time. The keys of an action must
+/// not be bound to another action of the same context.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Context {
+
+  /// Edit view when the cell cursor is enabled, its bindings are looked up
+  /// before the ones of <i>Edit</i>, so they must not be bound to an action
+  /// of <i>Edit</i> either
+  Cursor,
+
+  Edit,
+  Parameters,
+}
+
+const CURSOR: &[Context] = &[Context::Cursor];
+const EDIT: &[Context] = &[Context::Edit];
+const PARAMETERS: &[Context] = &[Context::Parameters];
+const EDIT_PARAMETERS: &[Context] = &[Context::Edit, Context::Parameters];
+
+/// Contexts whose keys are checked together for conflicts
+const CHECKED_CONTEXTS: [&[Context]; 2] =
+  [&[Context::Cursor, Context::Edit], PARAMETERS];
+
+/// Name of each action in the configuration file, its default keys and the
+/// contexts where it is reachable
+const BINDINGS: [(Action, &str, &str, &[Context]); 48] = [
+  (Action::CursorLeft, "cursor_left", "j Alt+Left", CURSOR),
+  (Action::CursorDown, "cursor_down", "k Alt+Down", CURSOR),
+  (Action::CursorUp, "cursor_up", "i Alt+Up", CURSOR),
+  (Action::CursorRight, "cursor_right", "l Alt+Right", CURSOR),
+  (Action::CursorBrush, "cursor_brush", "Space", CURSOR),
+  (Action::CursorErase, "cursor_erase", "x", CURSOR),
+  (Action::CursorCyclePixel, "cursor_cycle_pixel", "p", CURSOR),
+  (Action::Exit, "exit", "Esc", EDIT_PARAMETERS),
+  (Action::PreviousBrush, "previous_brush", "a", EDIT),
+  (Action::NextBrush, "next_brush", "q", EDIT),
+  (Action::SetBrushColor, "set_brush_color", "c", EDIT),
+  (Action::Export, "export", "e", EDIT),
+  (Action::Generate, "generate", "g", EDIT_PARAMETERS),
+  (Action::Tasks, "tasks", "t", EDIT_PARAMETERS),
+  (Action::IncrementHeight, "increment_height", "h", EDIT),
+  (Action::DecrementHeight, "decrement_height", "H", EDIT),
+  (Action::IncrementWidth, "increment_width", "w", EDIT),
+  (Action::DecrementWidth, "decrement_width", "W", EDIT),
+  (Action::ToggleCursor, "toggle_cursor", "m", EDIT),
+  (Action::NextGrid, "next_grid", "n", EDIT),
+  (Action::PreviousGrid, "previous_grid", "N", EDIT),
+  (Action::SwitchView, "switch_view", "s", EDIT_PARAMETERS),
+  (Action::RenameGrid, "rename_grid", "r", EDIT),
+  (Action::ResizeGrid, "resize_grid", "R", EDIT),
+  (Action::Undo, "undo", "u", EDIT),
+  (Action::Redo, "redo", "U", EDIT),
+  (Action::History, "history", "L", EDIT),
+  (Action::AddGrid, "add_grid", "+", EDIT),
+  (Action::DeleteGrid, "delete_grid", "-", EDIT),
+  (Action::ScrollUp, "scroll_up", "Up", EDIT),
+  (Action::ScrollDown, "scroll_down", "Down", EDIT),
+  (Action::ScrollLeft, "scroll_left", "Left", EDIT),
+  (Action::ScrollRight, "scroll_right", "Right", EDIT),
+  (Action::ClearGrid, "clear_grid", "C", EDIT),
+  (Action::SwitchGridsOrder, "switch_grids_order", "S", EDIT),
+  (Action::IncrementBodyEmptyRatio, "increment_body_empty_ratio", "b",
+    PARAMETERS),
+  (Action::DecrementBodyEmptyRatio, "decrement_body_empty_ratio", "B",
+    PARAMETERS),
+  (Action::IncrementBodyBorderRatio, "increment_body_border_ratio", "n",
+    PARAMETERS),
+  (Action::DecrementBodyBorderRatio, "decrement_body_border_ratio", "N",
+    PARAMETERS),
+  (Action::SetTextureFormat, "set_texture_format", "f", PARAMETERS),
+  (Action::IncrementPixelRatio, "increment_pixel_ratio", "p", PARAMETERS),
+  (Action::DecrementPixelRatio, "decrement_pixel_ratio", "P", PARAMETERS),
+  (Action::ZoomIn, "zoom_in", "z", EDIT),
+  (Action::ZoomOut, "zoom_out", "Z", EDIT),
+  (Action::ToggleOnionSkin, "toggle_onion_skin", "o", EDIT),
+  (Action::ToggleComposite, "toggle_composite", "O", EDIT),
+  (Action::ToggleVisibility, "toggle_visibility", "v", EDIT),
+  (Action::ToggleLock, "toggle_lock", "V", EDIT),
+];
+
+/// Named keys of the configuration file, other keys are single characters
+const KEY_NAMES: [(KeyCode, &str); 15] = [
+  (KeyCode::Char(' '), "Space"),
+  (KeyCode::Esc, "Esc"),
+  (KeyCode::Enter, "Enter"),
+  (KeyCode::Tab, "Tab"),
+  (KeyCode::Backspace, "Backspace"),
+  (KeyCode::Delete, "Delete"),
+  (KeyCode::Insert, "Insert"),
+  (KeyCode::Home, "Home"),
+  (KeyCode::End, "End"),
+  (KeyCode::PageUp, "PageUp"),
+  (KeyCode::PageDown, "PageDown"),
+  (KeyCode::Left, "Left"),
+  (KeyCode::Right, "Right"),
+  (KeyCode::Up, "Up"),
+  (KeyCode::Down, "Down"),
+];
+
+/// Symbols of the arrow keys in the shortcuts panels
+const ARROWS: [(KeyCode, &str); 4] = [
+  (KeyCode::Left, "← "),
+  (KeyCode::Up, "↑ "),
+  (KeyCode::Down, "↓ "),
+  (KeyCode::Right, "→ "),
+];
+
+const CONTROL_PREFIX: &str = "Ctrl+";
+const ALT_PREFIX: &str = "Alt+";
+
+impl Context {
+  fn get_actions(self) -> Vec<Action> {
+    BINDINGS.iter()
+      .filter(|&&(_, _, _, contexts)| contexts.contains(&self))
+      .map(|&(action, _, _, _)| action).collect()
+  }
+}
+
+/// Parses a key of the configuration file: a single character, which is
+/// shifted when it is uppercase, a key name such as <i>Space</i> or
+/// <i>F5</i>, optionally prefixed by <i>Ctrl+</i> or <i>Alt+</i>
+fn parse_key(name: &str) -> Result<KeyEvent> {
+  let (mut modifiers, name) = if name.len() > CONTROL_PREFIX.len() &&
+    name.starts_with(CONTROL_PREFIX) {
+      (KeyModifiers::CONTROL, &name[CONTROL_PREFIX.len()..])
+  } else if name.len() > ALT_PREFIX.len() && name.starts_with(ALT_PREFIX) {
+    (KeyModifiers::ALT, &name[ALT_PREFIX.len()..])
+  } else {
+    (KeyModifiers::NONE, name)
+  };
+  let mut chars = name.chars();
+  let code = match (chars.next(), chars.next()) {
+    (Some(character), None) => {
+      if character.is_uppercase() {
+        modifiers |= KeyModifiers::SHIFT;
+      }
+      KeyCode::Char(character)
+    },
+    _ => match KEY_NAMES.iter().find(|&&(_, key_name)| key_name == name) {
+      Some(&(code, _)) => code,
+      None => match name.strip_prefix('F').map(str::parse::<u8>) {
+        Some(Ok(number)) if (1..=12).contains(&number) => KeyCode::F(number),
+        _ => return Err(Error::InvalidArgument(format!(
+          "{} is not a key.", name))),
+      },
+    },
+  };
+  Ok(KeyEvent::new(code, modifiers))
+}
+
+/// Label of <i>key</i> in the shortcuts panels
+fn get_label(key: &KeyEvent) -> String {
+  let name = match key.code {
+    KeyCode::Char(' ') => String::from("Space"),
+    KeyCode::Char(character) => character.to_string(),
+    KeyCode::F(number) => format!("F{}", number),
+    code => match ARROWS.iter().chain(KEY_NAMES.iter())
+      .find(|&&(key_code, _)| key_code == code) {
+        Some(&(_, name)) => String::from(name),
+        None => String::new(),
+      },
+  };
+  if key.modifiers.contains(KeyModifiers::CONTROL) {
+    format!("{}{}", CONTROL_PREFIX, name)
+  } else if key.modifiers.contains(KeyModifiers::ALT) {
+    format!("{}{}", ALT_PREFIX, name)
+  } else {
+    name
+  }
+}
+
+/// Keys bound to each action
+pub struct Keys {
+  bindings: HashMap<Action, Vec<KeyEvent>>,
+}
+
+impl Default for Keys {
+  fn default() -> Keys {
+    let bindings = BINDINGS.iter().map(|&(action, _, keys, _)| {
+      (action, keys.split_whitespace().map(|key| parse_key(key).unwrap())
+        .collect())
+    }).collect();
+    Keys {
+      bindings,
+    }
+  }
+}
+
+impl Keys {
+
+  /// Replaces the keys of the action called <i>name</i> by <i>keys</i>,
+  /// separated by spaces
+  pub fn bind(&mut self, name: &str, keys: &str) -> Result<()> {
+    let action = match BINDINGS.iter()
+      .find(|&&(_, action_name, _, _)| action_name == name) {
+        Some(&(action, _, _, _)) => action,
+        None => return Err(Error::InvalidArgument(format!(
+          "{} is not an action.", name))),
+      };
+    let keys = keys.split_whitespace().map(parse_key)
+      .collect::<Result<Vec<KeyEvent>>>()?;
+    if keys.is_empty() {
+      return Err(Error::InvalidArgument(format!("{} needs a key.", name)))
+    }
+    self.bindings.insert(action, keys);
+    Ok(())
+  }
+
+  /// Return an error if a key is bound to two actions of a context, or to
+  /// an action of <i>Cursor</i> and an action of <i>Edit</i>
+  pub fn check(&self) -> Result<()> {
+    for contexts in CHECKED_CONTEXTS.iter() {
+      let mut bound_keys = HashMap::new();
+      for action in contexts.iter().flat_map(|context| context.get_actions()) {
+        for key in self.bindings[&action].iter() {
+          if let Some(other) = bound_keys.insert(*key, action) {
+            return Err(Error::InvalidArgument(format!(
+              "{} is bound to {} and {}.", get_label(key),
+              get_name(other), get_name(action))))
+          }
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// Returns the action of <i>context</i> bound to <i>key</i>
+  pub fn get_action(&self, context: Context, key: KeyEvent)
+    -> Option<Action> {
+      context.get_actions().into_iter()
+        .find(|action| self.bindings[action].contains(&key))
+  }
+
+  /// Returns the first key of each action of <i>actions</i> as a shortcut
+  /// instruction, such as <i>< u | U | L ></i>
+  pub fn get_instruction(&self, actions: &[Action]) -> String {
+    format!("< {} >", actions.iter()
+      .map(|action| get_label(&self.bindings[action][0]))
+      .collect::<Vec<String>>().join(" | "))
+  }
+}
+
+/// Name of <i>action</i> in the configuration file
+fn get_name(action: Action) -> &'static str {
+  BINDINGS.iter().find(|&&(bound_action, _, _, _)| bound_action == action)
+    .map(|&(_, name, _, _)| name).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+
+  #[test]
+  fn it_binds_the_default_keys_without_conflicts() {
+    let keys = Keys::default();
+    let shifted_u = KeyEvent::new(KeyCode::Char('U'), KeyModifiers::SHIFT);
+    assert!(keys.check().is_ok() &&
+      (keys.get_action(Context::Edit, shifted_u) == Some(Action::Redo)) &&
+      (keys.get_action(Context::Cursor,
+        KeyEvent::new(KeyCode::Left, KeyModifiers::ALT)) ==
+        Some(Action::CursorLeft)) &&
+      (keys.get_action(Context::Parameters,
+        KeyEvent::from(KeyCode::Char('n'))) ==
+        Some(Action::IncrementBodyBorderRatio)) &&
+      (keys.get_instruction(&[Action::ScrollLeft, Action::ScrollUp]) ==
+        "< ←  | ↑  >"));
+  }
+
+  #[test]
+  fn it_rebinds_an_action_and_finds_conflicts() {
+    let mut keys = Keys::default();
+    keys.bind("undo", "Ctrl+z F2").unwrap();
+    let control_z = KeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL);
+    let undo = keys.get_action(Context::Edit, control_z);
+    let instruction = keys.get_instruction(&[Action::Undo, Action::Redo]);
+    keys.bind("exit", "s").unwrap();
+    let exit_conflict = keys.check().is_err();
+    keys.bind("exit", "Esc").unwrap();
+    keys.bind("cursor_left", "h").unwrap();
+    assert!((undo == Some(Action::Undo)) &&
+      (instruction == "< Ctrl+z | U >") && exit_conflict &&
+      keys.check().is_err() &&
+      keys.bind("undo", "Shift").is_err() &&
+      keys.bind("fly", "f").is_err() && keys.bind("undo", " ").is_err());
+  }
+}
@@ -0,0 +1,145 @@
+//! Errors of the library and of the user interface
+
+extern crate image;
+use image::ImageError;
+
+extern crate std;
+use std::fmt;
+use std::io;
+
+/// Result whose error is an <i>Error</i>
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+
+  /// An argument or a setting is outside of its interval, or conflicts with
+  /// another one
+  InvalidArgument(String),
+
+  /// A grid name is empty or already used
+  GridName(String),
+
+  /// A limit of the grids, the templates or the archives is exceeded
+  Overflow(String),
+
+  /// A file, a directory or a stream can not be read or written
+  Io(io::Error),
+
+  /// An image or a text mask can not be encoded or decoded
+  Encoding(String),
+}
+
+impl Error {
+
+  /// Code returned by the process ending with this error. Each variant has
+  /// its own code, 1 is left to panics.
+  pub fn exit_code(&self) -> i32 {
+    match self {
+      Error::InvalidArgument(_) => 2,
+      Error::GridName(_) => 3,
+      Error::Overflow(_) => 4,
+      Error::Io(_) => 5,
+      Error::Encoding(_) => 6,
+    }
+  }
+
+  /// Prefixes the message with <i>context</i>, keeping the variant
+  pub fn context(self, context: &str) -> Error {
+    match self {
+      Error::InvalidArgument(message) =>
+        Error::InvalidArgument(format!("{}: {}", context, message)),
+      Error::GridName(message) =>
+        Error::GridName(format!("{}: {}", context, message)),
+      Error::Overflow(message) =>
+        Error::Overflow(format!("{}: {}", context, message)),
+      Error::Io(error) => Error::Io(io::Error::new(error.kind(),
+        format!("{}: {}", context, error))),
+      Error::Encoding(message) =>
+        Error::Encoding(format!("{}: {}", context, message)),
+    }
+  }
+}
+
+/// A cloned I/O error keeps its kind and its message
+impl Clone for Error {
+  fn clone(&self) -> Error {
+    match self {
+      Error::InvalidArgument(message) =>
+        Error::InvalidArgument(message.clone()),
+      Error::GridName(message) => Error::GridName(message.clone()),
+      Error::Overflow(message) => Error::Overflow(message.clone()),
+      Error::Io(error) =>
+        Error::Io(io::Error::new(error.kind(), error.to_string())),
+      Error::Encoding(message) => Error::Encoding(message.clone()),
+    }
+  }
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Error::InvalidArgument(message) | Error::GridName(message) |
+      Error::Overflow(message) | Error::Encoding(message) =>
+        write!(f, "{}", message),
+      Error::Io(error) => write!(f, "{}", error),
+    }
+  }
+}
+
+impl std::error::Error for Error {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      Error::Io(error) => Some(error),
+      _ => None,
+    }
+  }
+}
+
+impl From<io::Error> for Error {
+  fn from(error: io::Error) -> Error {
+    Error::Io(error)
+  }
+}
+
+/// Reading or writing failures of an image stay I/O errors
+impl From<ImageError> for Error {
+  fn from(error: ImageError) -> Error {
+    match error {
+      ImageError::IoError(error) => Error::Io(error),
+      error => Error::Encoding(error.to_string()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+
+  #[test]
+  fn it_gives_a_distinct_exit_code_to_each_variant() {
+    let mut codes = vec![
+      Error::InvalidArgument(String::new()).exit_code(),
+      Error::GridName(String::new()).exit_code(),
+      Error::Overflow(String::new()).exit_code(),
+      Error::Io(io::Error::from(io::ErrorKind::Other)).exit_code(),
+      Error::Encoding(String::new()).exit_code(),
+    ];
+    codes.sort_unstable();
+    codes.dedup();
+    assert!((codes.len() == 5) && !codes.contains(&0) && !codes.contains(&1));
+  }
+
+  #[test]
+  fn it_adds_a_context_and_keeps_the_variant() {
+    let io_error = Error::from(io::Error::new(io::ErrorKind::NotFound,
+      "missing")).context("mask.png");
+    let overflow = Error::Overflow(String::from("too large"))
+      .context("mask.png");
+    assert!((io_error.to_string() == "mask.png: missing") &&
+      (io_error.exit_code() == 5) &&
+      (overflow.to_string() == "mask.png: too large") &&
+      (overflow.exit_code() == 4));
+  }
+}
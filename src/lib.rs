@@ -0,0 +1,22 @@
+//! # liriodendron
+//!
+//! `liriodendron` is the library which renders textures without the terminal
+//! user interface. A mask is built programmatically or from templates, then
+//! rendered in memory:
+//!
+//! ```
+//! use liriodendron::texture::{Mask, Settings, Texture};
+//! use liriodendron::utils::FullPixel;
+//!
+//! let mut mask = Mask::new(8, 8);
+//! mask.set_cell(0, (3, 3), FullPixel::Body).unwrap();
+//! let texture = Texture::new(&mask, &Settings::new()).unwrap();
+//! let image = texture.render(42);
+//! assert_eq!(image.dimensions(), (32, 32));
+//! ```
+
+pub mod error;
+pub mod template;
+pub mod texture;
+
+pub mod utils;
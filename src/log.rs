@@ -4,17 +4,34 @@ extern crate num;
 use num::PrimInt;
 
 extern crate std;
+use std::cmp::{max, min};
 use std::collections::{HashMap, VecDeque};
 use std::convert::TryFrom;
+use std::time::{Duration, Instant};
 
 mod action;
 
 mod tools;
-use tools::{brush, grids, texturessettings, setter::Setter};
+pub use tools::pointer::{Axis, Scroller};
+use tools::{brush, cursor, exportsettings, grids, overlay, pointer,
+  texturessettings, zoom, setter::Setter};
 
-use crate::utils::FullPixel;
+use liriodendron::utils::FullPixel;
 
-use crate::task::generation::palette::PaletteGeneration;
+use liriodendron::texture::palette::PaletteGeneration;
+use liriodendron::error::Error;
+use liriodendron::template::{MaskFormat, Template};
+
+use crate::config::{Defaults, Interval};
+
+/// Maximum delay between two identical grid size actions to group them, as
+/// when a key is held
+const KEY_REPEAT_DELAY: Duration = Duration::from_millis(500);
+
+/// Number of snapshots kept for a full history. Each one is a copy of every
+/// grid, so a longer history replays more actions rather than keeping more
+/// copies.
+const MAX_SNAPSHOTS: usize = 16;
 
 /// Represents a communication interface between crates. Methods are using
 /// this notation to optimize readability: `tool_toolmethod()`
@@ -27,14 +44,27 @@ pub struct Log {
   brush: brush::Brush,
   brushcolor: brush::brushcolor::BrushColor,
 
+  /// Cell cursor to edit grids with the keyboard
+  cursor: cursor::Cursor,
+
   /// Delayed state of grids
   delayedgrids: grids::Grids,
 
+  exportsettings: exportsettings::ExportSettings,
+
   generationnumber: texturessettings::generationnumber::GenerationNumber,
   gridname: grids::gridname::GridName,
+  gridsize: grids::gridsize::GridSize,
 
   /// Actual state of grids
   grids: grids::Grids,
+
+  /// Grids shown with the current grid in the <i>user interface</i>
+  overlay: overlay::Overlay,
+
+  /// Mouse pointer at its last click in the <i>user interface</i>
+  pointer: pointer::Pointer,
+
   texturessettings: texturessettings::TexturesSettings,
 
   /// When <i>true</i>, a <i>grids</i>.<i>state</i>'s overflow occured
@@ -45,26 +75,97 @@ pub struct Log {
 
   /// Stocks last canceled actions
   canceledactions: Vec<action::Action>,
+
+  /// Number of actions poped from <i>actions</i> and applied to
+  /// <i>delayedgrids</i>
+  foldedactions: usize,
+
+  /// States of grids saved every <i>snapshotinterval</i> actions. Each state
+  /// is saved with the number of actions applied since the first action of
+  /// the session, so an undo only replays actions from the nearest snapshot
+  snapshots: VecDeque<(usize, grids::Grids)>,
+  snapshotinterval: usize,
+
+  /// Selected state in the history view
+  historycursor: usize,
+
+  /// Number of applied actions when every grid was last exported, or
+  /// <i>None</i> when this state can not be reached anymore
+  savedstate: Option<usize>,
+
+  /// Group identifier of the last created action
+  groupid: usize,
+
+  /// Tool and time of the last grid size action, to group held keys
+  lastgridaction: Option<(tools::GridTool, Instant)>,
+
+  /// Zoom of the current grid in the <i>user interface</i>
+  zoom: zoom::Zoom,
 }
 
 impl Log {
-  pub fn new(map_capacity: usize, queue_capacity: usize) -> Log {
+  pub fn new(map_capacity: usize, queue_capacity: usize, max_grids: usize)
+    -> Log {
     Log {
-      actions: VecDeque::with_capacity(queue_capacity),
+      actions: VecDeque::new(),
       brush: brush::Brush::new(),
       brushcolor: brush::brushcolor::BrushColor::new(),
-      delayedgrids: grids::Grids::new(map_capacity),
+      cursor: cursor::Cursor::new(),
+      delayedgrids: grids::Grids::new(map_capacity, max_grids),
+      exportsettings: exportsettings::ExportSettings::new(),
       generationnumber:
         texturessettings::generationnumber::GenerationNumber::new(),
       gridname: grids::gridname::GridName::new(),
-      grids: grids::Grids::new(map_capacity),
+      gridsize: grids::gridsize::GridSize::new(),
+      grids: grids::Grids::new(map_capacity, max_grids),
+      overlay: overlay::Overlay::new(),
+      pointer: pointer::Pointer::new(),
       texturessettings: texturessettings::TexturesSettings::new(),
       overflow: false,
       queuecapacity: queue_capacity,
-      canceledactions: Vec::with_capacity(queue_capacity),
+      canceledactions: Vec::new(),
+      foldedactions: 0,
+      snapshots: VecDeque::new(),
+      snapshotinterval: max(1, queue_capacity / MAX_SNAPSHOTS),
+      historycursor: 0,
+      savedstate: Some(0),
+      groupid: 0,
+      lastgridaction: None,
+      zoom: zoom::Zoom::new(),
     }
   }
 
+  /// Replaces the built-in defaults of the tools by the ones of the
+  /// configuration file. The grids' history starts from the first action,
+  /// so it is called before.
+  pub fn set_defaults(&mut self, defaults: &Defaults) -> Result<(), Error> {
+    let ratios = Interval { min: 1, max: 99 };
+    if let Some(size) = defaults.grid_size {
+      Interval { min: 1, max: u32::from(grids::size_limit()) }
+        .check(u32::from(size), "grid_size default")?;
+      self.grids.set_size(size, size);
+      self.delayedgrids.set_size(size, size);
+    }
+    if let Some(pixel_ratio) = defaults.pixel_ratio {
+      self.texturessettings.set_pixel_ratio(pixel_ratio)?;
+    }
+    if let Some(ratio) = defaults.body_empty_ratio {
+      ratios.check(u32::from(ratio), "body_empty_ratio default")?;
+      self.brush.set_body_de_ratio(ratio);
+    }
+    if let Some(ratio) = defaults.body_border_ratio {
+      ratios.check(u32::from(ratio), "body_border_ratio default")?;
+      self.brush.set_body_dr_ratio(ratio);
+    }
+    self.grids.set_exclude_hidden(defaults.exclude_hidden_grids);
+    self.delayedgrids.set_exclude_hidden(defaults.exclude_hidden_grids);
+    if let Some(number) = defaults.generation_number {
+      self.texturessettings.set_number(number);
+      self.generationnumber.set_number(number);
+    }
+    Ok(())
+  }
+
   /************************ TOOLS MEMBERS METHODS ***************************/
 
                  /*************** BRUSH ******************/
@@ -147,6 +248,115 @@ impl Log {
     self.brushcolor.set_value(number);
   }
 
+          /******************** CELL CURSOR **********************/
+
+  pub fn cursor_isenabled(&self) -> bool {
+    self.cursor.is_enabled()
+  }
+
+  /// Enables the cursor on the first visible cell, or disables it
+  pub fn cursor_toggle(&mut self) {
+    self.cursor.toggle(self.grids.get_scroll());
+    self.cursor_checkscroll();
+  }
+
+  /// Returns the cursor position in grid's coordinates
+  pub fn cursor_getposition(&self) -> (u16, u16) {
+    self.cursor.get_position(
+      (self.grids.get_grid_width(), self.grids.get_grid_height()))
+  }
+
+  pub fn cursor_up(&mut self) {
+    self.cursor.up((self.grids.get_grid_width(), self.grids.get_grid_height()));
+    self.cursor_checkscroll();
+  }
+
+  pub fn cursor_down(&mut self) {
+    self.cursor.down(
+      (self.grids.get_grid_width(), self.grids.get_grid_height()));
+    self.cursor_checkscroll();
+  }
+
+  pub fn cursor_left(&mut self) {
+    self.cursor.left(
+      (self.grids.get_grid_width(), self.grids.get_grid_height()));
+    self.cursor_checkscroll();
+  }
+
+  pub fn cursor_right(&mut self) {
+    self.cursor.right(
+      (self.grids.get_grid_width(), self.grids.get_grid_height()));
+    self.cursor_checkscroll();
+  }
+
+  /// Scrolls the grid until the cursor is inside the visible area
+  fn cursor_checkscroll(&mut self) {
+    let (x, y) = self.cursor_getposition();
+    let (area_width, area_height) = self.grids.get_area();
+    if (area_width == 0) || (area_height == 0) {
+      return
+    }
+    while x < self.grids.get_scroll().0 {
+      self.gridscroll_scrollleft();
+    }
+    while x >= self.grids.get_scroll().0 + area_width {
+      self.gridscroll_scrollright();
+    }
+    while y < self.grids.get_scroll().1 {
+      self.gridscroll_scrollup();
+    }
+    while y >= self.grids.get_scroll().1 + area_height {
+      self.gridscroll_scrolldown();
+    }
+  }
+
+  /// Applies a cell tool on the cursor's cell as a new checked action
+  fn cursor_apply(&mut self, tool: tools::CellTool) {
+    let mut action = action::Action::new(self.grids.get_current_grid_info(),
+      tools::Tool::CellSetter(tool))
+      .corrected(Some(0), Some(0), &zoom::Zoom::new());
+    action.set_group_id(self.next_group_id());
+    self.clear_canceled_actions();
+    self.apply_cell_action(action);
+  }
+
+  pub fn cursor_brush(&mut self) {
+    let (x, y) = self.cursor_getposition();
+    let pixel = self.brush.get_current_action();
+    self.cursor_apply(tools::CellTool::PixelBrush(pixel, x, y));
+  }
+
+  pub fn cursor_erase(&mut self) {
+    let (x, y) = self.cursor_getposition();
+    self.cursor_apply(tools::CellTool::PixelEraser(x, y));
+  }
+
+  /// Replaces the content of the cursor's cell by the next brush action
+  pub fn cursor_cyclepixel(&mut self) {
+    let (x, y) = self.cursor_getposition();
+    let pixel = self.brush.next_pixel(self.grids.get_cell((x, y)));
+    self.cursor_apply(tools::CellTool::PixelBrush(pixel, x, y));
+  }
+
+          /****************** EXPORT SETTINGS ********************/
+
+  pub fn exportsettings_getformat(&self) -> MaskFormat {
+    self.exportsettings.get_option().1
+  }
+
+  pub fn exportsettings_nextoption(&mut self) {
+    self.exportsettings.next_option();
+  }
+
+  pub fn exportsettings_previousoption(&mut self) {
+    self.exportsettings.previous_option();
+  }
+
+  pub fn exportsettings_getoptioninfo(&self) ->
+    ([(bool, MaskFormat); 4], usize) {
+      self.exportsettings.get_option_info()
+  }
+
           /************ GENERATION NUMBER SETTER ***************/
 
   pub fn generationnumber_getcursor(&self) -> (u16, bool) {
@@ -193,6 +403,10 @@ impl Log {
     self.gridname.get_value()
   }
 
+  pub fn gridname_getmaxlength(&self) -> usize {
+    self.gridname.max_length
+  }
+
   pub fn gridname_setvalue(&mut self, letter: char) {
     self.gridname.set_value(letter);
   }
@@ -205,6 +419,52 @@ impl Log {
     self.gridname.reset();
   }
 
+          /******************* GRID SIZE ***********************/
+
+  /// Returns the cursor's column, after the width and the size separator
+  /// for a digit of the height
+  pub fn gridsize_getcursor(&self) -> (u16, bool) {
+    let (width, _) = self.gridsize.get_value();
+    let pos = self.gridsize.get_pos();
+    let column = if pos < width.len() { pos } else { pos + 1 };
+    (u16::try_from(column).unwrap(), self.gridsize.cursor_is_blinking())
+  }
+
+  pub fn gridsize_cursorleft(&mut self) {
+    self.gridsize.cursor_left();
+  }
+
+  pub fn gridsize_cursorright(&mut self) {
+    self.gridsize.cursor_right();
+  }
+
+  pub fn gridsize_getvalue(&self) -> (String, String) {
+    self.gridsize.get_value()
+  }
+
+  pub fn gridsize_setvalue(&mut self, number: char) {
+    self.gridsize.set_value(number);
+  }
+
+  pub fn gridsize_getanchor(&self) -> &'static str {
+    self.gridsize.get_anchor().get_label()
+  }
+
+  pub fn gridsize_nextanchor(&mut self) {
+    self.gridsize.next_anchor();
+  }
+
+  pub fn gridsize_previousanchor(&mut self) {
+    self.gridsize.previous_anchor();
+  }
+
+  /// Writes the current size of the grids and moves the cursor back
+  pub fn gridsize_reset(&mut self) {
+    self.gridsize.set_size(
+      (self.grids.get_grid_width(), self.grids.get_grid_height()));
+    self.gridsize.reset_cursor();
+  }
+
           /************** GRIDS & GRID SCROLL ******************/
 
   pub fn grids_getwidth<T>(&self) -> T
@@ -219,10 +479,18 @@ impl Log {
     T::try_from(self.grids.get_grid_height()).unwrap()
   }
 
+  pub fn grids_getsizelimit(&self) -> u16 {
+    grids::size_limit()
+  }
+
   pub fn grids_getlengthcapacity(&self) -> (usize, usize) {
     self.grids.get_length_capacity_state()
   }
 
+  pub fn grids_getcell(&self, position: (u16, u16)) -> Option<FullPixel> {
+    self.grids.get_cell(position)
+  }
+
   pub fn grids_getcurrentgrid(&self) -> Vec<(FullPixel, u16, u16)> {
     self.grids.get_current_grid()
   }
@@ -235,10 +503,42 @@ impl Log {
     ).collect()
   }
 
+  pub fn grids_getvisiblegrids<T>(&self)
+    -> HashMap<(T, T), (usize, FullPixel)>
+    where T: PrimInt + std::convert::From<u16> + std::hash::Hash,
+  {
+    self.grids.get_visible_grids().iter().map(|(key, &value)|
+      ((T::try_from(key.0).unwrap(), T::try_from(key.1).unwrap()), value)
+    ).collect()
+  }
+
   pub fn grids_getnames(&self) -> Vec<String> {
     self.grids.get_names()
   }
 
+  pub fn grids_gethidden(&self) -> Vec<bool> {
+    self.grids.get_hidden()
+  }
+
+  pub fn grids_getlocked(&self) -> Vec<bool> {
+    self.grids.get_locked()
+  }
+
+  /// Hides or shows the current grid, as an action which can be undone
+  pub fn grids_togglevisibility(&mut self) {
+    let action = action::Action::new(self.grids.get_current_grid_info(),
+      tools::Tool::WorkspaceSetter(tools::WorkspaceTool::ToggleVisibility));
+    self.push_action(action);
+  }
+
+  /// Locks or unlocks the cells of the current grid, as an action which can
+  /// be undone
+  pub fn grids_togglelock(&mut self) {
+    let action = action::Action::new(self.grids.get_current_grid_info(),
+      tools::Tool::WorkspaceSetter(tools::WorkspaceTool::ToggleLock));
+    self.push_action(action);
+  }
+
   pub fn grids_getcurrentgridid(&self) -> usize {
     self.grids.get_current_grid_info().1
   }
@@ -248,38 +548,44 @@ impl Log {
   }
 
   pub fn grids_add(&mut self) {
-    self.check_queue_size();
     if let Some(name) = self.grids.check_name(self.gridname.get_value()) {
       let action = action::Action::new(self.grids.get_current_grid_info(),
         tools::Tool::WorkspaceSetter(tools::WorkspaceTool::AddGrid(name)));
-      self.actions.push_back(action);
-      self.canceledactions.clear();
+      self.push_action(action);
     }
   }
 
   pub fn grids_renamecurrentgrid(&mut self) {
-    self.check_queue_size();
     if let Some(name) = self.grids.check_name(self.gridname.get_value()) {
       let action = action::Action::new(self.grids.get_current_grid_info(),
         tools::Tool::WorkspaceSetter(tools::WorkspaceTool::RenameGrid(name)));
-      self.actions.push_back(action);
-      self.canceledactions.clear();
+      self.push_action(action);
     }
   }
 
   pub fn grids_deletecurrentgrid(&mut self) {
-    self.check_queue_size();
     let action = action::Action::new(self.grids.get_current_grid_info(),
       tools::Tool::WorkspaceSetter(tools::WorkspaceTool::DeleteGrid));
-    self.actions.push_back(action);
-    self.canceledactions.clear();
+    self.push_action(action);
+  }
+
+  /// Replaces the initial grids by imported templates
+  pub fn grids_import(&mut self, templates: Vec<Template>)
+    -> Result<(), Error> {
+      self.grids.import(templates.clone())?;
+      self.delayedgrids.import(templates)
+  }
+
+  /// Returns the grids selected in the export settings as templates
+  pub fn grids_gettemplates(&self) -> Vec<Template> {
+    self.grids.get_templates(self.exportsettings.get_option().0)
   }
 
   pub fn grids_erroroccured(&self) -> bool {
     self.grids.error_occured()
   }
 
-  pub fn grids_geterror(&self) -> String {
+  pub fn grids_geterror(&self) -> Option<&Error> {
     self.grids.get_error()
   }
 
@@ -304,12 +610,18 @@ impl Log {
   }
 
   pub fn grids_switchorder(&mut self) {
-    self.check_queue_size();
     let action = action::Action::new(self.grids.get_current_grid_info(),
       tools::Tool::WorkspaceSetter(
       tools::WorkspaceTool::SwitchGrid(self.grids.get_switch_cursor())));
-    self.actions.push_back(action);
-    self.canceledactions.clear();
+    self.push_action(action);
+  }
+
+  pub fn grids_gettabsscroll(&self) -> usize {
+    self.grids.get_tabs_scroll()
+  }
+
+  pub fn grids_checktabsscroll(&mut self, nb_visible_tabs: usize) {
+    self.grids.check_tabs_scroll(nb_visible_tabs);
   }
 
   pub fn grids_next(&mut self) {
@@ -344,6 +656,147 @@ impl Log {
     self.grids.scroll_left();
   }
 
+          /**************************** OVERLAY *****************************/
+
+  pub fn overlay_isonionskin(&self) -> bool {
+    self.overlay.is_onionskin()
+  }
+
+  pub fn overlay_iscomposite(&self) -> bool {
+    self.overlay.is_composite()
+  }
+
+  pub fn overlay_toggleonionskin(&mut self) {
+    self.overlay.toggle_onionskin();
+  }
+
+  pub fn overlay_togglecomposite(&mut self) {
+    self.overlay.toggle_composite();
+  }
+
+          /**************************** POINTER *****************************/
+
+  pub fn pointer_setposition(&mut self, position: (u16, u16)) {
+    self.pointer.set_position(position);
+  }
+
+  pub fn pointer_setarea(&mut self, area: (u16, u16, u16, u16)) {
+    self.pointer.set_area(area);
+  }
+
+  pub fn pointer_setbrushes(&mut self, area: (u16, u16, u16, u16),
+    nb: usize) {
+      self.pointer.set_brushes(area, nb);
+  }
+
+  pub fn pointer_settabs(&mut self, area: (u16, u16, u16, u16),
+    first_tab: usize) {
+      self.pointer.set_tabs(area, first_tab);
+  }
+
+  pub fn pointer_setscroller(&mut self, axis: pointer::Axis,
+    scroller: Option<pointer::Scroller>) {
+      self.pointer.set_scroller(axis, scroller);
+  }
+
+  pub fn pointer_setcorner(&mut self, corner: (u16, u16)) {
+    self.pointer.set_corner(corner);
+  }
+
+  /// Selects the brush or the grid tab under a click, or grabs the thumb of
+  /// a grid scroller or the corner of the grid. Returns <i>false</i> if
+  /// nothing is under the click.
+  pub fn pointer_press(&mut self, position: (u16, u16)) -> bool {
+    let size = (self.grids.get_grid_width(), self.grids.get_grid_height());
+    match self.pointer.press(position, (self.grids.get_scroll(), size)) {
+      Some(pointer::Target::Brush(index)) => self.brush.select(index),
+      Some(pointer::Target::Tab(id)) => self.grids.select(id),
+      Some(pointer::Target::Scroller(_)) | Some(pointer::Target::Corner) => {},
+      None => return false,
+    };
+    true
+  }
+
+  /// Scrolls the grid when a grabbed scroller thumb is dragged, or previews
+  /// its size when its grabbed corner is dragged. Returns <i>false</i> if
+  /// nothing is grabbed.
+  pub fn pointer_drag(&mut self, position: (u16, u16)) -> bool {
+    match self.pointer.drag(position) {
+      Some(pointer::Drag::Scroll(scroll)) => self.grids.set_scroll(scroll),
+      Some(pointer::Drag::Resize((width, height), (moved_x, moved_y))) => {
+        let (cells_x, cells_y) = self.zoom.to_cells((
+          u16::try_from(moved_x.unsigned_abs()).unwrap_or(u16::MAX),
+          u16::try_from(moved_y.unsigned_abs()).unwrap_or(u16::MAX)));
+        let resized = |size: u16, cells: u16, moved: i32| if moved < 0 {
+          size.saturating_sub(cells)
+        } else {
+          size.saturating_add(cells)
+        }.clamp(1, grids::size_limit());
+        self.pointer.set_preview((resized(width, cells_x, moved_x),
+          resized(height, cells_y, moved_y)));
+      },
+      None => {},
+    };
+    self.pointer.is_grabbed()
+  }
+
+  /// Releases the grabbed target. A dragged corner resizes the grids to the
+  /// previewed size, so the cells outside of a size passed by the drag are
+  /// kept.
+  pub fn pointer_release(&mut self, position: (u16, u16)) {
+    let size = (self.grids.get_grid_width(), self.grids.get_grid_height());
+    if let Some((width, height)) = self.pointer.release(position)
+      .filter(|&preview| preview != size) {
+        let action = action::Action::new(self.grids.get_current_grid_info(),
+          tools::Tool::GridSetter(tools::GridTool::Resize(width, height,
+            tools::Anchor::TopLeft)));
+        self.push_action(action);
+    }
+  }
+
+  /// Returns the size of the grids drawn in the <i>user interface</i>,
+  /// which is previewed while their corner is dragged
+  pub fn pointer_getgridsize(&self) -> (u16, u16) {
+    self.pointer.get_preview().unwrap_or(
+      (self.grids.get_grid_width(), self.grids.get_grid_height()))
+  }
+
+  /// Returns the cell of the current grid under the last click, drag or
+  /// wheel move, if any
+  pub fn pointer_getcell(&self) -> Option<(u16, u16)> {
+    self.pointer.get_position().map(|position| self.zoom.get_cell(
+      self.zoom.get_position(position, self.grids.get_scroll()),
+      self.pointer.get_origin()))
+      .filter(|&(x, y)| (x < self.grids.get_grid_width()) &&
+        (y < self.grids.get_grid_height()))
+  }
+
+          /****************************** ZOOM ******************************/
+
+  pub fn zoom_in(&mut self) {
+    self.zoom.zoom_in();
+  }
+
+  pub fn zoom_out(&mut self) {
+    self.zoom.zoom_out();
+  }
+
+  pub fn zoom_getscale(&self) -> ((u16, u16), (u16, u16)) {
+    self.zoom.get_scale()
+  }
+
+  pub fn zoom_ishalfblock(&self) -> bool {
+    self.zoom.is_halfblock()
+  }
+
+  pub fn zoom_tocharacters(&self, size: (u16, u16)) -> (u16, u16) {
+    self.zoom.to_characters(size)
+  }
+
+  pub fn zoom_tocells(&self, size: (u16, u16)) -> (u16, u16) {
+    self.zoom.to_cells(size)
+  }
+
           /************************ IMAGE SETTINGS **************************/
 
   pub fn texturessettings_getpixelratio<T>(&self) -> T
@@ -368,13 +821,13 @@ impl Log {
     self.texturessettings.get_palette()
   }
 
-  pub fn texturessettings_getgenerationnumber(&self) -> u16 {
+  pub fn texturessettings_getgenerationnumber(&self) -> u32 {
     self.texturessettings.get_number()
   }
 
   pub fn texturessettings_setgenerationnumber(&mut self) {
     let number = self.generationnumber.get_value();
-    self.texturessettings.set_number(number.parse::<u16>().unwrap());
+    self.texturessettings.set_number(number.parse::<u32>().unwrap());
   }
 
   pub fn texturessettings_getformat(&self) -> image::ImageFormat {
@@ -396,9 +849,127 @@ impl Log {
 
           /********************* self.actions METHODS ***********************/
 
+  /// Returns the group identifier of a new group of actions
+  fn next_group_id(&mut self) -> usize {
+    self.groupid += 1;
+    self.lastgridaction = None;
+    self.groupid
+  }
+
+  /// Clears the canceled actions before a new action, the saved state is
+  /// lost if it was one of them
+  fn clear_canceled_actions(&mut self) {
+    let applied = self.foldedactions + self.actions.len();
+    if self.savedstate.is_some_and(|saved| saved > applied) {
+      self.savedstate = None;
+    }
+    self.canceledactions.clear();
+  }
+
+  /// Pushes a new unchecked action which starts a new group
+  fn push_action(&mut self, mut action: action::Action) {
+    action.set_group_id(self.next_group_id());
+    self.actions.push_back(action);
+    self.clear_canceled_actions();
+  }
+
+  /// Pushes a new unchecked action which continues the group of the last
+  /// created action
+  fn push_grouped_action(&mut self, mut action: action::Action) {
+    action.set_group_id(self.groupid);
+    self.actions.push_back(action);
+    self.clear_canceled_actions();
+  }
+
+  /// Pushes a new unchecked grid size action, grouped with the last one if
+  /// the same tool has been used less than <i>KEY_REPEAT_DELAY</i> ago
+  fn push_grid_action(&mut self, tool: tools::GridTool) {
+    let action = action::Action::new(self.grids.get_current_grid_info(),
+      tools::Tool::GridSetter(tool));
+    let now = Instant::now();
+    match self.lastgridaction {
+      Some((last_tool, time))
+        if (last_tool == tool) && (now - time < KEY_REPEAT_DELAY) =>
+          self.push_grouped_action(action),
+      _ => self.push_action(action),
+    };
+    self.lastgridaction = Some((tool, now));
+  }
+
+  /// Applies each tool of <i>action</i> to <i>grids</i>
+  fn apply(grids: &mut grids::Grids, action: &action::Action) {
+    for action in action.split() {
+      grids.update(&action);
+    }
+  }
+
   fn check_queue_size(&mut self) {
     if self.actions.len() >= self.queuecapacity {
-      self.delayedgrids.update(&self.actions.pop_front().unwrap());
+      Log::apply(&mut self.delayedgrids, &self.actions.pop_front().unwrap());
+      self.foldedactions += 1;
+      while let Some((applied, _)) = self.snapshots.front() {
+        if *applied <= self.foldedactions {
+          self.snapshots.pop_front();
+        } else {
+          break;
+        }
+      }
+    }
+  }
+
+  /// Adds an action already applied to <i>grids</i> to the group of the last
+  /// saved action if possible, or saves it
+  fn push_checked_action(&mut self, action: action::Action) {
+    let applied = self.foldedactions + self.actions.len();
+    if let Some(last) = self.actions.back_mut() {
+      if last.can_group(&action) {
+        if let Some((snapshot_applied, snapshot)) = self.snapshots.back_mut() {
+          if *snapshot_applied == applied {
+            snapshot.update(&action);
+          }
+        }
+        last.group(action);
+        return;
+      }
+    }
+    self.save_action(action);
+  }
+
+  /// Saves an action already applied to <i>grids</i> and saves the state of
+  /// <i>grids</i> if the number of applied actions is a multiple of
+  /// <i>snapshotinterval</i>
+  fn save_action(&mut self, action: action::Action) {
+    self.check_queue_size();
+    self.actions.push_back(action);
+    let applied = self.foldedactions + self.actions.len();
+    if applied % self.snapshotinterval == 0 {
+      self.snapshots.push_back((applied, self.grids.clone()));
+    }
+  }
+
+  /// Restores <i>grids</i> from the nearest snapshot which does not contain
+  /// canceled actions and replays the following actions
+  fn rebuild_grids(&mut self) {
+    let applied = self.foldedactions + self.actions.len();
+    while let Some((snapshot_applied, _)) = self.snapshots.back() {
+      if *snapshot_applied > applied {
+        self.snapshots.pop_back();
+      } else {
+        break;
+      }
+    }
+    let replay_from = match self.snapshots.back() {
+      Some((snapshot_applied, snapshot)) => {
+        self.grids.clone_from(snapshot);
+        snapshot_applied - self.foldedactions
+      },
+      None => {
+        self.grids.clone_from(&self.delayedgrids);
+        0
+      },
+    };
+    for action in self.actions.iter().skip(replay_from) {
+      Log::apply(&mut self.grids, action);
     }
   }
 
@@ -408,171 +979,283 @@ impl Log {
     res
   }
 
-  pub fn brush(&mut self, (x, y): (u16, u16)) {
-    self.check_queue_size();
-    let (scroll_x, scroll_y) = self.grids.get_scroll();
+  /// The position of a mouse action depends on the zoom and the scroll of
+  /// the grid, it is mapped back to a cell by <i>check_last_action</i>
+  fn brush_action(&self, position: (u16, u16)) -> action::Action {
+    let (x, y) = self.zoom.get_position(position, self.grids.get_scroll());
     let new_value = self.brush.get_current_action();
-    let action = action::Action::new(self.grids.get_current_grid_info(),
-      tools::Tool::CellSetter(tools::CellTool::PixelBrush(
-        new_value, x + scroll_x, y + scroll_y)));
-    self.actions.push_back(action);
-    self.canceledactions.clear();
+    action::Action::new(self.grids.get_current_grid_info(),
+      tools::Tool::CellSetter(tools::CellTool::PixelBrush(new_value, x, y)))
   }
 
-  pub fn erase(&mut self, (x, y): (u16, u16)) {
-    self.check_queue_size();
-    let (scroll_x, scroll_y) = self.grids.get_scroll();
-    let action = action::Action::new(self.grids.get_current_grid_info(),
-      tools::Tool::CellSetter(
-        tools::CellTool::PixelEraser(x + scroll_x, y + scroll_y)));
-    self.actions.push_back(action);
-    self.canceledactions.clear();
+  fn erase_action(&self, position: (u16, u16)) -> action::Action {
+    let (x, y) = self.zoom.get_position(position, self.grids.get_scroll());
+    action::Action::new(self.grids.get_current_grid_info(),
+      tools::Tool::CellSetter(tools::CellTool::PixelEraser(x, y)))
+  }
+
+  /// Brushes the cell under a click. A character draws two rows of cells
+  /// with half blocks, so the mouse does not paint at this zoom, and the
+  /// cursor mode is used instead.
+  pub fn brush(&mut self, position: (u16, u16)) {
+    if self.zoom.is_halfblock() {
+      return
+    }
+    let action = self.brush_action(position);
+    self.push_action(action);
+  }
+
+  /// Brushes a cell in the same group as the last action, for mouse strokes
+  pub fn drag_brush(&mut self, position: (u16, u16)) {
+    if self.zoom.is_halfblock() {
+      return
+    }
+    let action = self.brush_action(position);
+    self.push_grouped_action(action);
+  }
+
+  /// Erases the cell under a click, except at the half block zoom
+  pub fn erase(&mut self, position: (u16, u16)) {
+    if self.zoom.is_halfblock() {
+      return
+    }
+    let action = self.erase_action(position);
+    self.push_action(action);
+  }
+
+  /// Erases a cell in the same group as the last action, for mouse strokes
+  pub fn drag_erase(&mut self, position: (u16, u16)) {
+    if self.zoom.is_halfblock() {
+      return
+    }
+    let action = self.erase_action(position);
+    self.push_grouped_action(action);
   }
 
   pub fn clear(&mut self) {
-    self.check_queue_size();
     let action = action::Action::new(self.grids.get_current_grid_info(),
       tools::Tool::GridSetter(tools::GridTool::ClearGrid));
-    self.actions.push_back(action);
-    self.canceledactions.clear();
+    self.push_action(action);
   }
 
   pub fn increment_width(&mut self) {
-    self.check_queue_size();
-    let action = action::Action::new(self.grids.get_current_grid_info(),
-      tools::Tool::GridSetter(tools::GridTool::WidthIncrementor));
-    self.actions.push_back(action);
-    self.canceledactions.clear();
+    self.push_grid_action(tools::GridTool::WidthIncrementor);
   }
 
   pub fn decrement_width(&mut self) {
-    self.check_queue_size();
-    let action = action::Action::new(self.grids.get_current_grid_info(),
-      tools::Tool::GridSetter(tools::GridTool::WidthDecrementor));
-    self.actions.push_back(action);
-    self.canceledactions.clear();
+    self.push_grid_action(tools::GridTool::WidthDecrementor);
   }
 
   pub fn increment_height(&mut self) {
-    self.check_queue_size();
-    let action = action::Action::new(self.grids.get_current_grid_info(),
-      tools::Tool::GridSetter(tools::GridTool::HeightIncrementor));
-    self.actions.push_back(action);
-    self.canceledactions.clear();
+    self.push_grid_action(tools::GridTool::HeightIncrementor);
   }
 
   pub fn decrement_height(&mut self) {
-    self.check_queue_size();
+    self.push_grid_action(tools::GridTool::HeightDecrementor);
+  }
+
+  /// Resizes the grids to the size and the anchor of the resize popup
+  pub fn resize(&mut self) {
+    let (width, height) = self.gridsize.get_size();
     let action = action::Action::new(self.grids.get_current_grid_info(),
-      tools::Tool::GridSetter(tools::GridTool::HeightDecrementor));
-    self.actions.push_back(action);
-    self.canceledactions.clear();
+      tools::Tool::GridSetter(tools::GridTool::Resize(width, height,
+        self.gridsize.get_anchor())));
+    self.push_action(action);
   }
 
   pub fn undo(&mut self) {
     if !self.actions.is_empty() {
-      self.grids.clone_from(&self.delayedgrids);
       self.canceledactions.push(self.actions.pop_back().unwrap());
-      for action in self.actions.iter() {
-        self.grids.update(&action);
-      }
+      self.rebuild_grids();
     }
   }
 
   pub fn redo(&mut self) {
-    if !self.canceledactions.is_empty() {
-      self.actions.push_back(self.canceledactions.pop().unwrap());
-      self.grids.update(self.actions.back().unwrap());
+    if let Some(action) = self.canceledactions.pop() {
+      Log::apply(&mut self.grids, &action);
+      self.save_action(action);
     }
   }
 
+          /************************** HISTORY *******************************/
+
+  /// Returns the labels of the saved states: the oldest saved state, then
+  /// one state after each applied or canceled action
+  pub fn history_getentries(&self) -> Vec<String> {
+    let mut entries = vec![String::from("Oldest saved state")];
+    entries.extend(self.actions.iter()
+      .chain(self.canceledactions.iter().rev())
+      .map(|action| action.get_label()));
+    entries
+  }
+
+  /// Returns the index of the current state in the history entries
+  pub fn history_getposition(&self) -> usize {
+    self.actions.len()
+  }
+
+  /// Returns <i>true</i> if the grids are in the state of their last export
+  pub fn history_issaved(&self) -> bool {
+    self.savedstate == Some(self.foldedactions + self.actions.len())
+  }
+
+  /// Marks the current state as saved once every grid is exported. The next
+  /// actions start a new group, so they are not grouped with a saved one.
+  pub fn history_setsaved(&mut self) {
+    self.savedstate = Some(self.foldedactions + self.actions.len());
+    self.next_group_id();
+  }
+
+  pub fn history_getcursor(&self) -> usize {
+    self.historycursor
+  }
+
+  pub fn history_resetcursor(&mut self) {
+    self.historycursor = self.history_getposition();
+  }
+
+  pub fn history_nextcursor(&mut self) {
+    self.historycursor = min(self.historycursor + 1,
+      self.actions.len() + self.canceledactions.len());
+  }
+
+  pub fn history_previouscursor(&mut self) {
+    if self.historycursor > 0 {
+      self.historycursor -= 1;
+    }
+  }
+
+  /// Cancels or redoes actions until the state selected by the history
+  /// cursor is reached
+  pub fn history_goto(&mut self) {
+    let position = self.history_getposition();
+    if self.historycursor < position {
+      for _ in self.historycursor..position {
+        self.canceledactions.push(self.actions.pop_back().unwrap());
+      }
+      self.rebuild_grids();
+    } else {
+      for _ in position..self.historycursor {
+        self.redo();
+      }
+    }
+  }
+
+  /// Applies a corrected cell action to <i>grids</i> and saves it if it
+  /// modified a cell. Cells of a locked grid are not modified.
+  fn apply_cell_action(&mut self, action: action::Action) {
+    if self.grids.is_locked(action.get_grid_id()) {
+      return
+    }
+    match action.get_tool() {
+      tools::Tool::CellSetter(tools::CellTool::PixelBrush(pixel, _, _)) => {
+        match self.grids.update(&action) {
+          (grids::Change::Set(Some(old_value)), _) => {
+            if old_value != pixel {
+              self.push_checked_action(action);
+            }
+            self.overflow = false;
+          },
+          (grids::Change::Set(None), overflow) => {
+            self.push_checked_action(action);
+            self.overflow = overflow;
+          },
+          (grids::Change::Outside, _) => {},
+        };
+      },
+      tools::Tool::CellSetter(tools::CellTool::PixelEraser(_, _)) => {
+        if let (grids::Change::Set(Some(_)), _) = self.grids.update(&action) {
+          self.push_checked_action(action);
+        }
+        self.overflow = false;
+      },
+      _ => {},
+    }
+  }
+
+  /// Applies the last action if it is not checked yet. A cell action is
+  /// only applied inside the grid area <i>(left, right, top, bottom)</i> of
+  /// the terminal.
   pub fn check_last_action(&mut self,
     (left, right, top, bottom): (u16, u16, u16, u16)) {
+      let (area_left, area_top) =
+        self.zoom.get_position((left, top), self.grids.get_scroll());
+      let (area_right, area_bottom) =
+        self.zoom.get_position((right, bottom), self.grids.get_scroll());
       if let Some(last) = self.actions.pop_back() {
         if !last.is_checked() {
           match last.get_tool() {
             tools::Tool::CellSetter(tool) => {
               match tool {
-                tools::CellTool::PixelBrush(pixel, x, y) => {
-                  if (x >= left) && (x < right) &&
-                    (y >= top) && (y < bottom) {
-                      let corrected_last_action =
-                        last.corrected(Some(left), Some(top));
-                      match self.grids.update(&corrected_last_action) {
-                        (Some(old_value), _) => {
-                          if old_value != pixel {
-                            self.actions.push_back(corrected_last_action);
-                          }
-                          self.overflow = false;
-                        },
-                        (None, overflow) => {
-                          if !overflow {
-                            self.actions.push_back(corrected_last_action);
-                          }
-                          self.overflow = overflow;
-                        },
-                      };
-                  }
-                },
+                tools::CellTool::PixelBrush(_, x, y) |
                 tools::CellTool::PixelEraser(x, y) => {
-                  if (x >= left) && (x < right) && (y >= top) && (y < bottom) {
-                    let corrected_last_action =
-                      last.corrected(Some(left), Some(top));
-                    match self.grids.update(&corrected_last_action) {
-                      (Some(_), _) =>
-                        self.actions.push_back(corrected_last_action),
-                      (None, _) => {},
-                    };
-                    self.overflow = false;
+                  if (x >= area_left) && (x < area_right) &&
+                    (y >= area_top) && (y < area_bottom) {
+                      self.apply_cell_action(last.corrected(Some(left),
+                        Some(top), &self.zoom));
                   }
                 },
               };
             },
             tools::Tool::GridSetter(tool) => {
-              let corrected_last_action = last.corrected(None, None);
+              let corrected_last_action =
+                last.corrected(None, None, &self.zoom);
               match tool {
                 tools::GridTool::ClearGrid => {
-                  if self.grids.get_nb_filledcells_current_grid() > 0 {
+                  if (self.grids.get_nb_filledcells_current_grid() > 0) &&
+                    !self.grids.is_locked(last.get_grid_id()) {
                     self.grids.update(&corrected_last_action);
-                    self.actions.push_back(corrected_last_action);
+                    self.push_checked_action(corrected_last_action);
                   }
                 },
                 tools::GridTool::WidthIncrementor => {
                   if self.grids.get_grid_width() < grids::size_limit() {
                     self.grids.update(&corrected_last_action);
-                    self.actions.push_back(corrected_last_action);
+                    self.push_checked_action(corrected_last_action);
                   }
                 },
                 tools::GridTool::WidthDecrementor => {
                   if self.grids.get_grid_width() > 1 {
                     self.grids.update(&corrected_last_action);
-                    self.actions.push_back(corrected_last_action);
+                    self.push_checked_action(corrected_last_action);
                   }
                 },
                 tools::GridTool::HeightIncrementor => {
                   if self.grids.get_grid_height() < grids::size_limit() {
                     self.grids.update(&corrected_last_action);
-                    self.actions.push_back(corrected_last_action);
+                    self.push_checked_action(corrected_last_action);
                   }
                 },
                 tools::GridTool::HeightDecrementor => {
                   if self.grids.get_grid_height() > 1 {
                     self.grids.update(&corrected_last_action);
-                    self.actions.push_back(corrected_last_action);
+                    self.push_checked_action(corrected_last_action);
+                  }
+                },
+                tools::GridTool::Resize(width, height, _) => {
+                  let size = (width.clamp(1, grids::size_limit()),
+                    height.clamp(1, grids::size_limit()));
+                  if size != (self.grids.get_grid_width(),
+                    self.grids.get_grid_height()) {
+                      self.grids.update(&corrected_last_action);
+                      self.push_checked_action(corrected_last_action);
                   }
                 },
               };
               self.overflow = false;
             },
             tools::Tool::WorkspaceSetter(tool) => {
-              let corrected_last_action = last.corrected(None, None);
+              let corrected_last_action =
+                last.corrected(None, None, &self.zoom);
               match tool {
                 tools::WorkspaceTool::AddGrid(_) |
                 tools::WorkspaceTool::RenameGrid(_) |
                 tools::WorkspaceTool::DeleteGrid |
-                tools::WorkspaceTool::SwitchGrid(_) => {
+                tools::WorkspaceTool::SwitchGrid(_) |
+                tools::WorkspaceTool::ToggleVisibility |
+                tools::WorkspaceTool::ToggleLock => {
                   self.grids.update(&corrected_last_action);
-                  self.actions.push_back(corrected_last_action);
+                  self.push_checked_action(corrected_last_action);
                 },
               };
               self.overflow = false;
@@ -592,7 +1275,7 @@ pub mod tests {
 
   #[test]
   fn it_adds_a_brush_action() {
-    let mut log = Log::new(1, 1);
+    let mut log = Log::new(1, 1, 10);
     let len = log.actions.len();
     log.brush((1, 1));
     assert_eq!(log.actions.len(), len + 1);
@@ -600,7 +1283,7 @@ pub mod tests {
 
   #[test]
   fn it_adds_an_erase_action() {
-    let mut log = Log::new(1, 1);
+    let mut log = Log::new(1, 1, 10);
     let len = log.actions.len();
     log.erase((1, 1));
     assert_eq!(log.actions.len(), len + 1);
@@ -608,7 +1291,7 @@ pub mod tests {
 
   #[test]
   fn it_adds_a_clear_action() {
-    let mut log = Log::new(1, 2);
+    let mut log = Log::new(1, 2, 10);
     log.brush((1, 1));
     let len = log.actions.len();
     log.clear();
@@ -617,7 +1300,7 @@ pub mod tests {
 
   #[test]
   fn it_adds_an_increment_width_action() {
-    let mut log = Log::new(0, 1);
+    let mut log = Log::new(0, 1, 10);
     let init_len = log.actions.len();
     log.increment_width();
     assert!(log.actions.len() == init_len + 1);
@@ -625,7 +1308,7 @@ pub mod tests {
 
   #[test]
   fn it_adds_an_increment_height_action() {
-    let mut log = Log::new(0, 1);
+    let mut log = Log::new(0, 1, 10);
     let init_len = log.actions.len();
     log.increment_height();
     assert!(log.actions.len() == init_len + 1);
@@ -633,7 +1316,7 @@ pub mod tests {
 
   #[test]
   fn it_adds_a_decrement_width_action() {
-    let mut log = Log::new(0, 1);
+    let mut log = Log::new(0, 1, 10);
     let init_len = log.actions.len();
     log.decrement_width();
     assert!(log.actions.len() == init_len + 1);
@@ -641,7 +1324,7 @@ pub mod tests {
 
   #[test]
   fn it_adds_a_decrement_height_action() {
-    let mut log = Log::new(0, 1);
+    let mut log = Log::new(0, 1, 10);
     let init_len = log.actions.len();
     log.decrement_height();
     assert!(log.actions.len() == init_len + 1);
@@ -649,7 +1332,7 @@ pub mod tests {
 
   #[test]
   fn it_adds_an_addgrid_action() {
-    let mut log = Log::new(0, 1);
+    let mut log = Log::new(0, 1, 10);
     let init_len = log.actions.len();
     log.gridname_setvalue('0');
     log.grids_add();
@@ -658,7 +1341,7 @@ pub mod tests {
 
   #[test]
   fn it_adds_a_renamegrid_action() {
-    let mut log = Log::new(0, 1);
+    let mut log = Log::new(0, 1, 10);
     let init_len = log.actions.len();
     log.gridname_setvalue('0');
     log.grids_renamecurrentgrid();
@@ -667,7 +1350,7 @@ pub mod tests {
 
   #[test]
   fn it_adds_a_deletegrid_action() {
-    let mut log = Log::new(0, 1);
+    let mut log = Log::new(0, 1, 10);
     let init_len = log.actions.len();
     log.grids_deletecurrentgrid();
     assert!(log.actions.len() == init_len + 1);
@@ -675,7 +1358,7 @@ pub mod tests {
 
   #[test]
   fn it_adds_a_switchgrid_action() {
-    let mut log = Log::new(0, 1);
+    let mut log = Log::new(0, 1, 10);
     let init_len = log.actions.len();
     log.grids_switchorder();
     assert!(log.actions.len() == init_len + 1);
@@ -683,7 +1366,7 @@ pub mod tests {
 
   #[test]
   fn it_does_not_add_an_addgrid_action_with_an_empty_grid_name() {
-    let mut log = Log::new(0, 1);
+    let mut log = Log::new(0, 1, 10);
     let init_len = log.actions.len();
     log.grids_add();
     assert!(log.actions.len() == init_len);
@@ -691,7 +1374,7 @@ pub mod tests {
 
   #[test]
   fn it_does_not_add_an_addgrid_action_with_an_used_grid_name() {
-    let mut log = Log::new(0, 2);
+    let mut log = Log::new(0, 2, 10);
     let grid_area = (0, log.grids_getwidth(), 0, log.grids_getheight());
     log.gridname_setvalue('0');
     log.grids_add();
@@ -705,7 +1388,7 @@ pub mod tests {
 
   #[test]
   fn it_brushes_the_correct_grid_after_an_addgrid_action() {
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let grid_area = (0, log.grids_getwidth(), 0, log.grids_getheight());
     log.gridname_setvalue('0');
     log.grids_add();
@@ -727,7 +1410,7 @@ pub mod tests {
 
   #[test]
   fn it_erases_the_correct_grid_after_an_addgrid_action() {
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let grid_area = (0, log.grids_getwidth(), 0, log.grids_getheight());
     log.gridname_setvalue('0');
     log.grids_add();
@@ -780,7 +1463,7 @@ pub mod tests {
 
   #[test]
   fn it_clears_the_correct_grid_after_an_addgrid_action() {
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let grid_area = (0, log.grids_getwidth(), 0, log.grids_getheight());
     log.gridname_setvalue('0');
     log.brush((1, 1));
@@ -821,14 +1504,14 @@ pub mod tests {
 
   #[test]
   fn it_renames_the_correct_grids_after_an_addgrid_action() {
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let grid_area = (0, log.grids_getwidth(), 0, log.grids_getheight());
     log.gridname_setvalue('0');
     log.grids_add();
     log.check_last_action(grid_area);
     let check1 =
-      log.grids_getnames()[0].eq(&String::from("Grid1           ")) &&
-      log.grids_getnames()[1].eq(&String::from("0               ")) &&
+      log.grids_getnames()[0].eq(&String::from("Grid1")) &&
+      log.grids_getnames()[1].eq(&String::from("0")) &&
       log.grids_getnb() == 2;
 
     log.grids_previous();
@@ -842,8 +1525,8 @@ pub mod tests {
     log.grids_renamecurrentgrid();
     log.check_last_action(grid_area);
     let check2 =
-      log.grids_getnames()[0].eq(&String::from("blibli          ")) &&
-      log.grids_getnames()[1].eq(&String::from("0               ")) &&
+      log.grids_getnames()[0].eq(&String::from("blibli")) &&
+      log.grids_getnames()[1].eq(&String::from("0")) &&
       log.grids_getnb() == 2;
 
     log.grids_next();
@@ -857,32 +1540,32 @@ pub mod tests {
     log.grids_renamecurrentgrid();
     log.check_last_action(grid_area);
     let check3 =
-      log.grids_getnames()[0].eq(&String::from("blibli          ")) &&
-      log.grids_getnames()[1].eq(&String::from("tlitli          ")) &&
+      log.grids_getnames()[0].eq(&String::from("blibli")) &&
+      log.grids_getnames()[1].eq(&String::from("tlitli")) &&
       log.grids_getnb() == 2;
 
     log.undo();
     let check4 =
-      log.grids_getnames()[0].eq(&String::from("blibli          ")) &&
-      log.grids_getnames()[1].eq(&String::from("0               ")) &&
+      log.grids_getnames()[0].eq(&String::from("blibli")) &&
+      log.grids_getnames()[1].eq(&String::from("0")) &&
       log.grids_getnb() == 2;
 
     log.redo();
     let check5 =
-      log.grids_getnames()[0].eq(&String::from("blibli          ")) &&
-      log.grids_getnames()[1].eq(&String::from("tlitli          ")) &&
+      log.grids_getnames()[0].eq(&String::from("blibli")) &&
+      log.grids_getnames()[1].eq(&String::from("tlitli")) &&
       log.grids_getnb() == 2;
 
     log.undo();
     log.undo();
     let check6 =
-      log.grids_getnames()[0].eq(&String::from("Grid1           ")) &&
-      log.grids_getnames()[1].eq(&String::from("0               ")) &&
+      log.grids_getnames()[0].eq(&String::from("Grid1")) &&
+      log.grids_getnames()[1].eq(&String::from("0")) &&
       log.grids_getnb() == 2;
 
     log.undo();
     let check7 =
-      log.grids_getnames()[0].eq(&String::from("Grid1           ")) &&
+      log.grids_getnames()[0].eq(&String::from("Grid1")) &&
       log.grids_getnb() == 1;
 
     assert!(check1 && check2 && check3 && check4 && check5 && check6 && check7);
@@ -890,7 +1573,7 @@ pub mod tests {
 
   #[test]
   fn it_deletes_a_grid() {
-    let mut log = Log::new(0, 2);
+    let mut log = Log::new(0, 2, 10);
     let grid_area = (0, log.grids_getwidth(), 0, log.grids_getheight());
     log.gridname_setvalue('0');
     log.grids_add();
@@ -903,7 +1586,7 @@ pub mod tests {
 
   #[test]
   fn it_brushes_2_grids_and_decrements_width_and_keeps_cells_of_first_grid() {
-    let mut log = Log::new(4, 3);
+    let mut log = Log::new(4, 3, 10);
     let grid_area = (0, log.grids_getwidth(), 0, log.grids_getheight());
     log.brush((5, 1));
     log.check_last_action(grid_area);
@@ -931,7 +1614,7 @@ pub mod tests {
 
   #[test]
   fn it_brushes_2_grids_and_decrements_width_and_keeps_cells_of_second_grid() {
-    let mut log = Log::new(4, 3);
+    let mut log = Log::new(4, 3, 10);
     let grid_area = (0, log.grids_getwidth(), 0, log.grids_getheight());
     log.brush((5, 1));
     log.check_last_action(grid_area);
@@ -960,7 +1643,7 @@ pub mod tests {
 
   #[test]
   fn it_brushes_2_grids_and_decrements_width_and_keeps_cells_of_none_of_them() {
-    let mut log = Log::new(4, 3);
+    let mut log = Log::new(4, 3, 10);
     let grid_area = (0, log.grids_getwidth(), 0, log.grids_getheight());
     log.brush((9, 1));
     log.check_last_action(grid_area);
@@ -989,7 +1672,7 @@ pub mod tests {
 
   #[test]
   fn it_brushes_2_grids_and_decrements_width_and_keeps_cells_of_both_of_them() {
-    let mut log = Log::new(4, 3);
+    let mut log = Log::new(4, 3, 10);
     let grid_area = (0, log.grids_getwidth(), 0, log.grids_getheight());
     log.brush((7, 1));
     log.check_last_action(grid_area);
@@ -1018,7 +1701,7 @@ pub mod tests {
 
   #[test]
   fn it_brushes_2_grids_and_decrements_height_and_keeps_cells_of_first_grid() {
-    let mut log = Log::new(4, 3);
+    let mut log = Log::new(4, 3, 10);
     let grid_area = (0, log.grids_getwidth(), 0, log.grids_getheight());
     log.brush((5, 8));
     log.check_last_action(grid_area);
@@ -1046,7 +1729,7 @@ pub mod tests {
 
   #[test]
   fn it_brushes_2_grids_and_decrements_height_and_keeps_cells_of_second_grid() {
-    let mut log = Log::new(4, 3);
+    let mut log = Log::new(4, 3, 10);
     let grid_area = (0, log.grids_getheight(), 0, log.grids_getheight());
     log.brush((4, 9));
     log.check_last_action(grid_area);
@@ -1075,7 +1758,7 @@ pub mod tests {
 
   #[test]
   fn it_brushes_2_grids_and_decrements_height_and_keeps_cells_of_none_of_them() {
-    let mut log = Log::new(4, 3);
+    let mut log = Log::new(4, 3, 10);
     let grid_area = (0, log.grids_getheight(), 0, log.grids_getheight());
     log.brush((4, 9));
     log.check_last_action(grid_area);
@@ -1104,7 +1787,7 @@ pub mod tests {
 
   #[test]
   fn it_brushes_2_grids_and_decrements_height_and_keeps_cells_of_both_of_them() {
-    let mut log = Log::new(4, 3);
+    let mut log = Log::new(4, 3, 10);
     let grid_area = (0, log.grids_getheight(), 0, log.grids_getheight());
     log.brush((4, 6));
     log.check_last_action(grid_area);
@@ -1133,7 +1816,7 @@ pub mod tests {
 
   #[test]
   fn it_canceled_the_last_action() {
-    let mut log = Log::new(1, 1);
+    let mut log = Log::new(1, 1, 10);
     log.brush((1, 1));
     let actions_len = log.actions.len();
     let canceled_actions_len = log.canceledactions.len();
@@ -1144,7 +1827,7 @@ pub mod tests {
 
   #[test]
   fn it_canceled_several_last_actions() {
-    let mut log = Log::new(3, 3);
+    let mut log = Log::new(3, 3, 10);
     log.brush((1, 1));
     log.brush((2, 1));
     log.brush((1, 2));
@@ -1159,7 +1842,7 @@ pub mod tests {
 
   #[test]
   fn it_did_not_cancel_last_action_because_there_are_no_last_action() {
-    let mut log = Log::new(0, 1);
+    let mut log = Log::new(0, 1, 10);
     let actions_len = log.actions.len();
     let canceled_actions_len = log.canceledactions.len();
     log.undo();
@@ -1169,7 +1852,7 @@ pub mod tests {
 
   #[test]
   fn it_clears_canceled_actions_after_an_action() {
-    let mut log = Log::new(3, 3);
+    let mut log = Log::new(3, 3, 10);
     log.brush((1, 1));
     log.brush((2, 1));
     log.brush((1, 2));
@@ -1187,7 +1870,7 @@ pub mod tests {
 
   #[test]
   fn it_redid_the_last_action() {
-    let mut log = Log::new(1, 1);
+    let mut log = Log::new(1, 1, 10);
     log.brush((1, 1));
     log.undo();
     let actions_len = log.actions.len();
@@ -1199,7 +1882,7 @@ pub mod tests {
 
   #[test]
   fn it_redid_several_last_actions() {
-    let mut log = Log::new(3, 3);
+    let mut log = Log::new(3, 3, 10);
     log.brush((1, 1));
     log.brush((2, 1));
     log.brush((1, 2));
@@ -1217,7 +1900,7 @@ pub mod tests {
 
   #[test]
   fn it_did_not_redo_last_action_because_there_are_no_canceled_action() {
-    let mut log = Log::new(1, 1);
+    let mut log = Log::new(1, 1, 10);
     log.brush((1, 1));
     let actions_len = log.actions.len();
     let canceled_actions_len = log.canceledactions.len();
@@ -1228,7 +1911,7 @@ pub mod tests {
 
   #[test]
   fn it_pushed_last_poped_action_inside_delayed_grid_when_log_is_full() {
-    let mut log = Log::new(3, 1);
+    let mut log = Log::new(3, 1, 10);
     let grid_area = (0, log.grids_getwidth(), 0, log.grids_getheight());
     log.brush((1, 1));
     log.check_last_action(grid_area);
@@ -1246,8 +1929,8 @@ pub mod tests {
   }
 
   #[test]
-  fn it_pushed_last_poped_action_inside_delayed_grid_when_log_is_full_but_this_last_action_is_repetitive_so_log_poped_it_from_actions() {
-    let mut log = Log::new(3, 2);
+  fn it_does_not_pop_an_action_from_a_full_log_when_the_last_action_is_repetitive() {
+    let mut log = Log::new(3, 2, 10);
     let grid_area = (0, log.grids_getwidth(), 0, log.grids_getheight());
     log.brush((1, 1));
     log.check_last_action(grid_area);
@@ -1262,30 +1945,35 @@ pub mod tests {
     log.brush((2, 2));
     log.check_last_action(grid_area);
     assert!((actions_len == 2) && (delayed_len == 0) &&
-      (actions_len2 == 1) && (delayed_len2 == 1) && (log.actions.len() == 2)
+      (actions_len2 == 2) && (delayed_len2 == 0) && (log.actions.len() == 2)
       && (log.delayedgrids.get_nb_filledcells_current_grid() == 1));
   }
 
   #[test]
   fn it_pops_1_action_when_1_new_action_is_pushed_inside_a_full_log() {
-    let mut log = Log::new(2, 256);
+    let mut log = Log::new(100, 16, 10);
+    let grid_area = (0, log.grids_getwidth(), 0, log.grids_getheight());
+    let mut x = 0;
     while log.actions.len() < log.queuecapacity {
-      log.brush((1, 1));
+      log.brush((x % 10, x / 10));
+      log.check_last_action(grid_area);
+      x += 1;
     }
     let init_len = log.actions.len();
-    log.brush((2, 2));
+    log.brush((9, 9));
+    log.check_last_action(grid_area);
     if let Some(last_brush) = log.actions.back() {
       assert!((init_len == log.queuecapacity) &&
         (log.actions.len() == log.queuecapacity) &&
         (*last_brush == action::Action::new(log.grids.get_current_grid_info(),
           tools::Tool::CellSetter(tools::CellTool::PixelBrush(
-            log.brush.get_current_action(), 2, 2)))));
+            log.brush.get_current_action(), 9, 9)))));
     }
   }
 
   #[test]
   fn it_checks_6_last_action_outside_the_grid_and_pops_it(){
-    let mut log = Log::new(6, 6);
+    let mut log = Log::new(6, 6, 10);
     let (init_width, init_height) =
       (log.grids_getwidth(), log.grids_getheight());
     let grid_area = (5, init_width, 5, init_height);
@@ -1306,7 +1994,7 @@ pub mod tests {
 
   #[test]
   fn it_checks_last_brush_action_inside_the_grid_and_keeps_it(){
-    let mut log = Log::new(1, 1);
+    let mut log = Log::new(1, 1, 10);
     let (init_width, init_height) =
       (log.grids_getwidth::<u16>(), log.grids_getheight::<u16>());
     let grid_area = (0, init_width, 0, init_height);
@@ -1317,7 +2005,7 @@ pub mod tests {
 
   #[test]
   fn it_checks_3_same_brush_action_inside_the_grid_and_pops_2_of_them(){
-    let mut log = Log::new(1, 3);
+    let mut log = Log::new(1, 3, 10);
     let (init_width, init_height) =
       (log.grids_getwidth::<u16>(), log.grids_getheight::<u16>());
     let grid_area = (0, init_width, 0, init_height);
@@ -1332,7 +2020,7 @@ pub mod tests {
 
   #[test]
   fn it_checks_3_same_eraser_action_inside_the_grid_and_pops_2_of_them(){
-    let mut log = Log::new(1, 4);
+    let mut log = Log::new(1, 4, 10);
     let (init_width, init_height) =
       (log.grids_getwidth::<u16>(), log.grids_getheight::<u16>());
     let grid_area = (0, init_width, 0, init_height);
@@ -1349,7 +2037,7 @@ pub mod tests {
 
   #[test]
   fn it_checks_3_same_clear_action_and_pops_2_of_them(){
-    let mut log = Log::new(1, 4);
+    let mut log = Log::new(1, 4, 10);
     let (init_width, init_height) =
       (log.grids_getwidth::<u16>(), log.grids_getheight::<u16>());
     let grid_area = (0, init_width, 0, init_height);
@@ -1365,8 +2053,8 @@ pub mod tests {
   }
 
   #[test]
-  fn it_checks_4_last_decrement_actions_and_keeps_it(){
-    let mut log = Log::new(0, 4);
+  fn it_checks_4_last_decrement_actions_and_groups_repeated_ones(){
+    let mut log = Log::new(0, 4, 10);
     let (init_width, init_height) =
       (log.grids_getwidth::<u16>(), log.grids_getheight::<u16>());
     log.decrement_width();
@@ -1377,14 +2065,14 @@ pub mod tests {
     log.check_last_action((0, init_width - 2, 0, init_height - 1));
     log.decrement_height();
     log.check_last_action((0, init_width - 2, 0, init_height - 2));
-    assert!((log.actions.len() == 4) &&
+    assert!((log.actions.len() == 2) &&
       (log.grids_getwidth::<u16>() == init_width - 2) &&
       (log.grids_getheight::<u16>() == init_height - 2));
   }
 
   #[test]
-  fn it_checks_4_last_increment_actions_and_keeps_it(){
-    let mut log = Log::new(0, 4);
+  fn it_checks_4_last_increment_actions_and_groups_repeated_ones(){
+    let mut log = Log::new(0, 4, 10);
     let (init_width, init_height) =
       (log.grids_getwidth::<u16>(), log.grids_getheight::<u16>());
     log.increment_width();
@@ -1395,14 +2083,14 @@ pub mod tests {
     log.check_last_action((0, init_width + 2, 0, init_height + 1));
     log.increment_height();
     log.check_last_action((0, init_width + 2, 0, init_height + 2));
-    assert!((log.actions.len() == 4) &&
+    assert!((log.actions.len() == 2) &&
       (log.grids_getwidth::<u16>() == init_width + 2) &&
       (log.grids_getheight::<u16>() == init_height + 2));
   }
 
   #[test]
   fn it_checks_last_brush_action_on_a_filled_cell_and_it_does_not_overflow_memory_grids(){
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let (init_width, init_height) =
       (log.grids_getwidth::<u16>(), log.grids_getheight::<u16>());
     let grid_area = (0, init_width, 0, init_height);
@@ -1418,7 +2106,7 @@ pub mod tests {
 
   #[test]
   fn it_checks_last_brush_action_on_an_empty_cell_and_it_overflows_memory_grids(){
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let (init_width, init_height) =
       (log.grids_getwidth::<u16>(), log.grids_getheight::<u16>());
     let grid_area = (0, init_width, 0, init_height);
@@ -1429,12 +2117,12 @@ pub mod tests {
     let init_len = log.actions.len();
     log.brush((6, 4));
     log.check_last_action(grid_area);
-    assert!((init_len == 2) && (log.actions.len() == 2) && log.overflow);
+    assert!((init_len == 2) && (log.actions.len() == 3) && log.overflow);
   }
 
   #[test]
   fn it_checks_last_brush_action_on_a_filled_cell_after_a_memory_grids_overflow_and_it_does_not_overflow_grids(){
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let (init_width, init_height) =
       (log.grids_getwidth::<u16>(), log.grids_getheight::<u16>());
     let grid_area = (0, init_width, 0, init_height);
@@ -1448,13 +2136,13 @@ pub mod tests {
     let was_overflow = log.overflow;
     log.brush((5, 4));
     log.check_last_action(grid_area);
-    assert!((init_len == 2) && (log.actions.len() == 2) && !log.overflow &&
+    assert!((init_len == 2) && (log.actions.len() == 3) && !log.overflow &&
       was_overflow);
   }
 
   #[test]
   fn it_checks_last_eraser_action_after_a_memory_grids_overflow_and_it_does_not_overflow_grids(){
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let (init_width, init_height) =
       (log.grids_getwidth::<u16>(), log.grids_getheight::<u16>());
     let grid_area = (0, init_width, 0, init_height);
@@ -1474,7 +2162,7 @@ pub mod tests {
 
   #[test]
   fn it_checks_last_missed_eraser_action_after_a_memory_grids_overflow_and_it_does_not_overflow_grids(){
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let (init_width, init_height) =
       (log.grids_getwidth::<u16>(), log.grids_getheight::<u16>());
     let grid_area = (0, init_width, 0, init_height);
@@ -1488,13 +2176,13 @@ pub mod tests {
     let was_overflow = log.overflow;
     log.erase((6, 4));
     log.check_last_action(grid_area);
-    assert!((init_len == 2) && (log.actions.len() == 2) && !log.overflow &&
+    assert!((init_len == 2) && (log.actions.len() == 3) && !log.overflow &&
       was_overflow);
   }
 
   #[test]
   fn it_checks_last_clear_action_after_a_memory_grids_overflow_and_it_does_not_overflow_grids(){
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let (init_width, init_height) =
       (log.grids_getwidth::<u16>(), log.grids_getheight::<u16>());
     let grid_area = (0, init_width, 0, init_height);
@@ -1514,7 +2202,7 @@ pub mod tests {
 
   #[test]
   fn it_increments_width_grid_after_a_memory_grids_overflow_and_it_does_not_overflow_grids(){
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let (init_width, init_height) =
       (log.grids_getwidth::<u16>(), log.grids_getheight::<u16>());
     let grid_area = (0, init_width, 0, init_height);
@@ -1534,7 +2222,7 @@ pub mod tests {
 
   #[test]
   fn it_increments_height_grid_after_a_memory_grids_overflow_and_it_does_not_overflow_grids(){
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let (init_width, init_height) =
       (log.grids_getwidth::<u16>(), log.grids_getheight::<u16>());
     let grid_area = (0, init_width, 0, init_height);
@@ -1554,7 +2242,7 @@ pub mod tests {
 
   #[test]
   fn it_decrements_width_grid_after_a_memory_grids_overflow_and_it_does_not_overflow_grids(){
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let (init_width, init_height) =
       (log.grids_getwidth::<u16>(), log.grids_getheight::<u16>());
     let grid_area = (0, init_width, 0, init_height);
@@ -1574,7 +2262,7 @@ pub mod tests {
 
   #[test]
   fn it_decrements_height_grid_after_a_memory_grids_overflow_and_it_does_not_overflow_grids(){
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let (init_width, init_height) =
       (log.grids_getwidth::<u16>(), log.grids_getheight::<u16>());
     let grid_area = (0, init_width, 0, init_height);
@@ -1594,7 +2282,7 @@ pub mod tests {
 
   #[test]
   fn it_brushed_an_empty_cell_and_gets_a_filler_grid() {
-    let mut log = Log::new(1, 1);
+    let mut log = Log::new(1, 1, 10);
     let (init_width, init_height) =
       (log.grids_getwidth(), log.grids_getheight());
     let grid_area = (0, init_width, 0, init_height);
@@ -1606,7 +2294,7 @@ pub mod tests {
 
   #[test]
   fn it_brushed_a_filled_cell_and_gets_the_same_grid_and_a_len_log_equal_to_1() {
-    let mut log = Log::new(2, 2);
+    let mut log = Log::new(2, 2, 10);
     let (init_width, init_height) =
       (log.grids_getwidth(), log.grids_getheight());
     let grid_area = (0, init_width, 0, init_height);
@@ -1621,7 +2309,7 @@ pub mod tests {
 
   #[test]
   fn it_erased_a_filled_cell_and_gets_an_emptier_grid() {
-    let mut log = Log::new(1, 2);
+    let mut log = Log::new(1, 2, 10);
     let (init_width, init_height) =
       (log.grids_getwidth(), log.grids_getheight());
     let grid_area = (0, init_width, 0, init_height);
@@ -1635,7 +2323,7 @@ pub mod tests {
 
   #[test]
   fn it_erased_an_empty_cell_and_gets_the_same_grid_and_an_empty_log() {
-    let mut log = Log::new(0, 1);
+    let mut log = Log::new(0, 1, 10);
     let (init_width, init_height) =
       (log.grids_getwidth(), log.grids_getheight());
     let grid_area = (0, init_width, 0, init_height);
@@ -1648,7 +2336,7 @@ pub mod tests {
 
   #[test]
   fn it_cleared_a_filled_cell_and_gets_an_empty_grid() {
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let (init_width, init_height) =
       (log.grids_getwidth(), log.grids_getheight());
     let grid_area = (0, init_width, 0, init_height);
@@ -1664,7 +2352,7 @@ pub mod tests {
 
   #[test]
   fn it_cleared_an_empty_grid_and_gets_the_same_grid_and_an_empty_log() {
-    let mut log = Log::new(0, 1);
+    let mut log = Log::new(0, 1, 10);
     let (init_width, init_height) =
       (log.grids_getwidth(), log.grids_getheight());
     let grid_area = (0, init_width, 0, init_height);
@@ -1678,7 +2366,7 @@ pub mod tests {
 
   #[test]
   fn it_brushed_an_empty_cell_1_but_decrements_width_and_erase_cell_1() {
-    let mut log = Log::new(1, 2);
+    let mut log = Log::new(1, 2, 10);
     let (init_width, init_height) =
       (log.grids_getwidth(), log.grids_getheight());
     let grid_area = (0, init_width, 0, init_height);
@@ -1693,7 +2381,7 @@ pub mod tests {
 
   #[test]
   fn it_brushed_an_empty_cell_1_but_decrements_height_and_erase_cell_1() {
-    let mut log = Log::new(1, 2);
+    let mut log = Log::new(1, 2, 10);
     let (init_width, init_height) =
       (log.grids_getwidth(), log.grids_getheight());
     let grid_area = (0, init_width, 0, init_height);
@@ -1708,7 +2396,7 @@ pub mod tests {
 
   #[test]
   fn it_brushed_2_empty_cell_1_and_2_but_decrements_width_and_erase_cell_1() {
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let (init_width, init_height) =
       (log.grids_getwidth(), log.grids_getheight());
     let grid_area = (0, init_width, 0, init_height);
@@ -1725,7 +2413,7 @@ pub mod tests {
 
   #[test]
   fn it_brushed_2_empty_cell_1_and_2_but_decrements_height_and_erase_cell_1() {
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let (init_width, init_height) =
       (log.grids_getwidth(), log.grids_getheight());
     let grid_area = (0, init_width, 0, init_height);
@@ -1742,7 +2430,7 @@ pub mod tests {
 
   #[test]
   fn it_brushed_2_empty_cells_but_decrements_width_and_erase_both() {
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let (init_width, init_height) =
       (log.grids_getwidth(), log.grids_getheight());
     let grid_area = (0, init_width, 0, init_height);
@@ -1759,7 +2447,7 @@ pub mod tests {
 
   #[test]
   fn it_brushed_2_empty_cells_but_decrements_height_and_erase_both() {
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let (init_width, init_height) =
       (log.grids_getwidth(), log.grids_getheight());
     let grid_area = (0, init_width, 0, init_height);
@@ -1776,7 +2464,7 @@ pub mod tests {
 
   #[test]
   fn it_brushed_2_empty_cells_and_decrements_width() {
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let (init_width, init_height) =
       (log.grids_getwidth(), log.grids_getheight());
     let grid_area = (0, init_width, 0, init_height);
@@ -1793,7 +2481,7 @@ pub mod tests {
 
   #[test]
   fn it_brushed_2_empty_cells_and_decrements_height() {
-    let mut log = Log::new(2, 3);
+    let mut log = Log::new(2, 3, 10);
     let (init_width, init_height) =
       (log.grids_getwidth(), log.grids_getheight());
     let grid_area = (0, init_width, 0, init_height);
@@ -1808,4 +2496,308 @@ pub mod tests {
       (init_height == 10) &&
       (log.grids_getheight::<u16>() == init_height - 1));
   }
+
+  #[test]
+  fn it_restores_the_grid_from_a_snapshot_after_several_undos() {
+    let mut log = Log::new(20, 64, 10);
+    let grid_area = (0, log.grids_getwidth(), 0, log.grids_getheight());
+    for x in 0..10 {
+      log.brush((x, 1));
+      log.check_last_action(grid_area);
+    }
+    log.undo();
+    log.undo();
+    log.undo();
+    assert!((log.snapshots.len() == 1) &&
+      (log.grids_getcurrentgrid().len() == 7));
+  }
+
+  #[test]
+  fn it_goes_back_and_forth_in_the_history() {
+    let mut log = Log::new(20, 16, 10);
+    let grid_area = (0, log.grids_getwidth(), 0, log.grids_getheight());
+    for x in 0..6 {
+      log.brush((x, 1));
+      log.check_last_action(grid_area);
+    }
+    log.history_resetcursor();
+    log.history_previouscursor();
+    log.history_previouscursor();
+    log.history_previouscursor();
+    log.history_previouscursor();
+    log.history_goto();
+    let back_len = log.grids_getcurrentgrid().len();
+    let entries_len = log.history_getentries().len();
+    log.history_nextcursor();
+    log.history_nextcursor();
+    log.history_goto();
+    assert!((back_len == 2) && (entries_len == 7) &&
+      (log.grids_getcurrentgrid().len() == 4) &&
+      (log.history_getposition() == 4) && (log.canceledactions.len() == 2));
+  }
+
+  #[test]
+  fn it_undoes_and_redoes_a_drag_stroke_as_a_unit() {
+    let mut log = Log::new(20, 4, 10);
+    let grid_area = (0, log.grids_getwidth(), 0, log.grids_getheight());
+    log.brush((0, 0));
+    log.check_last_action(grid_area);
+    for x in 1..8 {
+      log.drag_brush((x, 0));
+      log.check_last_action(grid_area);
+    }
+    let actions_len = log.actions.len();
+    let stroke_len = log.grids_getcurrentgrid().len();
+    log.undo();
+    let undone_len = log.grids_getcurrentgrid().len();
+    log.redo();
+    assert!((actions_len == 1) && (stroke_len == 8) && (undone_len == 0) &&
+      (log.grids_getcurrentgrid().len() == 8));
+  }
+
+  #[test]
+  fn it_does_not_group_two_drag_strokes() {
+    let mut log = Log::new(20, 4, 10);
+    let grid_area = (0, log.grids_getwidth(), 0, log.grids_getheight());
+    log.brush((0, 0));
+    log.check_last_action(grid_area);
+    log.drag_brush((1, 0));
+    log.check_last_action(grid_area);
+    log.brush((0, 1));
+    log.check_last_action(grid_area);
+    log.drag_brush((1, 1));
+    log.check_last_action(grid_area);
+    log.undo();
+    assert!((log.actions.len() == 1) &&
+      (log.grids_getcurrentgrid().len() == 2));
+  }
+
+  #[test]
+  fn it_brushes_erases_and_cycles_the_cell_under_the_cursor() {
+    let mut log = Log::new(20, 8, 10);
+    log.cursor_toggle();
+    log.cursor_right();
+    log.cursor_down();
+    log.cursor_brush();
+    let brushed = log.grids_getcurrentgrid();
+    log.cursor_cyclepixel();
+    let cycled = log.grids_getcurrentgrid();
+    log.cursor_erase();
+    assert!((brushed.len() == 1) && (brushed[0].1 == 1) &&
+      (brushed[0].2 == 1) && (brushed[0].0 == FullPixel::BodyEmpty) &&
+      (cycled[0].0 == FullPixel::BodyBorder) &&
+      (log.grids_getcurrentgrid().len() == 0) && (log.actions.len() == 3));
+  }
+
+  #[test]
+  fn it_scrolls_the_grid_when_the_cursor_leaves_the_visible_area() {
+    let mut log = Log::new(20, 8, 10);
+    log.gridscroll_checkscroll(&(4, 4));
+    log.cursor_toggle();
+    for _ in 0..5 {
+      log.cursor_right();
+    }
+    let scroll_right = log.gridscroll_getscroll();
+    for _ in 0..5 {
+      log.cursor_left();
+    }
+    assert!((scroll_right == (2, 0)) && (log.gridscroll_getscroll() == (0, 0)));
+  }
+
+  #[test]
+  fn it_brushes_the_clicked_cell_of_a_zoomed_and_scrolled_grid() {
+    let mut log = Log::new(20, 8, 10);
+    let grid_area = (10, 14, 4, 8);
+    log.zoom_in();
+    log.zoom_in();
+    log.gridscroll_checkscroll(&log.zoom_tocells((4, 4)));
+    log.gridscroll_scrollright();
+    log.gridscroll_scrolldown();
+    log.brush((13, 7));
+    log.check_last_action(grid_area);
+    log.brush((9, 5));
+    log.check_last_action(grid_area);
+    let grid = log.grids_getcurrentgrid();
+    assert!((grid.len() == 1) && (grid[0].1 == 2) && (grid[0].2 == 2));
+  }
+
+  #[test]
+  fn it_only_paints_with_the_cursor_at_the_half_block_zoom() {
+    let mut log = Log::new(20, 8, 10);
+    let grid_area = (0, 10, 0, 10);
+    log.zoom_out();
+    log.brush((1, 1));
+    log.check_last_action(grid_area);
+    let clicked = log.grids_getcurrentgrid().is_empty();
+    log.cursor_toggle();
+    log.cursor_down();
+    log.cursor_brush();
+    assert!(clicked && (log.actions.len() == 1) &&
+      (log.grids_getcell((0, 1)).is_some()));
+  }
+
+  #[test]
+  fn it_gets_the_cell_under_the_pointer_of_a_zoomed_and_scrolled_grid() {
+    let mut log = Log::new(20, 8, 10);
+    log.pointer_setarea((10, 14, 4, 8));
+    log.pointer_setposition((13, 7));
+    let unscrolled = log.pointer_getcell();
+    log.zoom_in();
+    log.zoom_in();
+    log.gridscroll_checkscroll(&log.zoom_tocells((4, 4)));
+    log.gridscroll_scrollright();
+    log.gridscroll_scrolldown();
+    let scrolled = log.pointer_getcell();
+    log.pointer_setposition((9, 5));
+    assert!((unscrolled == Some((3, 3))) && (scrolled == Some((2, 2))) &&
+      log.pointer_getcell().is_none());
+  }
+
+  #[test]
+  fn it_selects_a_clicked_tab_and_scrolls_with_a_dragged_thumb() {
+    let mut log = Log::new(20, 8, 10);
+    log.gridname_setvalue('0');
+    log.grids_add();
+    log.check_last_action((0, 10, 0, 10));
+    log.pointer_settabs((40, 56, 1, 3), 0);
+    let pressed = log.pointer_press((45, 1));
+    let first_grid = log.grids_getcurrentgridid();
+    log.pointer_release((45, 1));
+    log.gridscroll_checkscroll(&(4, 4));
+    log.pointer_setscroller(Axis::Vertical,
+      Some(Scroller { line: 9, thumb: (2, 4), cells: 8, track: 4 }));
+    log.pointer_press((9, 2));
+    let dragged = log.pointer_drag((9, 20));
+    assert!(pressed && (first_grid == 0) && dragged &&
+      (log.gridscroll_getscroll() == (0, 6)) && (log.actions.len() == 1));
+  }
+
+  #[test]
+  fn it_resizes_the_grids_around_the_typed_anchor_and_undoes_it() {
+    let mut log = Log::new(20, 8, 10);
+    let grid_area = (0, 10, 0, 10);
+    log.brush((1, 1));
+    log.check_last_action(grid_area);
+    let brushed = log.grids_getcurrentgrid();
+    log.gridsize_reset();
+    for number in "020015".chars() {
+      log.gridsize_setvalue(number);
+    }
+    log.gridsize_previousanchor();
+    log.resize();
+    log.check_last_action(grid_area);
+    let resized: (u16, u16) = (log.grids_getwidth(), log.grids_getheight());
+    let moved = log.grids_getcurrentgrid();
+    log.undo();
+    assert!((resized == (20, 15)) && (moved == brushed.iter()
+      .map(|&(pixel, x, y)| (pixel, x + 10, y + 5)).collect::<Vec<_>>()) &&
+      (log.grids_getwidth::<u16>() == 10) &&
+      (log.grids_getcurrentgrid() == brushed));
+  }
+
+  #[test]
+  fn it_resizes_the_grids_once_their_dragged_corner_is_released() {
+    let mut log = Log::new(20, 8, 10);
+    let grid_area = (0, 10, 0, 10);
+    log.brush((8, 8));
+    log.check_last_action(grid_area);
+    log.pointer_setcorner((10, 10));
+    let pressed = log.pointer_press((10, 10));
+    log.pointer_drag((4, 4));
+    log.check_last_action(grid_area);
+    let previewed = log.pointer_getgridsize();
+    let unchanged: (u16, u16) = (log.grids_getwidth(), log.grids_getheight());
+    log.pointer_drag((16, 13));
+    log.pointer_release((16, 13));
+    log.check_last_action(grid_area);
+    let resized: (u16, u16) = (log.grids_getwidth(), log.grids_getheight());
+    let kept = log.grids_getcell((8, 8)).is_some();
+    log.undo();
+    assert!(pressed && (previewed == (4, 4)) && (unchanged == (10, 10)) &&
+      (resized == (16, 13)) && kept && (log.actions.len() == 1) &&
+      (log.pointer_getgridsize() == (10, 10)));
+  }
+
+  #[test]
+  fn it_loses_the_saved_state_after_an_undo_and_a_new_action() {
+    let mut log = Log::new(20, 8, 10);
+    let grid_area = (0, 10, 0, 10);
+    let started = log.history_issaved();
+    log.brush((1, 1));
+    log.check_last_action(grid_area);
+    let edited = log.history_issaved();
+    log.history_setsaved();
+    log.undo();
+    let undone = log.history_issaved();
+    log.redo();
+    let redone = log.history_issaved();
+    log.undo();
+    log.brush((2, 2));
+    log.check_last_action(grid_area);
+    log.undo();
+    assert!(started && !edited && !undone && redone &&
+      !log.history_issaved());
+  }
+
+  #[test]
+  fn it_does_not_edit_a_locked_grid() {
+    let mut log = Log::new(20, 8, 10);
+    let grid_area = (0, 10, 0, 10);
+    log.brush((1, 1));
+    log.check_last_action(grid_area);
+    log.grids_togglelock();
+    log.check_last_action(grid_area);
+    log.brush((2, 2));
+    log.check_last_action(grid_area);
+    log.clear();
+    log.check_last_action(grid_area);
+    log.cursor_toggle();
+    log.cursor_erase();
+    let locked = log.grids_getcurrentgrid().len();
+    log.grids_togglelock();
+    log.check_last_action(grid_area);
+    log.clear();
+    log.check_last_action(grid_area);
+    assert!((locked == 1) && (log.grids_getlocked() == vec![false]) &&
+      log.grids_getcurrentgrid().is_empty());
+  }
+
+  #[test]
+  fn it_undoes_the_visibility_of_a_grid() {
+    let mut log = Log::new(20, 8, 10);
+    log.brush((1, 1));
+    log.check_last_action((0, 10, 0, 10));
+    log.grids_togglevisibility();
+    log.check_last_action((0, 10, 0, 10));
+    let hidden = log.grids_gethidden();
+    let visible_grids = log.grids_getvisiblegrids::<u16>().len();
+    log.undo();
+    assert!((hidden == vec![true]) && (visible_grids == 0) &&
+      (log.grids_gethidden() == vec![false]) &&
+      (log.grids_getgrids::<u16>().len() == 1));
+  }
+
+  #[test]
+  fn it_replaces_the_defaults_of_the_tools() {
+    let mut log = Log::new(0, 0, 10);
+    log.set_defaults(&Defaults {
+      grid_size: Some(16),
+      pixel_ratio: Some(12),
+      body_empty_ratio: Some(20),
+      generation_number: Some(30),
+      ..Defaults::default()
+    }).unwrap();
+    let wrong_defaults = Defaults {
+      pixel_ratio: Some(11),
+      ..Defaults::default()
+    };
+    assert!((log.grids_getwidth::<u16>() == 16) &&
+      (log.grids_getheight::<u16>() == 16) &&
+      (log.texturessettings_getpixelratio::<u16>() == 12) &&
+      ((log.brush_getbodyderatio() - 0.2).abs() < f64::EPSILON) &&
+      (log.brush_getbodydrratio() == 0.5) &&
+      (log.texturessettings_getgenerationnumber() == 30) &&
+      (log.generationnumber_getvalue() == "00030") &&
+      log.set_defaults(&wrong_defaults).is_err());
+  }
 }
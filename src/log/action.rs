@@ -1,7 +1,8 @@
-use crate::log::tools;
+use crate::log::tools::{self, zoom::Zoom};
+use liriodendron::utils::FullPixel;
 
 /// Represents a user action
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct Action {
   grid_id: usize,
   grid: tools::Grid,
@@ -17,6 +18,13 @@ pub struct Action {
   /// system for the tested action. The <i>checked</i> member only matters for
   /// cells setter tools.
   checked: bool,
+
+  /// Consecutive actions with the same <i>group_id</i> and the same kind of
+  /// tool are stocked as one action, with the <i>group</i> member keeping
+  /// the tools applied after <i>tool</i>. A mouse stroke or a held key is
+  /// then undone and redone as a unit.
+  group_id: usize,
+  group: Vec<tools::Tool>,
 }
 
 impl PartialEq for Action {
@@ -34,9 +42,52 @@ impl Action {
         grid: grid,
         tool: tool,
         checked: false,
+        group_id: 0,
+        group: Vec::new(),
       }
   }
 
+  pub fn set_group_id(&mut self, group_id: usize) {
+    self.group_id = group_id;
+  }
+
+  /// Returns <i>true</i> if <i>other</i> continues the group of this action:
+  /// both actions have the same group, the same grid and the same kind of
+  /// tool
+  pub fn can_group(&self, other: &Action) -> bool {
+    (self.group_id == other.group_id) && (self.grid_id == other.grid_id) &&
+    (self.grid == other.grid) &&
+    match (&self.tool, &other.tool) {
+      (tools::Tool::CellSetter(tools::CellTool::PixelBrush(_, _, _)),
+        tools::Tool::CellSetter(tools::CellTool::PixelBrush(_, _, _))) |
+      (tools::Tool::CellSetter(tools::CellTool::PixelEraser(_, _)),
+        tools::Tool::CellSetter(tools::CellTool::PixelEraser(_, _))) => true,
+      (tools::Tool::GridSetter(tool), tools::Tool::GridSetter(other_tool)) =>
+        (*tool != tools::GridTool::ClearGrid) && (tool == other_tool),
+      _ => false,
+    }
+  }
+
+  /// Adds the tool of <i>other</i> to the group of this action
+  pub fn group(&mut self, other: Action) {
+    self.group.push(other.tool);
+  }
+
+  /// Returns one action per tool of the group, in the order they have been
+  /// applied
+  pub fn split(&self) -> Vec<Action> {
+    let mut tools = vec![self.tool.clone()];
+    tools.extend(self.group.iter().cloned());
+    tools.into_iter()
+      .map(|tool| {
+        let mut action = Action::new((self.grid, self.grid_id), tool);
+        action.checked = self.checked;
+        action.group_id = self.group_id;
+        action
+      })
+      .collect()
+  }
+
   pub fn get_grid_id(&self) -> usize {
     self.grid_id
   }
@@ -46,38 +97,99 @@ impl Action {
   }
 
   pub fn get_tool(&self) -> tools::Tool {
-    self.tool
+    self.tool.clone()
   }
 
   pub fn is_checked(&self) -> bool {
     self.checked
   }
 
+  /// Returns a short description of the action for the <i>user
+  /// interface</i> history
+  pub fn get_label(&self) -> String {
+    let grid = format!("Grid {}", self.grid_id + 1);
+    let label = match &self.tool {
+      tools::Tool::CellSetter(tools::CellTool::PixelBrush(pixel, x, y)) => {
+        let pixel = match pixel {
+          FullPixel::Body => "Body",
+          FullPixel::Border => "Border",
+          FullPixel::BodyBorder => "D|R",
+          FullPixel::BodyEmpty => "D|E",
+          FullPixel::SpecificColor(_, _, _) => "Color",
+        };
+        format!("{}: {} ({}, {})", grid, pixel, x, y)
+      },
+      tools::Tool::CellSetter(tools::CellTool::PixelEraser(x, y)) =>
+        format!("{}: Erase ({}, {})", grid, x, y),
+      tools::Tool::GridSetter(tool) => {
+        let tool = match tool {
+          tools::GridTool::ClearGrid => String::from("Clear"),
+          tools::GridTool::HeightDecrementor => String::from("Height -1"),
+          tools::GridTool::HeightIncrementor => String::from("Height +1"),
+          tools::GridTool::WidthDecrementor => String::from("Width -1"),
+          tools::GridTool::WidthIncrementor => String::from("Width +1"),
+          tools::GridTool::Resize(width, height, _) =>
+            format!("Resize {}×{}", width, height),
+        };
+        format!("{}: {}", grid, tool)
+      },
+      tools::Tool::WorkspaceSetter(tool) => {
+        match tool {
+          tools::WorkspaceTool::AddGrid(name) => format!("Add {}", name),
+          tools::WorkspaceTool::RenameGrid(name) =>
+            format!("{}: Rename {}", grid, name),
+          tools::WorkspaceTool::DeleteGrid => format!("{}: Delete", grid),
+          tools::WorkspaceTool::SwitchGrid(_) =>
+            format!("{}: Switch Order", grid),
+          tools::WorkspaceTool::ToggleVisibility =>
+            format!("{}: Hide | Show", grid),
+          tools::WorkspaceTool::ToggleLock =>
+            format!("{}: Lock | Unlock", grid),
+        }
+      },
+    };
+    if self.group.is_empty() {
+      label
+    } else {
+      format!("{} x{}", label, self.group.len() + 1)
+    }
+  }
+
   /// Creates a new Action object with fitted grid's coordinates to the
-  /// <i>user interface</i> system.
-  pub fn corrected(&self, left: Option<u16>, top: Option<u16>) -> Action {
+  /// <i>user interface</i> system. The coordinates of a cell tool are a
+  /// position got from <i>zoom</i>, mapped back to a cell.
+  pub fn corrected(&self, left: Option<u16>, top: Option<u16>, zoom: &Zoom)
+    -> Action {
     if !self.is_checked() {
       Action {
         grid_id: self.grid_id,
         grid: self.grid,
         tool: match self.tool {
-                tools::Tool::WorkspaceSetter(_) => self.tool,
-                tools::Tool::GridSetter(_) => self.tool,
+                tools::Tool::WorkspaceSetter(_) => self.tool.clone(),
+                tools::Tool::GridSetter(_) => self.tool.clone(),
                 tools::Tool::CellSetter(cell_tool) => {
                   match cell_tool {
-                    tools::CellTool::PixelBrush(pixel, x, y) =>
-                      tools::Tool::CellSetter(tools::CellTool::PixelBrush(
-                        pixel, x - left.unwrap(), y - top.unwrap())),
-                    tools::CellTool::PixelEraser(x, y) =>
-                      tools::Tool::CellSetter(tools::CellTool::PixelEraser(
-                        x - left.unwrap(), y - top.unwrap())),
+                    tools::CellTool::PixelBrush(pixel, x, y) => {
+                      let (x, y) = zoom.get_cell((x, y),
+                        (left.unwrap(), top.unwrap()));
+                      tools::Tool::CellSetter(
+                        tools::CellTool::PixelBrush(pixel, x, y))
+                    },
+                    tools::CellTool::PixelEraser(x, y) => {
+                      let (x, y) = zoom.get_cell((x, y),
+                        (left.unwrap(), top.unwrap()));
+                      tools::Tool::CellSetter(
+                        tools::CellTool::PixelEraser(x, y))
+                    },
                   }
                 },
               },
         checked: true,
+        group_id: self.group_id,
+        group: self.group.clone(),
       }
     } else {
-      *self
+      self.clone()
     }
   }
 }
@@ -86,7 +198,6 @@ impl Action {
 mod tests {
 
   use super::*;
-  use crate::utils::FullPixel;
 
   #[test]
   fn it_corrects_a_brush_action() {
@@ -96,7 +207,8 @@ mod tests {
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::Body, brush_x, brush_y)));
     let not_checked = action.checked;
-    action = action.corrected(left_correction, top_correction);
+    action = action.corrected(left_correction, top_correction,
+      &Zoom::new());
     if let tools::Tool::CellSetter(cell_tool) = action.get_tool() {
       if let tools::CellTool::PixelBrush(_pixel, x, y) = cell_tool {
         assert!(!not_checked && action.checked &&
@@ -114,7 +226,8 @@ mod tests {
       tools::Tool::CellSetter(tools::CellTool::PixelEraser(
         eraser_x, eraser_y)));
     let not_checked = action.checked;
-    action = action.corrected(left_correction, top_correction);
+    action = action.corrected(left_correction, top_correction,
+      &Zoom::new());
     if let tools::Tool::CellSetter(cell_tool) = action.get_tool() {
       if let tools::CellTool::PixelEraser(x, y) = cell_tool {
         assert!(!not_checked && action.checked &&
@@ -133,7 +246,8 @@ mod tests {
         eraser_x, eraser_y)));
     action.checked = true;
     let checked = action.checked;
-    action = action.corrected(left_correction, top_correction);
+    action = action.corrected(left_correction, top_correction,
+      &Zoom::new());
     if let tools::Tool::CellSetter(cell_tool) = action.get_tool() {
       if let tools::CellTool::PixelEraser(x, y) = cell_tool {
         assert!(checked && action.checked && (x == eraser_x) &&
@@ -141,4 +255,55 @@ mod tests {
       }
     }
   }
+
+  #[test]
+  fn it_corrects_a_zoomed_brush_action() {
+    let mut zoom = Zoom::new();
+    zoom.zoom_in();
+    let (brush_x, brush_y) = zoom.get_position((9, 6), (1, 0));
+    let action = Action::new((tools::Grid::Generation, 0),
+      tools::Tool::CellSetter(tools::CellTool::PixelBrush(
+        FullPixel::Body, brush_x, brush_y))).corrected(Some(4), Some(2),
+        &zoom);
+    assert!(action.get_tool() == tools::Tool::CellSetter(
+      tools::CellTool::PixelBrush(FullPixel::Body, 3, 4)));
+  }
+
+  #[test]
+  fn it_groups_two_brush_actions_of_the_same_group() {
+    let mut action = Action::new((tools::Grid::Generation, 0),
+      tools::Tool::CellSetter(tools::CellTool::PixelBrush(
+        FullPixel::Body, 1, 1)));
+    let mut other = Action::new((tools::Grid::Generation, 0),
+      tools::Tool::CellSetter(tools::CellTool::PixelBrush(
+        FullPixel::Border, 2, 1)));
+    action.set_group_id(1);
+    other.set_group_id(1);
+    let can_group = action.can_group(&other);
+    action.group(other);
+    assert!(can_group && (action.split().len() == 2));
+  }
+
+  #[test]
+  fn it_does_not_group_a_brush_action_and_an_eraser_action() {
+    let mut action = Action::new((tools::Grid::Generation, 0),
+      tools::Tool::CellSetter(tools::CellTool::PixelBrush(
+        FullPixel::Body, 1, 1)));
+    let mut other = Action::new((tools::Grid::Generation, 0),
+      tools::Tool::CellSetter(tools::CellTool::PixelEraser(2, 1)));
+    action.set_group_id(1);
+    other.set_group_id(1);
+    assert!(!action.can_group(&other));
+  }
+
+  #[test]
+  fn it_does_not_group_two_actions_of_different_groups() {
+    let mut action = Action::new((tools::Grid::Generation, 0),
+      tools::Tool::GridSetter(tools::GridTool::WidthIncrementor));
+    let mut other = Action::new((tools::Grid::Generation, 0),
+      tools::Tool::GridSetter(tools::GridTool::WidthIncrementor));
+    action.set_group_id(1);
+    other.set_group_id(2);
+    assert!(!action.can_group(&other));
+  }
 }
@@ -1,14 +1,19 @@
 pub mod brush;
+pub mod cursor;
+pub mod exportsettings;
 pub mod grids;
+pub mod overlay;
+pub mod pointer;
 pub mod texturessettings;
+pub mod zoom;
 
 /// Tools and settings where <i>user interface</i> requests user's
 /// inputs
 pub mod setter;
 
-use crate::utils::FullPixel;
+use liriodendron::utils::FullPixel;
 
-const MAX_SIZE: usize = 16;
+const MAX_SIZE: usize = 32;
 
 /// Possible content for a cell
 #[derive(Clone, Copy)]
@@ -24,15 +29,65 @@ pub enum Grid {
 }
 
 /// Workspace tools: grids management
-#[derive(Clone, Copy, Eq, PartialEq)]
+#[derive(Clone, Eq, PartialEq)]
 pub enum WorkspaceTool {
-  AddGrid([char; MAX_SIZE]),
-  RenameGrid([char; MAX_SIZE]),
+  AddGrid(String),
+  RenameGrid(String),
   DeleteGrid,
   SwitchGrid(usize),
+  ToggleVisibility,
+  ToggleLock,
+}
+
+/// Side of a resized grid where the existing cells stay
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Anchor {
+  TopLeft,
+  Top,
+  TopRight,
+  Left,
+  Center,
+  Right,
+  BottomLeft,
+  Bottom,
+  BottomRight,
 }
 
-/// Grid tools: grid cleaner and grid size incrementors and decrementors
+pub const ANCHORS: [Anchor; 9] = [Anchor::TopLeft, Anchor::Top,
+  Anchor::TopRight, Anchor::Left, Anchor::Center, Anchor::Right,
+  Anchor::BottomLeft, Anchor::Bottom, Anchor::BottomRight];
+
+impl Anchor {
+
+  pub fn get_label(self) -> &'static str {
+    match self {
+      Anchor::TopLeft => "Top Left",
+      Anchor::Top => "Top",
+      Anchor::TopRight => "Top Right",
+      Anchor::Left => "Left",
+      Anchor::Center => "Center",
+      Anchor::Right => "Right",
+      Anchor::BottomLeft => "Bottom Left",
+      Anchor::Bottom => "Bottom",
+      Anchor::BottomRight => "Bottom Right",
+    }
+  }
+
+  /// Returns how far the cells move when a grid of size <i>old</i> is
+  /// resized to <i>new</i>
+  pub fn get_offset(self, (old_width, old_height): (u16, u16),
+    (new_width, new_height): (u16, u16)) -> (i32, i32) {
+      let index = ANCHORS.iter().position(|&anchor| anchor == self).unwrap();
+      let (column, row) = (index % 3, index / 3);
+      let offset = |old: u16, new: u16, factor: usize|
+        (i32::from(new) - i32::from(old)) * factor as i32 / 2;
+      (offset(old_width, new_width, column),
+        offset(old_height, new_height, row))
+  }
+}
+
+/// Grid tools: grid cleaner, grid size incrementors and decrementors, and
+/// grid resizer to an exact size
 #[derive(Clone, Copy, Eq, PartialEq)]
 pub enum GridTool {
   ClearGrid,
@@ -40,6 +95,7 @@ pub enum GridTool {
   HeightIncrementor,
   WidthDecrementor,
   WidthIncrementor,
+  Resize(u16, u16, Anchor),
 }
 
 /// Grid cells' content setters
@@ -50,7 +106,7 @@ pub enum CellTool {
 }
 
 /// Tool types
-#[derive(Clone, Copy, Eq, PartialEq)]
+#[derive(Clone, Eq, PartialEq)]
 pub enum Tool {
   WorkspaceSetter(WorkspaceTool),
   GridSetter(GridTool),
@@ -1,4 +1,4 @@
-use crate::utils::FullPixel;
+use liriodendron::utils::FullPixel;
 
 /// Module to set and check <i>color</i> member of the brush
 pub mod brushcolor;
@@ -58,6 +58,14 @@ impl Brush {
     }
   }
 
+  /// Selects the brush action <i>index</i>, 4 being
+  /// <i>FullPixel::SpecificColor</i>
+  pub fn select(&mut self, index: usize) {
+    if index <= 4 {
+      self.current_action = index;
+    }
+  }
+
   pub fn previous(&mut self) {
     if self.current_action == 0 {
       self.current_action = 4;
@@ -66,6 +74,23 @@ impl Brush {
     }
   }
 
+  /// Returns the pixel following <i>pixel</i> in the brush actions' cycle,
+  /// or the first brush action for an empty cell
+  pub fn next_pixel(&self, pixel: Option<FullPixel>) -> FullPixel {
+    let (red, green, blue) = self.color;
+    match pixel {
+      None => self.pixels[0],
+      Some(FullPixel::SpecificColor(_, _, _)) => self.pixels[0],
+      Some(pixel) => {
+        match self.pixels.iter().position(|&p| p == pixel) {
+          Some(index) if index + 1 < self.pixels.len() =>
+            self.pixels[index + 1],
+          _ => FullPixel::SpecificColor(red, green, blue),
+        }
+      },
+    }
+  }
+
   pub fn get_color(&self) -> (u8, u8, u8) {
     self.color
   }
@@ -90,6 +115,10 @@ impl Brush {
     }
   }
 
+  pub fn set_body_de_ratio(&mut self, ratio: u8) {
+    self.body_de_ratio = ratio;
+  }
+
   pub fn get_body_dr_ratio(&self) -> u8 {
     self.body_dr_ratio
   }
@@ -100,6 +129,10 @@ impl Brush {
     }
   }
 
+  pub fn set_body_dr_ratio(&mut self, ratio: u8) {
+    self.body_dr_ratio = ratio;
+  }
+
   pub fn drcr_body_dr_ratio(&mut self) {
     if self.body_dr_ratio < 99 {
       self.body_dr_ratio += 1;
@@ -138,4 +171,16 @@ mod tests {
     assert!((init_color == (255, 255, 255)) &&
       (brush.get_color() == new_color));
   }
+
+  #[test]
+  fn it_cycles_the_pixel_of_a_cell() {
+    let brush = Brush::new();
+    let first = brush.next_pixel(None);
+    let second = brush.next_pixel(Some(first));
+    let last = brush.next_pixel(Some(FullPixel::Body));
+    assert!((first == FullPixel::BodyEmpty) &&
+      (second == FullPixel::BodyBorder) &&
+      (last == FullPixel::SpecificColor(255, 255, 255)) &&
+      (brush.next_pixel(Some(last)) == FullPixel::BodyEmpty));
+  }
 }
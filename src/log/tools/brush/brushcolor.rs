@@ -72,7 +72,7 @@ impl setter::Setter for BrushColor {
     }
     value.remove(relative_pos);
     value.insert(relative_pos, number);
-    if value.parse::<u16>().unwrap() <= self.max_value {
+    if value.parse::<u32>().unwrap() <= self.max_value {
       self.value[pos] = number;
       self.cursor_right();
     }
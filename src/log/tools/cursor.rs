@@ -0,0 +1,97 @@
+extern crate std;
+use std::cmp::min;
+
+/// Represents the cell cursor used to edit grids with the keyboard
+pub struct Cursor {
+
+  /// When <i>true</i>, the cursor is displayed and keyboard edits the grid
+  /// under it
+  enabled: bool,
+
+  x: u16,
+  y: u16,
+}
+
+impl Cursor {
+
+  pub fn new() -> Cursor {
+    Cursor {
+      enabled: false,
+      x: 0,
+      y: 0,
+    }
+  }
+
+  pub fn is_enabled(&self) -> bool {
+    self.enabled
+  }
+
+  /// Enables or disables the cursor. An enabled cursor starts on
+  /// <i>position</i>, which is usually the first visible cell.
+  pub fn toggle(&mut self, position: (u16, u16)) {
+    self.enabled = !self.enabled;
+    if self.enabled {
+      let (x, y) = position;
+      self.x = x;
+      self.y = y;
+    }
+  }
+
+  /// Returns the cursor position inside a grid of size <i>(width,
+  /// height)</i>
+  pub fn get_position(&self, (width, height): (u16, u16)) -> (u16, u16) {
+    (min(self.x, width - 1), min(self.y, height - 1))
+  }
+
+  pub fn up(&mut self, size: (u16, u16)) {
+    let (x, y) = self.get_position(size);
+    self.x = x;
+    self.y = y.saturating_sub(1);
+  }
+
+  pub fn down(&mut self, size: (u16, u16)) {
+    let (x, y) = self.get_position(size);
+    self.x = x;
+    self.y = min(y + 1, size.1 - 1);
+  }
+
+  pub fn left(&mut self, size: (u16, u16)) {
+    let (x, y) = self.get_position(size);
+    self.x = x.saturating_sub(1);
+    self.y = y;
+  }
+
+  pub fn right(&mut self, size: (u16, u16)) {
+    let (x, y) = self.get_position(size);
+    self.x = min(x + 1, size.0 - 1);
+    self.y = y;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+
+  #[test]
+  fn it_stays_inside_the_grid() {
+    let mut cursor = Cursor::new();
+    cursor.toggle((0, 0));
+    cursor.up((3, 3));
+    cursor.left((3, 3));
+    let top_left = cursor.get_position((3, 3));
+    for _ in 0..5 {
+      cursor.right((3, 3));
+      cursor.down((3, 3));
+    }
+    assert!((top_left == (0, 0)) && (cursor.get_position((3, 3)) == (2, 2)));
+  }
+
+  #[test]
+  fn it_fits_a_smaller_grid() {
+    let mut cursor = Cursor::new();
+    cursor.toggle((8, 8));
+    cursor.left((5, 10));
+    assert!(cursor.get_position((5, 10)) == (3, 8));
+  }
+}
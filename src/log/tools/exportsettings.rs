@@ -0,0 +1,43 @@
+use liriodendron::template::MaskFormat;
+
+const NB_OPTIONS: usize = 4;
+
+/// Represents grids' export parameters
+pub struct ExportSettings {
+
+  /// Tuples <i>(all grids are exported, format of the exported files)</i>
+  options: [(bool, MaskFormat); NB_OPTIONS],
+
+  current_option: usize,
+}
+
+impl ExportSettings {
+
+  pub fn new() -> ExportSettings {
+    ExportSettings {
+      options: [(false, MaskFormat::Png), (true, MaskFormat::Png),
+        (false, MaskFormat::Text), (true, MaskFormat::Text)],
+      current_option: 0,
+    }
+  }
+
+  pub fn get_option(&self) -> (bool, MaskFormat) {
+    self.options[self.current_option]
+  }
+
+  pub fn next_option(&mut self) {
+    if self.current_option > 0 {
+      self.current_option -= 1;
+    }
+  }
+
+  pub fn previous_option(&mut self) {
+    if self.current_option < self.options.len() - 1 {
+      self.current_option += 1;
+    }
+  }
+
+  pub fn get_option_info(&self) -> ([(bool, MaskFormat); NB_OPTIONS], usize) {
+    (self.options, self.current_option)
+  }
+}
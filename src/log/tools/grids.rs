@@ -1,23 +1,32 @@
 extern crate std;
 use std::cmp::min;
 use std::collections::HashMap;
-use std::convert::{TryFrom, TryInto};
 
-use crate::utils::FullPixel;
+use liriodendron::error::Error;
+use liriodendron::template::Template;
+use liriodendron::utils::FullPixel;
 use crate::log::{action,
-  tools::{Pixel, Grid, GridTool, MAX_SIZE, Tool, WorkspaceTool}};
+  tools::{Anchor, Pixel, Grid, GridTool, Tool, WorkspaceTool}};
 
 /// Module for grids' cells
 mod cell;
 
+/// Module for the storage of each grid's filled cells
+mod layer;
+
 pub mod gridname;
+pub mod gridsize;
+
+pub use layer::Change;
 
 const DEFAULT_GRID_SIZE: u16 = 10;
 const SIZE_LIMIT: u16 = 512;
-const MAX_GRIDS: usize = 10;
 
 const OVERFLOW_OCCURED: bool = true;
 
+/// Name of an imported grid whose template name has no valid character
+const DEFAULT_NAME: &str = "Grid";
+
 /// Represents <i>user interface</i> set of grids
 #[derive(Clone)]
 pub struct Grids {
@@ -27,9 +36,17 @@ pub struct Grids {
   /// Names of grids
   names: Vec<String>,
 
-  /// If an error occured during an operation, a <i>String</i> is made to be
-  /// displayed on the graphic interface
-  error: String,
+  /// Grids hidden in the <i>user interface</i>, and grids whose cells can
+  /// not be edited
+  hidden: Vec<bool>,
+  locked: Vec<bool>,
+
+  /// When <i>true</i>, hidden grids are not generated
+  exclude_hidden: bool,
+
+  /// If an error occured during an operation, it is kept to be displayed
+  /// on the graphic interface
+  error: Option<Error>,
 
   height: u16,
   width: u16,
@@ -42,73 +59,177 @@ pub struct Grids {
   /// area is smaller than the grid
   scroll_y: u16,
 
-  /// Grids' filled cells
-  state: HashMap<cell::Cell, FullPixel>,
+  /// Grids' filled cells, one layer per grid
+  state: Vec<layer::Layer>,
 
-  /// Customizable member
+  /// Customizable member. The user is warned when the number of filled cells
+  /// exceeds it.
   map_capacity: usize,
 
   /// Determines which grid will be swapped with the current grid
   switch_cursor: usize,
+
+  /// Size of the <i>user interface</i> grid area, saved by
+  /// <i>check_scroll</i>
+  area: (u16, u16),
+
+  /// Determines the visible part of the tabs when <i>user interface</i> tabs
+  /// area is smaller than the number of grids
+  tabs_scroll: usize,
+
+  /// Customizable member
+  max_grids: usize,
 }
 
 pub fn size_limit() -> u16 {
   return SIZE_LIMIT
 }
 
+/// Returns <i>true</i> for the characters of a grid name, the ones which
+/// can be typed in the grid name popup
+fn is_name_character(character: char) -> bool {
+  character.is_ascii_alphanumeric()
+}
+
 impl Grids {
 
-  pub fn new(map_capacity: usize) -> Grids {
+  pub fn new(map_capacity: usize, max_grids: usize) -> Grids {
     let mut grids = Grids {
       current_grid_id: 0,
       current_grid: Grid::Generation,
-      names: Vec::with_capacity(MAX_GRIDS),
-      error: String::new(),
+      names: Vec::with_capacity(max_grids),
+      hidden: Vec::with_capacity(max_grids),
+      locked: Vec::with_capacity(max_grids),
+      exclude_hidden: false,
+      error: None,
       height: DEFAULT_GRID_SIZE,
       width: DEFAULT_GRID_SIZE,
       scroll_x: 0,
       scroll_y: 0,
-      state: HashMap::with_capacity(map_capacity),
+      state: Vec::with_capacity(max_grids),
       map_capacity: map_capacity,
       switch_cursor: 0,
+      area: (0, 0),
+      tabs_scroll: 0,
+      max_grids,
     };
-    let grid_name =
-      grids.check_name(String::from("Grid1")).unwrap().iter().collect();
+    let grid_name = grids.check_name(String::from("Grid1")).unwrap();
     grids.add(grid_name);
     grids
   }
 
-  pub fn get_current_grid_info(&self) -> (Grid, usize) {
-    (self.current_grid, self.current_grid_id)
+  /// Replaces grids by <i>templates</i>. Grids take the size of the largest
+  /// template.
+  pub fn import(&mut self, templates: Vec<Template>)
+    -> Result<(), Error> {
+      if templates.is_empty() {
+        return Ok(())
+      } else if templates.len() > self.max_grids {
+        return Err(Error::Overflow(format!("imported images have {} layers \
+          but the maximum number of grids is {}.", templates.len(),
+          self.max_grids)))
+      }
+      if let Some(template) = templates.iter()
+        .find(|t| (t.width > SIZE_LIMIT) || (t.height > SIZE_LIMIT)) {
+          return Err(Error::Overflow(format!("{} is larger than {}x{} \
+            pixels.", template.name, SIZE_LIMIT, SIZE_LIMIT)))
+      }
+      self.names.clear();
+      self.hidden.clear();
+      self.locked.clear();
+      self.state.clear();
+      self.width = templates.iter().map(|t| t.width).max().unwrap().max(1);
+      self.height = templates.iter().map(|t| t.height).max().unwrap().max(1);
+      for template in templates {
+        let mut stem = template.name.chars()
+          .filter(|&character| is_name_character(character))
+          .collect::<String>();
+        if stem.is_empty() {
+          stem = String::from(DEFAULT_NAME);
+        }
+        let mut name = stem.chars().take(super::MAX_SIZE).collect::<String>();
+        let mut index = 1;
+        while self.names.contains(&name) {
+          index += 1;
+          let suffix = index.to_string();
+          name = stem.chars()
+            .take(super::MAX_SIZE - suffix.len()).collect::<String>() + &suffix;
+        }
+        self.add(name);
+        *self.hidden.last_mut().unwrap() = template.hidden;
+        *self.locked.last_mut().unwrap() = template.locked;
+        let size = (self.width, self.height);
+        let layer = self.state.last_mut().unwrap();
+        for (x, y, pixel) in template.cells {
+          layer.insert((x, y), pixel, size);
+        }
+      }
+      self.resize_layers();
+      self.current_grid_id = 0;
+      Ok(())
   }
 
-  pub fn is_on_current_grid(&self, id: usize, grid: Grid) -> bool {
-    (id == self.current_grid_id) && (grid == self.current_grid)
+  pub fn get_current_grid_info(&self) -> (Grid, usize) {
+    (self.current_grid, self.current_grid_id)
   }
 
   pub fn get_length_capacity_state(&self) -> (usize, usize) {
-    (self.state.len(), self.map_capacity)
+    (self.get_nb_filledcells(), self.map_capacity)
   }
 
   /// Returns current grid's filled cells in a Vec of tuples <i>(cell content,
   /// cell X coordinate, cell Y coordinate)</i>
   pub fn get_current_grid(&self) -> Vec<(FullPixel, u16, u16)> {
-    self.state.iter()
-      .filter(|&(c, _)| self.is_on_current_grid(c.get_grid_id(), c.get_grid()))
-      .map(|(&c, &pixel)| (pixel, c.get_x(), c.get_y()))
+    self.state[self.current_grid_id].get_cells().into_iter()
+      .map(|(x, y, pixel)| (pixel, x, y))
       .collect()
   }
 
+  /// Returns the current grid, or every grid when <i>all</i> is
+  /// <i>true</i>, as templates
+  pub fn get_templates(&self, all: bool) -> Vec<Template> {
+    let ids = if all {
+      0..self.names.len()
+    } else {
+      self.current_grid_id..self.current_grid_id + 1
+    };
+    ids.map(|id| Template {
+      name: self.names[id].clone(),
+      width: self.width,
+      height: self.height,
+      cells: self.state[id].get_cells(),
+      hidden: self.hidden[id],
+      locked: self.locked[id],
+    }).collect()
+  }
+
   pub fn get_names(&self) -> Vec<String> {
     self.names.clone()
   }
 
+  pub fn get_hidden(&self) -> Vec<bool> {
+    self.hidden.clone()
+  }
+
+  pub fn get_locked(&self) -> Vec<bool> {
+    self.locked.clone()
+  }
+
+  pub fn is_locked(&self, grid_id: usize) -> bool {
+    self.locked[grid_id]
+  }
+
+  /// Sets whether hidden grids are generated, before the first action
+  pub fn set_exclude_hidden(&mut self, exclude_hidden: bool) {
+    self.exclude_hidden = exclude_hidden;
+  }
+
   pub fn get_nb(&self) -> usize {
     self.names.len()
   }
 
   pub fn is_full(&self) -> bool {
-    self.names.len() >= MAX_GRIDS
+    self.names.len() >= self.max_grids
   }
 
   pub fn is_alone(&self) -> bool {
@@ -116,15 +237,14 @@ impl Grids {
   }
 
   fn clear_current_grid(&mut self) {
-    let current_grid = self.current_grid;
-    let current_grid_id = self.current_grid_id;
-    self.state.retain(|key, _|
-      !((key.get_grid_id() == current_grid_id) &&
-      (key.get_grid() == current_grid)));
+    self.state[self.current_grid_id].clear();
   }
 
   fn add(&mut self, name: String) {
     self.names.push(name);
+    self.hidden.push(false);
+    self.locked.push(false);
+    self.state.push(layer::Layer::new());
     self.current_grid_id = self.names.len() - 1;
   }
 
@@ -132,18 +252,12 @@ impl Grids {
     self.names[self.current_grid_id].clone_from(&name);
   }
 
-  /// Clears content of the current grid, deletes it and decrements the grid
-  /// id of cells with a grid id greater than the deleted grid
+  /// Deletes the current grid and its content
   fn delete_current_grid(&mut self) {
-    self.clear_current_grid();
     self.names.remove(self.current_grid_id);
-    self.state = self.state.iter().map(|(&cell, &pixel)| {
-      if cell.get_grid_id() > self.current_grid_id {
-        (cell.set_grid_id(cell.get_grid_id() - 1), pixel)
-      } else {
-        (cell, pixel)
-      }
-    }).collect();
+    self.hidden.remove(self.current_grid_id);
+    self.locked.remove(self.current_grid_id);
+    self.state.remove(self.current_grid_id);
     if self.current_grid_id >= self.names.len() {
       self.current_grid_id -= 1;
     }
@@ -160,16 +274,10 @@ impl Grids {
     if switched_grid > self.current_grid_id {
       switched_grid += 1;
     }
-    self.state = self.state.iter().map(|(&cell, &pixel)| {
-      if cell.get_grid_id() == self.current_grid_id {
-        (cell.set_grid_id(switched_grid), pixel)
-      } else if cell.get_grid_id() == switched_grid {
-        (cell.set_grid_id(self.current_grid_id), pixel)
-      } else {
-        (cell, pixel)
-      }
-    }).collect();
+    self.state.swap(self.current_grid_id, switched_grid);
     self.names.swap(self.current_grid_id, switched_grid);
+    self.hidden.swap(self.current_grid_id, switched_grid);
+    self.locked.swap(self.current_grid_id, switched_grid);
   }
 
   pub fn get_switch_cursor(&self) -> usize {
@@ -191,35 +299,34 @@ impl Grids {
     }
   }
 
-  /// Chechs if the name typed by the user have a length between 1 and 16 and
-  /// if the name is not already used
-  pub fn check_name(&mut self, mut name: String) -> Option<[char; MAX_SIZE]> {
+  /// Chechs if the name typed by the user is not empty and if the name is not
+  /// already used. The name length is bounded by the <i>gridname</i> setter.
+  pub fn check_name(&mut self, name: String) -> Option<String> {
     let res;
     if name.is_empty() {
-      self.error = String::from("Grid name is empty");
+      self.error = Some(Error::GridName(String::from("Grid name is empty")));
+      res = None;
+    } else if !name.chars().all(is_name_character) {
+      self.error = Some(Error::GridName(String::from(
+        "Grid name has other characters than a-z, A-Z and 0-9")));
+      res = None;
+    } else if self.names.iter().any(|n| n.eq(&name)) {
+      self.error = Some(Error::GridName(
+        String::from("This grid name is already used")));
       res = None;
     } else {
-      while name.len() < MAX_SIZE {
-        name.push(' ');
-      }
-      if self.names.iter().any(|n| n.eq(&name)) {
-        self.error = String::from("This grid name is already used");
-        res = None;
-      } else {
-        self.error.clear();
-        res =
-          Some((&name.chars().collect::<Vec<char>>()[..]).try_into().unwrap());
-      }
+      self.error = None;
+      res = Some(name);
     }
     res
   }
 
   pub fn error_occured(&self) -> bool {
-    self.error.len() > 0
+    self.error.is_some()
   }
 
-  pub fn get_error(&self) -> String {
-    self.error.clone()
+  pub fn get_error(&self) -> Option<&Error> {
+    self.error.as_ref()
   }
 
 
@@ -230,6 +337,13 @@ impl Grids {
     }
   }
 
+  /// Makes the grid <i>id</i> the current one if it exists
+  pub fn select(&mut self, id: usize) {
+    if id < self.names.len() {
+      self.current_grid_id = id;
+    }
+  }
+
   pub fn previous(&mut self) {
     if self.current_grid_id == 0 {
       self.current_grid_id = self.names.len() - 1;
@@ -239,58 +353,78 @@ impl Grids {
   }
 
   pub fn get_nb_filledcells_current_grid(&self) -> usize {
-    self.state.len()
+    self.state[self.current_grid_id].len()
+  }
+
+  pub fn get_nb_filledcells(&self) -> usize {
+    self.state.iter().map(|layer| layer.len()).sum()
   }
 
   /// Returns grids' filled cells in a HashMap of key-tuples
   /// <i>(cell X coordinate, cell Y coordinate)</i> and value-tuples
-  /// <i>(grid ID, cell content)</i>
+  /// <i>(grid ID, cell content)</i>, without the hidden grids when they are
+  /// not generated
   pub fn get_grids(&self) -> HashMap<(u16, u16), (usize, FullPixel)> {
-    let mut grids = HashMap::<(u16, u16), (usize, FullPixel)>::with_capacity(
-      usize::try_from(self.height * self.width).unwrap());
-    let mut current_grid: HashMap<(u16, u16), (usize, FullPixel)>;
-    let mut current_grid_id: usize = self.names.len();
-    loop {
-      current_grid = self.state.iter()
-        .filter_map(|(&cell, &pixel)| match cell.get_grid_id() {
-                      id if id == (current_grid_id - 1) =>
-                        Some(((cell.get_x(), cell.get_y()), (id, pixel))),
-                      _ => None,
-                    }
-        ).collect();
-      grids.extend(current_grid);
-      current_grid_id -= 1;
-      if current_grid_id == 0 {
-        break;
+    self.overlay(!self.exclude_hidden)
+  }
+
+  /// Returns the filled cells of the grids shown in the <i>user
+  /// interface</i>, as <i>get_grids</i>
+  pub fn get_visible_grids(&self) -> HashMap<(u16, u16), (usize, FullPixel)> {
+    self.overlay(false)
+  }
+
+  /// A cell of a grid hides the cells of the grids with a greater ID
+  fn overlay(&self, hidden: bool)
+    -> HashMap<(u16, u16), (usize, FullPixel)> {
+      let mut grids =
+        HashMap::<(u16, u16), (usize, FullPixel)>::with_capacity(
+          usize::from(self.height) * usize::from(self.width));
+      let mut current_grid_id: usize = self.names.len();
+      loop {
+        let id = current_grid_id - 1;
+        if hidden || !self.hidden[id] {
+          grids.extend(self.state[id].get_cells().into_iter()
+            .map(|(x, y, pixel)| ((x, y), (id, pixel))));
+        }
+        current_grid_id -= 1;
+        if current_grid_id == 0 {
+          break;
+        }
       }
-    }
-    grids
+      grids
   }
 
   /// Updates <i>state</i> depending of <i>action</i>. Returns the old value if
-  /// <i>action</i> deletes of modifies a value in <i>state</i>. Returns a bool
-  /// for the <i>user interface</i> if <i>action</i> inserts and overflows
-  /// <i>state</i> capacity.
-  pub fn update(&mut self, action: &action::Action) ->
-    (Option<FullPixel>, bool) {
+  /// <i>action</i> deletes of modifies a value in <i>state</i>, or
+  /// <i>Change::Outside</i> if its cell is outside of the grid. Returns a bool
+  /// for the <i>user interface</i> if <i>action</i> inserts a value and the
+  /// number of filled cells exceeds <i>map_capacity</i> for the first time.
+  pub fn update(&mut self, action: &action::Action) -> (Change, bool) {
 
       match action.get_tool() {
         Tool::CellSetter(tool) => {
           self.current_grid_id = action.get_grid_id();
           let (key, value) =
             cell::Cell::new((action.get_grid_id(), action.get_grid(), tool));
-          match value {
-            Pixel::Empty => (self.state.remove(&key), !OVERFLOW_OCCURED),
+          let (width, height) = (self.width, self.height);
+          let layer = &mut self.state[key.get_grid_id()];
+          let res = match value {
+            Pixel::Empty => (layer.remove((key.get_x(), key.get_y()),
+              (width, height)), !OVERFLOW_OCCURED),
             Pixel::Full(content) => {
-              if (self.state.len() < self.map_capacity) ||
-                ((self.state.len() >= self.map_capacity) &&
-                self.state.contains_key(&key)) {
-                  return (self.state.insert(key, content), !OVERFLOW_OCCURED);
+              let change = layer.insert((key.get_x(), key.get_y()), content,
+                (width, height));
+              if (change == Change::Set(None)) &&
+                (self.get_nb_filledcells() == self.map_capacity + 1) {
+                  (change, OVERFLOW_OCCURED)
               } else {
-                return (None, OVERFLOW_OCCURED);
+                (change, !OVERFLOW_OCCURED)
               }
             },
-          }
+          };
+          self.state[key.get_grid_id()].check_density(width, height);
+          res
         },
         Tool::GridSetter(tool) => {
           match tool {
@@ -310,17 +444,20 @@ impl Grids {
             GridTool::HeightDecrementor => {
               self.decrement_height();
             },
+            GridTool::Resize(width, height, anchor) => {
+              self.resize((width, height), anchor);
+            },
           };
-          return (None, !OVERFLOW_OCCURED);
+          return (Change::Set(None), !OVERFLOW_OCCURED);
         },
         Tool::WorkspaceSetter(tool) => {
           match tool {
             WorkspaceTool::AddGrid(name) => {
-              self.add(name.iter().collect());
+              self.add(name);
             },
             WorkspaceTool::RenameGrid(name) => {
               self.current_grid_id = action.get_grid_id();
-              self.rename_current_grid(name.iter().collect());
+              self.rename_current_grid(name);
             },
             WorkspaceTool::DeleteGrid => {
               self.current_grid_id = action.get_grid_id();
@@ -330,8 +467,18 @@ impl Grids {
               self.current_grid_id = action.get_grid_id();
               self.switch_current_grid(switched_grid);
             },
+            WorkspaceTool::ToggleVisibility => {
+              self.current_grid_id = action.get_grid_id();
+              self.hidden[self.current_grid_id] =
+                !self.hidden[self.current_grid_id];
+            },
+            WorkspaceTool::ToggleLock => {
+              self.current_grid_id = action.get_grid_id();
+              self.locked[self.current_grid_id] =
+                !self.locked[self.current_grid_id];
+            },
           };
-          return (None, !OVERFLOW_OCCURED);
+          return (Change::Set(None), !OVERFLOW_OCCURED);
         },
       }
   }
@@ -344,31 +491,59 @@ impl Grids {
     self.height
   }
 
+  /// Sets the size of empty grids, before the first action
+  pub fn set_size(&mut self, width: u16, height: u16) {
+    self.width = width;
+    self.height = height;
+    self.resize_layers();
+  }
+
   fn increment_width(&mut self) {
     if self.width < SIZE_LIMIT {
       self.width += 1;
+      self.resize_layers();
     }
   }
 
   fn decrement_width(&mut self) {
     if self.width > 1 {
       self.width -= 1;
-      let width = self.width;
-      self.state.retain(|key, _| key.get_x() < width);
+      self.resize_layers();
     }
   }
 
   fn increment_height(&mut self) {
     if self.height < SIZE_LIMIT {
       self.height += 1;
+      self.resize_layers();
     }
   }
 
   fn decrement_height(&mut self) {
     if self.height > 1 {
       self.height -= 1;
-      let height = self.height;
-      self.state.retain(|key, _| key.get_y() < height);
+      self.resize_layers();
+    }
+  }
+
+  /// Resizes the grids to <i>(width, height)</i>, the existing cells staying
+  /// on the <i>anchor</i> side
+  fn resize(&mut self, (width, height): (u16, u16), anchor: Anchor) {
+    let (width, height) =
+      (width.clamp(1, SIZE_LIMIT), height.clamp(1, SIZE_LIMIT));
+    let offset = anchor.get_offset((self.width, self.height), (width, height));
+    self.width = width;
+    self.height = height;
+    for layer in self.state.iter_mut() {
+      layer.resize(width, height, offset);
+    }
+  }
+
+  /// Drops cells outside of the grids' size
+  fn resize_layers(&mut self) {
+    let (width, height) = (self.width, self.height);
+    for layer in self.state.iter_mut() {
+      layer.resize(width, height, (0, 0));
     }
   }
 
@@ -377,6 +552,7 @@ impl Grids {
   }
 
   pub fn check_scroll(&mut self, (area_width, area_height): &(u16, u16)) {
+    self.area = (*area_width, *area_height);
     if self.width <= *area_width {
       self.scroll_x = 0;
     } else {
@@ -389,6 +565,24 @@ impl Grids {
     }
   }
 
+  /// Scrolls the grid to <i>(x, y)</i>, inside the area of the last
+  /// <i>check_scroll</i>
+  pub fn set_scroll(&mut self, (x, y): (u16, u16)) {
+    self.scroll_x = x;
+    self.scroll_y = y;
+    let area = self.area;
+    self.check_scroll(&area);
+  }
+
+  pub fn get_area(&self) -> (u16, u16) {
+    self.area
+  }
+
+  /// Returns the content of a cell of the current grid
+  pub fn get_cell(&self, (x, y): (u16, u16)) -> Option<FullPixel> {
+    self.state[self.current_grid_id].get((x, y))
+  }
+
   pub fn scroll_up(&mut self) {
     if self.scroll_y > 0 {
       self.scroll_y -= 1;
@@ -408,6 +602,26 @@ impl Grids {
   pub fn scroll_right(&mut self) {
     self.scroll_x += 1;
   }
+
+  pub fn get_tabs_scroll(&self) -> usize {
+    self.tabs_scroll
+  }
+
+  /// Keeps the current grid's tab inside the <i>nb_visible_tabs</i> tabs
+  /// displayed by the <i>user interface</i>
+  pub fn check_tabs_scroll(&mut self, nb_visible_tabs: usize) {
+    if nb_visible_tabs == 0 || self.names.len() <= nb_visible_tabs {
+      self.tabs_scroll = 0;
+    } else {
+      if self.current_grid_id < self.tabs_scroll {
+        self.tabs_scroll = self.current_grid_id;
+      } else if self.current_grid_id >= self.tabs_scroll + nb_visible_tabs {
+        self.tabs_scroll = self.current_grid_id + 1 - nb_visible_tabs;
+      }
+      self.tabs_scroll =
+        min(self.tabs_scroll, self.names.len() - nb_visible_tabs);
+    }
+  }
 }
 
 #[cfg(test)]
@@ -418,7 +632,7 @@ mod tests {
 
   #[test]
   fn it_scrolls_right() {
-    let mut grids = Grids::new(0);
+    let mut grids = Grids::new(0, 10);
     let init_scroll = grids.get_scroll();
     grids.scroll_right();
     assert!((init_scroll == (0, 0)) && (grids.get_scroll() == (1, 0)));
@@ -426,7 +640,7 @@ mod tests {
 
   #[test]
   fn it_scrolls_left() {
-    let mut grids = Grids::new(0);
+    let mut grids = Grids::new(0, 10);
     grids.scroll_right();
     let init_scroll = grids.get_scroll();
     grids.scroll_left();
@@ -437,7 +651,7 @@ mod tests {
 
   #[test]
   fn it_scrolls_down() {
-    let mut grids = Grids::new(0);
+    let mut grids = Grids::new(0, 10);
     let init_scroll = grids.get_scroll();
     grids.scroll_down();
     assert!((init_scroll == (0, 0)) && (grids.get_scroll() == (0, 1)));
@@ -445,7 +659,7 @@ mod tests {
 
   #[test]
   fn it_scrolls_up() {
-    let mut grids = Grids::new(0);
+    let mut grids = Grids::new(0, 10);
     grids.scroll_down();
     let init_scroll = grids.get_scroll();
     grids.scroll_up();
@@ -456,7 +670,7 @@ mod tests {
 
   #[test]
   fn it_scrolls_in_each_direction_but_area_is_bigger_than_grid() {
-    let mut grids = Grids::new(0);
+    let mut grids = Grids::new(0, 10);
     let area = &(12, 12);
     let init_scroll = grids.get_scroll();
     grids.scroll_right();
@@ -477,7 +691,7 @@ mod tests {
 
   #[test]
   fn it_scrolls_2_times_on_the_right_but_grid_is_only_1_cell_wider_than_area() {
-    let mut grids = Grids::new(0);
+    let mut grids = Grids::new(0, 10);
     let area = &(9, 10);
     let init_scroll = grids.get_scroll();
     grids.scroll_right();
@@ -491,7 +705,7 @@ mod tests {
 
   #[test]
   fn it_scrolls_2_times_on_the_down_but_grid_is_only_1_cell_higher_than_area() {
-    let mut grids = Grids::new(0);
+    let mut grids = Grids::new(0, 10);
     let area = &(10, 9);
     let init_scroll = grids.get_scroll();
     grids.scroll_down();
@@ -505,7 +719,7 @@ mod tests {
 
   #[test]
   fn it_increments_height() {
-    let mut grids = Grids::new(0);
+    let mut grids = Grids::new(0, 10);
     let init_height = grids.get_grid_height();
     grids.increment_height();
     let second_height = grids.get_grid_height();
@@ -519,7 +733,7 @@ mod tests {
 
   #[test]
   fn it_increments_width() {
-    let mut grids = Grids::new(0);
+    let mut grids = Grids::new(0, 10);
     let init_width = grids.get_grid_width();
     grids.increment_width();
     let second_width = grids.get_grid_width();
@@ -533,7 +747,7 @@ mod tests {
 
   #[test]
   fn it_decrements_height() {
-    let mut grids = Grids::new(0);
+    let mut grids = Grids::new(0, 10);
     let init_height = grids.get_grid_height();
     grids.decrement_height();
     let second_height = grids.get_grid_height();
@@ -547,7 +761,7 @@ mod tests {
 
   #[test]
   fn it_decrements_width() {
-    let mut grids = Grids::new(0);
+    let mut grids = Grids::new(0, 10);
     let init_width = grids.get_grid_width();
     grids.decrement_width();
     let second_width = grids.get_grid_width();
@@ -561,62 +775,62 @@ mod tests {
 
   #[test]
   fn it_updates_state_with_one_brush_action_one_missed_eraser_action_and_one_eraser_action() {
-    let mut grids = Grids::new(1);
-    let init_state_len = grids.state.len();
+    let mut grids = Grids::new(1, 10);
+    let init_state_len = grids.get_nb_filledcells();
     grids.update(&action::Action::new(grids.get_current_grid_info(),
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::Body, 1, 1))));
-    let second_state_len = grids.state.len();
+    let second_state_len = grids.get_nb_filledcells();
     grids.update(&action::Action::new(grids.get_current_grid_info(),
       tools::Tool::CellSetter(tools::CellTool::PixelEraser(0, 1))));
-    let third_state_len = grids.state.len();
+    let third_state_len = grids.get_nb_filledcells();
     grids.update(&action::Action::new(grids.get_current_grid_info(),
       tools::Tool::CellSetter(tools::CellTool::PixelEraser(1, 1))));
     assert!((init_state_len == 0) && (second_state_len == 1) &&
-      (third_state_len == 1) && (grids.state.len() == 0));
+      (third_state_len == 1) && (grids.get_nb_filledcells() == 0));
   }
 
   #[test]
   fn it_brushes_a_filled_cell_with_another_brush_and_update_function_does_not_return_overflow() {
-    let mut grids = Grids::new(2);
-    let init_state_len = grids.state.len();
+    let mut grids = Grids::new(2, 10);
+    let init_state_len = grids.get_nb_filledcells();
     grids.update(&action::Action::new(grids.get_current_grid_info(),
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::Body, 1, 1))));
-    let second_state_len = grids.state.len();
+    let second_state_len = grids.get_nb_filledcells();
     grids.update(&action::Action::new(grids.get_current_grid_info(),
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::Body, 2, 1))));
-    let third_state_len = grids.state.len();
+    let third_state_len = grids.get_nb_filledcells();
     let (_, overflow) = grids.update(&action::Action::new(
       grids.get_current_grid_info(), tools::Tool::CellSetter(
         tools::CellTool::PixelBrush(FullPixel::Border, 2, 1))));
     assert!((init_state_len == 0) && (second_state_len == 1) &&
-      (third_state_len == 2) && (grids.state.len() == 2) && !overflow);
+      (third_state_len == 2) && (grids.get_nb_filledcells() == 2) && !overflow);
   }
 
   #[test]
   fn it_brushes_an_empty_cell_and_update_function_returns_overflow() {
-    let mut grids = Grids::new(2);
-    let init_state_len = grids.state.len();
+    let mut grids = Grids::new(2, 10);
+    let init_state_len = grids.get_nb_filledcells();
     grids.update(&action::Action::new(grids.get_current_grid_info(),
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::Body, 1, 1))));
-    let second_state_len = grids.state.len();
+    let second_state_len = grids.get_nb_filledcells();
     grids.update(&action::Action::new(grids.get_current_grid_info(),
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::Body, 2, 1))));
-    let third_state_len = grids.state.len();
+    let third_state_len = grids.get_nb_filledcells();
     let (_, overflow) = grids.update(&action::Action::new(
       grids.get_current_grid_info(), tools::Tool::CellSetter(
         tools::CellTool::PixelBrush(FullPixel::Border, 1, 2))));
     assert!((init_state_len == 0) && (second_state_len == 1) &&
-      (third_state_len == 2) && (grids.state.len() == 2) && overflow);
+      (third_state_len == 2) && (grids.get_nb_filledcells() == 3) && overflow);
   }
 
   #[test]
   fn it_get_correct_grid_after_one_brush_action() {
-    let mut grids = Grids::new(1);
+    let mut grids = Grids::new(1, 10);
     let mut current_grid = grids.get_current_grid();
     let len_init_current_grid = current_grid.len();
     grids.update(&action::Action::new(grids.get_current_grid_info(),
@@ -632,7 +846,7 @@ mod tests {
 
   #[test]
   fn it_get_correct_grid_after_one_eraser_action() {
-    let mut grids = Grids::new(1);
+    let mut grids = Grids::new(1, 10);
     grids.update(&action::Action::new(grids.get_current_grid_info(),
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::Body, 1, 1))));
@@ -646,7 +860,7 @@ mod tests {
 
   #[test]
   fn it_get_correct_grid_after_one_missed_eraser_action() {
-    let mut grids = Grids::new(1);
+    let mut grids = Grids::new(1, 10);
     grids.update(&action::Action::new(grids.get_current_grid_info(),
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::Body, 1, 1))));
@@ -664,7 +878,7 @@ mod tests {
 
   #[test]
   fn it_get_correct_grid_after_one_clear_action() {
-    let mut grids = Grids::new(1);
+    let mut grids = Grids::new(1, 10);
     grids.update(&action::Action::new(grids.get_current_grid_info(),
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::Body, 1, 1))));
@@ -678,7 +892,7 @@ mod tests {
 
   #[test]
   fn it_goes_next_grid() {
-    let mut grids = Grids::new(1);
+    let mut grids = Grids::new(1, 10);
     let current_pos = grids.current_grid_id;
     grids.add(String::from("grid2"));
     grids.add(String::from("grid3"));
@@ -692,9 +906,28 @@ mod tests {
       && (current_pos3 == 1) && (grids.current_grid_id == 2));
   }
 
+  #[test]
+  fn it_scrolls_tabs_to_keep_the_current_grid_visible() {
+    let mut grids = Grids::new(1, 10);
+    grids.add(String::from("grid2"));
+    grids.add(String::from("grid3"));
+    grids.add(String::from("grid4"));
+    grids.check_tabs_scroll(2);
+    let first_scroll = grids.get_tabs_scroll();
+    grids.next();
+    grids.check_tabs_scroll(2);
+    let second_scroll = grids.get_tabs_scroll();
+    grids.previous();
+    grids.check_tabs_scroll(2);
+    let third_scroll = grids.get_tabs_scroll();
+    grids.check_tabs_scroll(4);
+    assert!((first_scroll == 2) && (second_scroll == 0) &&
+      (third_scroll == 2) && (grids.get_tabs_scroll() == 0));
+  }
+
   #[test]
   fn it_goes_previous_grid() {
-    let mut grids = Grids::new(1);
+    let mut grids = Grids::new(1, 10);
     let current_pos = grids.current_grid_id;
     grids.add(String::from("grid2"));
     grids.add(String::from("grid3"));
@@ -710,42 +943,42 @@ mod tests {
 
   #[test]
   fn it_checks_an_empty_grid_name() {
-    let mut grids = Grids::new(1);
+    let mut grids = Grids::new(1, 10);
     grids.check_name(String::new());
     assert!(grids.error_occured() && (grids.names.len() == 1) &&
-      grids.error.eq(&String::from("Grid name is empty")));
+      (grids.error.as_ref().map(|error| error.to_string()) ==
+        Some(String::from("Grid name is empty"))));
   }
 
   #[test]
   fn it_checks_a_correct_grid_name() {
-    let mut grids = Grids::new(1);
-    let name: String =
-      grids.check_name(String::from("0")).unwrap().iter().collect();
+    let mut grids = Grids::new(1, 10);
+    let name = grids.check_name(String::from("0")).unwrap();
     grids.add(name);
     assert!(!grids.error_occured() && (grids.names.len() == 2) &&
-      grids.error.eq(&String::new()));
+      grids.error.is_none());
   }
 
   #[test]
   fn it_checks_an_used_grid_name() {
-    let mut grids = Grids::new(1);
-    let name: String =
-      grids.check_name(String::from("0")).unwrap().iter().collect();
+    let mut grids = Grids::new(1, 10);
+    let name = grids.check_name(String::from("0")).unwrap();
     grids.add(name.clone());
     grids.check_name(name);
     assert!(grids.error_occured() && (grids.names.len() == 2) &&
-      grids.error.eq(&String::from("This grid name is already used")));
+      (grids.error.as_ref().map(|error| error.to_string()) ==
+        Some(String::from("This grid name is already used"))));
   }
 
   #[test]
   fn it_get_correct_grids_after_brush_actions_on_same_cells_but_on_different_grids() {
-    let mut grids = Grids::new(6);
+    let mut grids = Grids::new(6, 10);
     grids.update(&action::Action::new(grids.get_current_grid_info(),
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::Body, 1, 1))));
     grids.update(&action::Action::new(grids.get_current_grid_info(),
-      tools::Tool::WorkspaceSetter(tools::WorkspaceTool::AddGrid(['0', '0',
-      '0', '0', '0', '0', '0', '0', '0', '0', '0', '0', '0', '0', '0', '0',]))));
+      tools::Tool::WorkspaceSetter(tools::WorkspaceTool::AddGrid(
+      String::from("0")))));
     grids.update(&action::Action::new(grids.get_current_grid_info(),
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::Border, 1, 1))));
@@ -753,8 +986,8 @@ mod tests {
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::Body, 2, 2))));
     grids.update(&action::Action::new(grids.get_current_grid_info(),
-      tools::Tool::WorkspaceSetter(tools::WorkspaceTool::AddGrid(['1', '1',
-      '1', '1', '1', '1', '1', '1', '1', '1', '1', '1', '1', '1', '1', '1',]))));
+      tools::Tool::WorkspaceSetter(tools::WorkspaceTool::AddGrid(
+      String::from("1")))));
     grids.update(&action::Action::new(grids.get_current_grid_info(),
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::BodyBorder, 1, 1))));
@@ -773,7 +1006,7 @@ mod tests {
 
   #[test]
   fn it_decrements_grid_id_of_cells_greater_than_the_deleted_grid_id() {
-    let mut grids = Grids::new(6);
+    let mut grids = Grids::new(6, 10);
     grids.update(&action::Action::new(grids.get_current_grid_info(),
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::Body, 1, 1))));
@@ -781,8 +1014,8 @@ mod tests {
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::Body, 1, 2))));
     grids.update(&action::Action::new(grids.get_current_grid_info(),
-      tools::Tool::WorkspaceSetter(tools::WorkspaceTool::AddGrid(['0', '0',
-      '0', '0', '0', '0', '0', '0', '0', '0', '0', '0', '0', '0', '0', '0',]))));
+      tools::Tool::WorkspaceSetter(tools::WorkspaceTool::AddGrid(
+      String::from("0")))));
     grids.update(&action::Action::new(grids.get_current_grid_info(),
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::Body, 2, 2))));
@@ -790,8 +1023,8 @@ mod tests {
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::Body, 2, 3))));
     grids.update(&action::Action::new(grids.get_current_grid_info(),
-      tools::Tool::WorkspaceSetter(tools::WorkspaceTool::AddGrid(['1', '1',
-      '1', '1', '1', '1', '1', '1', '1', '1', '1', '1', '1', '1', '1', '1',]))));
+      tools::Tool::WorkspaceSetter(tools::WorkspaceTool::AddGrid(
+      String::from("1")))));
     grids.update(&action::Action::new(grids.get_current_grid_info(),
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::Body, 3, 3))));
@@ -815,7 +1048,7 @@ mod tests {
 
   #[test]
   fn it_switches_grids_correctly() {
-    let mut grids = Grids::new(6);
+    let mut grids = Grids::new(6, 10);
     grids.update(&action::Action::new(grids.get_current_grid_info(),
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::Body, 1, 1))));
@@ -826,11 +1059,11 @@ mod tests {
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::Body, 1, 3))));
     grids.update(&action::Action::new(grids.get_current_grid_info(),
-      tools::Tool::WorkspaceSetter(tools::WorkspaceTool::AddGrid(['0', '0',
-      '0', '0', '0', '0', '0', '0', '0', '0', '0', '0', '0', '0', '0', '0',]))));
+      tools::Tool::WorkspaceSetter(tools::WorkspaceTool::AddGrid(
+      String::from("0")))));
     grids.update(&action::Action::new(grids.get_current_grid_info(),
-      tools::Tool::WorkspaceSetter(tools::WorkspaceTool::AddGrid(['1', '1',
-      '1', '1', '1', '1', '1', '1', '1', '1', '1', '1', '1', '1', '1', '1',]))));
+      tools::Tool::WorkspaceSetter(tools::WorkspaceTool::AddGrid(
+      String::from("1")))));
     grids.update(&action::Action::new(grids.get_current_grid_info(),
       tools::Tool::CellSetter(tools::CellTool::PixelBrush(
         FullPixel::Body, 2, 2))));
@@ -861,4 +1094,95 @@ mod tests {
     assert!(check_before_switch && check_after_switch &&
       (map_grids_before.len() == 6) && (map_grids_after.len() == 6));
   }
+
+  #[test]
+  fn it_imports_templates_as_grids() {
+    let mut grids = Grids::new(1, 10);
+    let templates = vec![
+      Template { name: String::from("mask"), width: 4, height: 2,
+        cells: vec![(3, 1, FullPixel::Body)], hidden: false,
+        locked: false },
+      Template { name: String::from("mask"), width: 2, height: 6,
+        cells: vec![(0, 5, FullPixel::Border), (1, 5, FullPixel::Border)],
+        hidden: true, locked: false },
+    ];
+    grids.import(templates).unwrap();
+    assert!((grids.get_names() == vec![String::from("mask"),
+      String::from("mask2")]) && (grids.get_grid_width() == 4) &&
+      (grids.get_grid_height() == 6) && (grids.get_nb_filledcells() == 3) &&
+      (grids.get_current_grid_info().1 == 0) &&
+      (grids.get_hidden() == vec![false, true]));
+  }
+
+  #[test]
+  fn it_imports_template_names_with_the_characters_of_typed_names() {
+    let mut grids = Grids::new(1, 10);
+    let template = |name: &str| Template { name: String::from(name),
+      width: 2, height: 2, cells: Vec::new(), hidden: false, locked: false };
+    grids.import(vec![template("my mask-1"), template("my_mask 1"),
+      template("é!")]).unwrap();
+    let names = grids.get_names();
+    let typed = grids.check_name(String::from("a-b"));
+    assert!((names == vec![String::from("mymask1"), String::from("mymask12"),
+      String::from("Grid")]) && typed.is_none() &&
+      names.iter().all(|name| grids.check_name(name.clone() + "0").is_some()));
+  }
+
+  #[test]
+  fn it_does_not_import_more_templates_than_the_maximum_number_of_grids() {
+    let mut grids = Grids::new(1, 1);
+    let template = Template { name: String::from("mask"), width: 4,
+      height: 2, cells: Vec::new(), hidden: false, locked: false };
+    assert!(grids.import(vec![template.clone(), template]).is_err() &&
+      (grids.get_nb() == 1));
+  }
+
+  #[test]
+  fn it_exports_the_current_grid_or_all_grids_as_templates() {
+    let mut grids = Grids::new(1, 10);
+    let template = Template { name: String::from("mask"), width: 4,
+      height: 2, cells: vec![(3, 1, FullPixel::Body)], hidden: false,
+      locked: true };
+    grids.import(vec![template.clone(), template]).unwrap();
+    grids.next();
+    let current = grids.get_templates(false);
+    let all = grids.get_templates(true);
+    assert!((current.len() == 1) && (current[0].name == "mask2") &&
+      (current[0].cells == vec![(3, 1, FullPixel::Body)]) &&
+      (all.len() == 2) && (all[0].name == "mask") && (all[1].width == 4) &&
+      current[0].locked);
+  }
+
+  #[test]
+  fn it_resizes_the_grids_around_the_anchor() {
+    let mut grids = Grids::new(0, 10);
+    for &(x, y) in &[(0, 0), (5, 5), (9, 9)] {
+      grids.update(&action::Action::new(grids.get_current_grid_info(),
+        tools::Tool::CellSetter(tools::CellTool::PixelBrush(
+          FullPixel::Body, x, y))));
+    }
+    grids.update(&action::Action::new(grids.get_current_grid_info(),
+      tools::Tool::GridSetter(tools::GridTool::Resize(6, 14,
+        tools::Anchor::Center))));
+    let centered = grids.get_current_grid();
+    grids.update(&action::Action::new(grids.get_current_grid_info(),
+      tools::Tool::GridSetter(tools::GridTool::Resize(0, 600,
+        tools::Anchor::BottomRight))));
+    assert!((centered == vec![(FullPixel::Body, 3, 7)]) &&
+      (grids.get_grid_width() == 1) && (grids.get_grid_height() == 512) &&
+      grids.get_current_grid().is_empty());
+  }
+
+  #[test]
+  fn it_overlays_the_grids_of_the_largest_size() {
+    let mut grids = Grids::new(0, 10);
+    grids.update(&action::Action::new(grids.get_current_grid_info(),
+      tools::Tool::GridSetter(tools::GridTool::Resize(512, 512,
+        tools::Anchor::TopLeft))));
+    grids.update(&action::Action::new(grids.get_current_grid_info(),
+      tools::Tool::CellSetter(tools::CellTool::PixelBrush(
+        FullPixel::Body, 511, 511))));
+    assert!((grids.get_visible_grids().len() == 1) &&
+      (grids.get_grids().len() == 1));
+  }
 }
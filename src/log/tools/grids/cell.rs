@@ -28,25 +28,10 @@ impl Cell {
       }
   }
 
-  /// Returns a new Cell object with a new grid id and other fields cell
-  /// caller
-  pub fn set_grid_id(&self, new_grid_id: usize) -> Cell {
-    Cell {
-      grid_id: new_grid_id,
-      grid: self.grid,
-      x: self.x,
-      y: self.y,
-    }
-  }
-
   pub fn get_grid_id(&self) -> usize {
     self.grid_id
   }
 
-  pub fn get_grid(&self) -> Grid {
-    self.grid
-  }
-
   pub fn get_x(&self) -> u16 {
     self.x
   }
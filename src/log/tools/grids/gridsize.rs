@@ -0,0 +1,113 @@
+extern crate std;
+use std::ops::{Deref, DerefMut};
+
+use crate::log::tools::{setter, Anchor, ANCHORS};
+
+/// Digits of a dimension
+const DIGITS: usize = 3;
+
+/// Represents the size typed in the resize popup, 3 digits for the width
+/// then 3 digits for the height, with the side where the existing cells
+/// stay
+pub struct GridSize {
+  cursor: setter::Cursor,
+  anchor: usize,
+}
+
+/// Tips to use shared functions between Setter sub-structs
+impl Deref for GridSize {
+  type Target = setter::Cursor;
+
+  fn deref(&self) -> &Self::Target {
+    &self.cursor
+  }
+}
+
+/// Tips to use shared functions between Setter sub-structs
+impl DerefMut for GridSize {
+
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.cursor
+  }
+}
+
+impl GridSize {
+  pub fn new() -> GridSize {
+    GridSize {
+      cursor: setter::Cursor::new(vec!['0'; DIGITS * 2], 1,
+        u32::from(super::size_limit())),
+      anchor: 0,
+    }
+  }
+
+  pub fn get_value(&self) -> (String, String) {
+    (self.value[..DIGITS].iter().collect(),
+      self.value[DIGITS..].iter().collect())
+  }
+
+  pub fn get_size(&self) -> (u16, u16) {
+    let (width, height) = self.get_value();
+    (width.parse().unwrap(), height.parse().unwrap())
+  }
+
+  /// Writes <i>(width, height)</i> and selects the first anchor
+  pub fn set_size(&mut self, (width, height): (u16, u16)) {
+    self.value = format!("{:0digits$}{:0digits$}", width, height,
+      digits = DIGITS).chars().collect();
+    self.anchor = 0;
+  }
+
+  pub fn get_anchor(&self) -> Anchor {
+    ANCHORS[self.anchor]
+  }
+
+  pub fn next_anchor(&mut self) {
+    self.anchor = (self.anchor + 1) % ANCHORS.len();
+  }
+
+  pub fn previous_anchor(&mut self) {
+    self.anchor = (self.anchor + ANCHORS.len() - 1) % ANCHORS.len();
+  }
+}
+
+impl setter::Setter for GridSize {
+
+  /// Checks the value of the dimension under the cursor and sets it
+  fn set_value(&mut self, number: char) {
+    let pos = self.pos;
+    let first = pos - pos % DIGITS;
+    let mut value: String = self.value[first..first + DIGITS].iter()
+      .collect();
+    value.remove(pos - first);
+    value.insert(pos - first, number);
+    let value = value.parse::<u32>().unwrap();
+    if (value <= self.max_value) && (value >= self.min_value) {
+      self.value[pos] = number;
+      self.cursor_right();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+  use crate::log::tools::setter::Setter;
+
+  #[test]
+  fn it_sets_each_dimension_between_1_and_the_size_limit() {
+    let mut gridsize = GridSize::new();
+    gridsize.set_size((10, 10));
+    for number in "6064100".chars() {
+      gridsize.set_value(number);
+    }
+    let typed = gridsize.get_size();
+    gridsize.reset_cursor();
+    for number in "45".chars() {
+      gridsize.set_value(number);
+    }
+    gridsize.previous_anchor();
+    assert!((typed == (64, 100)) && (gridsize.get_size() == (454, 100)) &&
+      (gridsize.get_anchor() == Anchor::BottomRight));
+  }
+}
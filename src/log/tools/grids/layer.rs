@@ -0,0 +1,248 @@
+extern crate std;
+use std::collections::HashMap;
+
+use liriodendron::utils::FullPixel;
+
+/// Ratio of filled cells above which a sparse layer becomes dense
+const DENSE_RATIO: f64 = 0.25;
+
+/// Ratio of filled cells under which a dense layer becomes sparse again
+const SPARSE_RATIO: f64 = 0.125;
+
+/// Result of setting a cell of a layer
+#[derive(Clone, Copy, PartialEq)]
+pub enum Change {
+
+  /// The cell is set, and held this old value
+  Set(Option<FullPixel>),
+
+  /// The cell is outside of the grid, nothing is set
+  Outside,
+}
+
+/// Filled cells of one grid. A sparse layer only stocks filled cells, while
+/// a dense layer stocks every cell of the grid in a Vec. The representation
+/// is switched automatically depending of the ratio of filled cells.
+#[derive(Clone)]
+pub enum Layer {
+  Sparse(HashMap<(u16, u16), FullPixel>),
+  Dense {
+    width: u16,
+    height: u16,
+    cells: Vec<Option<FullPixel>>,
+    len: usize,
+  },
+}
+
+impl Layer {
+
+  pub fn new() -> Layer {
+    Layer::Sparse(HashMap::new())
+  }
+
+  pub fn len(&self) -> usize {
+    match self {
+      Layer::Sparse(cells) => cells.len(),
+      Layer::Dense { len, .. } => *len,
+    }
+  }
+
+  pub fn is_dense(&self) -> bool {
+    match self {
+      Layer::Sparse(_) => false,
+      Layer::Dense { .. } => true,
+    }
+  }
+
+  pub fn get(&self, (x, y): (u16, u16)) -> Option<FullPixel> {
+    match self {
+      Layer::Sparse(cells) => cells.get(&(x, y)).copied(),
+      Layer::Dense { width, height, cells, .. } => {
+        if (x >= *width) || (y >= *height) {
+          None
+        } else {
+          cells[usize::from(y) * usize::from(*width) + usize::from(x)]
+        }
+      },
+    }
+  }
+
+  /// Sets the cell <i>(x, y)</i> of a grid sized <i>(width, height)</i>
+  /// and returns its old value. Cells outside of the grid are not set.
+  pub fn insert(&mut self, (x, y): (u16, u16), pixel: FullPixel,
+    (width, height): (u16, u16)) -> Change {
+      if (x >= width) || (y >= height) {
+        return Change::Outside
+      }
+      match self {
+        Layer::Sparse(cells) => Change::Set(cells.insert((x, y), pixel)),
+        Layer::Dense { width, cells, len, .. } => {
+          let index = usize::from(y) * usize::from(*width) + usize::from(x);
+          let old_value = cells[index].replace(pixel);
+          if old_value.is_none() {
+            *len += 1;
+          }
+          Change::Set(old_value)
+        },
+      }
+  }
+
+  /// Empties the cell <i>(x, y)</i> of a grid sized <i>(width, height)</i>
+  /// and returns its old value
+  pub fn remove(&mut self, (x, y): (u16, u16), (width, height): (u16, u16))
+    -> Change {
+      if (x >= width) || (y >= height) {
+        return Change::Outside
+      }
+      match self {
+        Layer::Sparse(cells) => Change::Set(cells.remove(&(x, y))),
+        Layer::Dense { width, cells, len, .. } => {
+          let index = usize::from(y) * usize::from(*width) + usize::from(x);
+          let old_value = cells[index].take();
+          if old_value.is_some() {
+            *len -= 1;
+          }
+          Change::Set(old_value)
+        },
+      }
+  }
+
+  pub fn clear(&mut self) {
+    *self = Layer::new();
+  }
+
+  /// Returns filled cells in a Vec of tuples <i>(cell X coordinate, cell Y
+  /// coordinate, cell content)</i>
+  pub fn get_cells(&self) -> Vec<(u16, u16, FullPixel)> {
+    match self {
+      Layer::Sparse(cells) =>
+        cells.iter().map(|(&(x, y), &pixel)| (x, y, pixel)).collect(),
+      Layer::Dense { width, cells, .. } => {
+        let width = usize::from(*width);
+        cells.iter().enumerate()
+          .filter_map(|(index, pixel)| pixel.map(|pixel|
+            ((index % width) as u16, (index / width) as u16, pixel)))
+          .collect()
+      },
+    }
+  }
+
+  /// Moves cells by <i>(offset_x, offset_y)</i>, drops cells outside of the
+  /// new grid's size and fits the representation of the layer to its new
+  /// ratio of filled cells
+  pub fn resize(&mut self, width: u16, height: u16,
+    (offset_x, offset_y): (i32, i32)) {
+      let filled_cells: Vec<(u16, u16, FullPixel)> = self.get_cells()
+        .into_iter()
+        .map(|(x, y, pixel)|
+          (i32::from(x) + offset_x, i32::from(y) + offset_y, pixel))
+        .filter(|&(x, y, _)| (x >= 0) && (y >= 0) &&
+          (x < i32::from(width)) && (y < i32::from(height)))
+        .map(|(x, y, pixel)| (x as u16, y as u16, pixel))
+        .collect();
+      let dense = self.is_dense();
+      *self = Layer::new();
+      if dense {
+        *self = Layer::dense(width, height);
+      }
+      for (x, y, pixel) in filled_cells {
+        self.insert((x, y), pixel, (width, height));
+      }
+      self.check_density(width, height);
+  }
+
+  /// Switches to the dense representation when the layer is filled enough,
+  /// or back to the sparse representation when it is mostly empty
+  pub fn check_density(&mut self, width: u16, height: u16) {
+    let ratio = self.len() as f64 /
+      (usize::from(width) * usize::from(height)) as f64;
+    if (!self.is_dense() && (ratio > DENSE_RATIO)) ||
+      (self.is_dense() && (ratio < SPARSE_RATIO)) {
+        let filled_cells = self.get_cells();
+        *self = if self.is_dense() {
+          Layer::new()
+        } else {
+          Layer::dense(width, height)
+        };
+        for (x, y, pixel) in filled_cells {
+          self.insert((x, y), pixel, (width, height));
+        }
+    }
+  }
+
+  fn dense(width: u16, height: u16) -> Layer {
+    Layer::Dense {
+      width,
+      height,
+      cells: vec![None; usize::from(width) * usize::from(height)],
+      len: 0,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+
+  #[test]
+  fn it_becomes_dense_when_it_is_filled_enough() {
+    let mut layer = Layer::new();
+    for x in 0..4 {
+      layer.insert((x, 0), FullPixel::Body, (4, 4));
+      layer.check_density(4, 4);
+    }
+    let was_dense = layer.is_dense();
+    layer.insert((0, 1), FullPixel::Border, (4, 4));
+    layer.check_density(4, 4);
+    assert!(!was_dense && layer.is_dense() && (layer.len() == 5));
+  }
+
+  #[test]
+  fn it_becomes_sparse_when_it_is_mostly_empty() {
+    let mut layer = Layer::new();
+    for x in 0..4 {
+      layer.insert((x, 0), FullPixel::Body, (4, 4));
+    }
+    layer.insert((0, 1), FullPixel::Body, (4, 4));
+    layer.check_density(4, 4);
+    let was_dense = layer.is_dense();
+    for x in 0..4 {
+      layer.remove((x, 0), (4, 4));
+      layer.check_density(4, 4);
+    }
+    assert!(was_dense && !layer.is_dense() && (layer.len() == 1) &&
+      (layer.get_cells() == vec![(0, 1, FullPixel::Body)]));
+  }
+
+  #[test]
+  fn it_keeps_cells_inside_the_grid_when_a_dense_layer_is_resized() {
+    let mut layer = Layer::new();
+    for x in 0..4 {
+      layer.insert((x, x), FullPixel::Body, (4, 4));
+    }
+    layer.insert((0, 1), FullPixel::Body, (4, 4));
+    layer.check_density(4, 4);
+    layer.resize(3, 4, (0, 0));
+    let cells = layer.get_cells();
+    assert!(layer.is_dense() && (cells.len() == 4) &&
+      cells.iter().all(|&(x, _, _)| x < 3));
+  }
+
+  #[test]
+  fn it_sets_no_cell_outside_of_the_grid_whatever_the_representation() {
+    let mut layer = Layer::new();
+    let sparse = layer.insert((4, 0), FullPixel::Body, (4, 4));
+    for x in 0..4 {
+      layer.insert((x, 0), FullPixel::Body, (4, 4));
+    }
+    layer.insert((0, 1), FullPixel::Body, (4, 4));
+    layer.check_density(4, 4);
+    let dense = layer.insert((0, 4), FullPixel::Body, (4, 4));
+    assert!((sparse == Change::Outside) && (dense == Change::Outside) &&
+      layer.is_dense() && (layer.len() == 5) &&
+      (layer.remove((4, 4), (4, 4)) == Change::Outside) &&
+      (layer.insert((0, 0), FullPixel::Border, (4, 4)) ==
+        Change::Set(Some(FullPixel::Body))));
+  }
+}
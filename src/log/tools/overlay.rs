@@ -0,0 +1,71 @@
+/// Grids shown by the edit view
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Mode {
+
+  /// Only the current grid
+  CurrentGrid,
+
+  /// The current grid over the other grids, which are dimmed
+  OnionSkin,
+
+  /// Every grid overlaid as by the generation, a grid hiding the grids of
+  /// greater ids
+  Composite,
+}
+
+/// Represents the grids shown by the edit view. The onion skin and the
+/// composite modes are toggled, enabling one disables the other.
+pub struct Overlay {
+  mode: Mode,
+}
+
+impl Overlay {
+
+  pub fn new() -> Overlay {
+    Overlay {
+      mode: Mode::CurrentGrid,
+    }
+  }
+
+  pub fn is_onionskin(&self) -> bool {
+    self.mode == Mode::OnionSkin
+  }
+
+  pub fn is_composite(&self) -> bool {
+    self.mode == Mode::Composite
+  }
+
+  pub fn toggle_onionskin(&mut self) {
+    self.toggle(Mode::OnionSkin);
+  }
+
+  pub fn toggle_composite(&mut self) {
+    self.toggle(Mode::Composite);
+  }
+
+  fn toggle(&mut self, mode: Mode) {
+    self.mode = if self.mode == mode {
+      Mode::CurrentGrid
+    } else {
+      mode
+    };
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+
+  #[test]
+  fn it_toggles_one_mode_at_a_time() {
+    let mut overlay = Overlay::new();
+    overlay.toggle_onionskin();
+    let onionskin = overlay.is_onionskin();
+    overlay.toggle_composite();
+    let composite = overlay.is_composite() && !overlay.is_onionskin();
+    overlay.toggle_composite();
+    assert!(onionskin && composite && !overlay.is_composite() &&
+      !overlay.is_onionskin());
+  }
+}
@@ -0,0 +1,294 @@
+/// Direction of a scroller
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Axis {
+  Horizontal,
+  Vertical,
+}
+
+/// Scroller of the grid as drawn by the last frame
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Scroller {
+
+  /// Column of a vertical scroller or row of a horizontal one
+  pub line: u16,
+
+  /// First character of the thumb along the scroller, and the one after it
+  pub thumb: (u16, u16),
+
+  /// Scrolled cells when the thumb moves by <i>track</i> characters
+  pub cells: u16,
+  pub track: u16,
+}
+
+/// Item of the <i>user interface</i> under a click
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Target {
+
+  /// Entry of the brush selector
+  Brush(usize),
+
+  /// Tab of the grid with this id
+  Tab(usize),
+
+  Scroller(Axis),
+
+  /// Bottom right corner of the grid borders
+  Corner,
+}
+
+/// Result of dragging a grabbed target
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Drag {
+
+  /// New grid scroll
+  Scroll((u16, u16)),
+
+  /// Grid size when the corner was grabbed, and how far in characters the
+  /// corner has been moved since
+  Resize((u16, u16), (i32, i32)),
+}
+
+/// Target under a click while the button is held, with the click position,
+/// the grid scroll and the grid size at that time
+#[derive(Clone, Copy)]
+struct Grab {
+  target: Target,
+  position: (u16, u16),
+  scroll: (u16, u16),
+  size: (u16, u16),
+}
+
+/// Represents the mouse pointer over the edit view at its last click, drag
+/// or wheel move. Crossterm 0.17 can not read the moves of the mouse without
+/// a pressed button, so the hovered cell is not known. The areas are saved
+/// by the widgets when they are drawn.
+pub struct Pointer {
+
+  /// Position of the last click, drag or wheel move in the terminal
+  position: Option<(u16, u16)>,
+
+  /// Grid area <i>(left, right, top, bottom)</i> of the last drawn frame
+  area: (u16, u16, u16, u16),
+
+  /// Brush selector area, each entry having the same height
+  brushes: ((u16, u16, u16, u16), usize),
+
+  /// Tabs area, one tab per row from the tab with the given id
+  tabs: ((u16, u16, u16, u16), usize),
+
+  horizontal: Option<Scroller>,
+  vertical: Option<Scroller>,
+
+  /// Position of the bottom right corner of the grid borders
+  corner: Option<(u16, u16)>,
+
+  grabbed: Option<Grab>,
+
+  /// Size of the grid drawn while its corner is dragged, applied once the
+  /// corner is released
+  preview: Option<(u16, u16)>,
+}
+
+/// Returns <i>true</i> if <i>(x, y)</i> is inside <i>(left, right, top,
+/// bottom)</i>
+fn contains((left, right, top, bottom): (u16, u16, u16, u16),
+  (x, y): (u16, u16)) -> bool {
+    (x >= left) && (x < right) && (y >= top) && (y < bottom)
+}
+
+impl Pointer {
+
+  pub fn new() -> Pointer {
+    Pointer {
+      position: None,
+      area: (0, 0, 0, 0),
+      brushes: ((0, 0, 0, 0), 0),
+      tabs: ((0, 0, 0, 0), 0),
+      horizontal: None,
+      vertical: None,
+      corner: None,
+      grabbed: None,
+      preview: None,
+    }
+  }
+
+  pub fn set_position(&mut self, position: (u16, u16)) {
+    self.position = Some(position);
+  }
+
+  pub fn set_area(&mut self, area: (u16, u16, u16, u16)) {
+    self.area = area;
+  }
+
+  pub fn set_brushes(&mut self, area: (u16, u16, u16, u16), nb: usize) {
+    self.brushes = (area, nb);
+  }
+
+  pub fn set_tabs(&mut self, area: (u16, u16, u16, u16), first_tab: usize) {
+    self.tabs = (area, first_tab);
+  }
+
+  pub fn set_scroller(&mut self, axis: Axis, scroller: Option<Scroller>) {
+    match axis {
+      Axis::Horizontal => self.horizontal = scroller,
+      Axis::Vertical => self.vertical = scroller,
+    };
+  }
+
+  pub fn set_corner(&mut self, corner: (u16, u16)) {
+    self.corner = Some(corner);
+  }
+
+  /// Returns the position of the pointer if it is inside the grid area
+  pub fn get_position(&self) -> Option<(u16, u16)> {
+    self.position.filter(|&position| contains(self.area, position))
+  }
+
+  /// Returns the top left corner of the grid area
+  pub fn get_origin(&self) -> (u16, u16) {
+    (self.area.0, self.area.2)
+  }
+
+  /// Returns the target under <i>(x, y)</i>: a brush, a tab, the thumb of a
+  /// scroller or the corner of the grid
+  fn get_target(&self, (x, y): (u16, u16)) -> Option<Target> {
+    let ((brushes_area, nb_brushes), (tabs_area, first_tab)) =
+      (self.brushes, self.tabs);
+    let on_thumb = |scroller: Option<Scroller>, line: u16, along: u16|
+      scroller.filter(|scroller| (scroller.line == line) &&
+        (along >= scroller.thumb.0) && (along < scroller.thumb.1)).is_some();
+    if contains(brushes_area, (x, y)) && (nb_brushes > 0) {
+      let entry_height = (brushes_area.3 - brushes_area.2) /
+        nb_brushes as u16;
+      Some(Target::Brush(usize::from((y - brushes_area.2) / entry_height)))
+    } else if contains(tabs_area, (x, y)) {
+      Some(Target::Tab(first_tab + usize::from(y - tabs_area.2)))
+    } else if on_thumb(self.horizontal, y, x) {
+      Some(Target::Scroller(Axis::Horizontal))
+    } else if on_thumb(self.vertical, x, y) {
+      Some(Target::Scroller(Axis::Vertical))
+    } else if self.corner == Some((x, y)) {
+      Some(Target::Corner)
+    } else {
+      None
+    }
+  }
+
+  /// Grabs the target under a click with the grid scrolled by
+  /// <i>scroll</i> and sized <i>size</i>, and returns it
+  pub fn press(&mut self, position: (u16, u16),
+    (scroll, size): ((u16, u16), (u16, u16))) -> Option<Target> {
+      self.set_position(position);
+      let target = self.get_target(position);
+      self.grabbed =
+        target.map(|target| Grab { target, position, scroll, size });
+      target
+  }
+
+  /// Releases the grabbed target and returns the previewed grid size, if
+  /// the corner was dragged
+  pub fn release(&mut self, position: (u16, u16)) -> Option<(u16, u16)> {
+    self.set_position(position);
+    self.grabbed = None;
+    self.preview.take()
+  }
+
+  pub fn set_preview(&mut self, size: (u16, u16)) {
+    self.preview = Some(size);
+  }
+
+  pub fn get_preview(&self) -> Option<(u16, u16)> {
+    self.preview
+  }
+
+  pub fn is_grabbed(&self) -> bool {
+    self.grabbed.is_some()
+  }
+
+  /// Returns the grid scroll once a grabbed scroller thumb is dragged to
+  /// <i>(x, y)</i>, or the move of a grabbed grid corner
+  pub fn drag(&mut self, (x, y): (u16, u16)) -> Option<Drag> {
+    self.set_position((x, y));
+    let moved = |from: u16, to: u16, scroll: u16, scroller: Scroller| {
+      let cells = (i32::from(to) - i32::from(from)) *
+        i32::from(scroller.cells) / i32::from(scroller.track.max(1));
+      (i32::from(scroll) + cells).max(0) as u16
+    };
+    match self.grabbed {
+      Some(Grab { target: Target::Scroller(Axis::Horizontal),
+        position: (from, _), scroll: (scroll_x, scroll_y), .. }) =>
+          self.horizontal.map(|scroller|
+            Drag::Scroll((moved(from, x, scroll_x, scroller), scroll_y))),
+      Some(Grab { target: Target::Scroller(Axis::Vertical),
+        position: (_, from), scroll: (scroll_x, scroll_y), .. }) =>
+          self.vertical.map(|scroller|
+            Drag::Scroll((scroll_x, moved(from, y, scroll_y, scroller)))),
+      Some(Grab { target: Target::Corner, position: (from_x, from_y), size,
+        .. }) => Some(Drag::Resize(size, (i32::from(x) - i32::from(from_x),
+          i32::from(y) - i32::from(from_y)))),
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+
+  #[test]
+  fn it_is_only_over_the_grid_inside_its_area() {
+    let mut pointer = Pointer::new();
+    pointer.set_area((10, 14, 4, 8));
+    let unknown = pointer.get_position();
+    pointer.set_position((14, 5));
+    let outside = pointer.get_position();
+    pointer.set_position((13, 7));
+    assert!(unknown.is_none() && outside.is_none() &&
+      (pointer.get_position() == Some((13, 7))));
+  }
+
+  #[test]
+  fn it_finds_the_clicked_brush_and_tab() {
+    let mut pointer = Pointer::new();
+    pointer.set_brushes((1, 8, 1, 16), 5);
+    pointer.set_tabs((40, 56, 1, 4), 2);
+    let brush = pointer.press((3, 10), ((0, 0), (10, 10)));
+    let tab = pointer.press((45, 3), ((0, 0), (10, 10)));
+    let nothing = pointer.press((20, 3), ((0, 0), (10, 10)));
+    assert!((brush == Some(Target::Brush(3))) &&
+      (tab == Some(Target::Tab(4))) && nothing.is_none() &&
+      !pointer.is_grabbed());
+  }
+
+  #[test]
+  fn it_scrolls_the_grid_by_dragging_a_scroller_thumb() {
+    let mut pointer = Pointer::new();
+    pointer.set_scroller(Axis::Vertical,
+      Some(Scroller { line: 9, thumb: (6, 9), cells: 20, track: 10 }));
+    let track = pointer.press((9, 5), ((0, 4), (10, 30)));
+    let thumb = pointer.press((9, 7), ((0, 4), (10, 30)));
+    let down = pointer.drag((9, 10));
+    let up = pointer.drag((9, 0));
+    pointer.release((9, 0));
+    assert!(track.is_none() &&
+      (thumb == Some(Target::Scroller(Axis::Vertical))) &&
+      (down == Some(Drag::Scroll((0, 10)))) &&
+      (up == Some(Drag::Scroll((0, 0)))) && pointer.drag((9, 10)).is_none());
+  }
+
+  #[test]
+  fn it_moves_the_grabbed_corner_of_the_grid() {
+    let mut pointer = Pointer::new();
+    pointer.set_corner((21, 12));
+    let corner = pointer.press((21, 12), ((0, 0), (10, 10)));
+    let dragged = pointer.drag((18, 15));
+    pointer.set_preview((7, 13));
+    let preview = pointer.get_preview();
+    assert!((corner == Some(Target::Corner)) &&
+      (dragged == Some(Drag::Resize((10, 10), (-3, 3)))) &&
+      (preview == Some((7, 13))) &&
+      (pointer.release((18, 15)) == Some((7, 13))) &&
+      pointer.get_preview().is_none());
+  }
+}
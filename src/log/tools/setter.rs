@@ -10,13 +10,13 @@ pub trait Setter {
 pub struct Cursor {
   pub pos: usize,
   pub value: Vec<char>,
-  pub min_value: u16,
-  pub max_value: u16,
+  pub min_value: u32,
+  pub max_value: u32,
   pub instant: Instant,
 }
 
 impl Cursor {
-  pub fn new(default_value: Vec<char>, min: u16, max: u16) -> Cursor {
+  pub fn new(default_value: Vec<char>, min: u32, max: u32) -> Cursor {
     Cursor {
       pos: 0,
       value: default_value,
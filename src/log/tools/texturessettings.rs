@@ -3,13 +3,14 @@ use image;
 /// Module to set and check the number of generated textures
 pub mod generationnumber;
 
-use crate::task::generation::palette::PaletteGeneration;
+use liriodendron::error::Error;
+use liriodendron::texture::palette::PaletteGeneration;
 
 const DEFAULT_PIXEL_RATIO: usize = 3;
 const DEFAULT_BORDER_RATIO: u8 = 3;
 const DEFAULT_PALETTE: PaletteGeneration =
   PaletteGeneration::RandomPredefinedColors;
-const DEFAULT_NUMBER_GENERATION: u16 = 1;
+const DEFAULT_NUMBER_GENERATION: u32 = 1;
 
 const NB_PIXEL_RATIO_SETTINGS: usize = 15;
 const NB_FORMAT: usize = 2;
@@ -27,7 +28,7 @@ pub struct TexturesSettings {
   border_ratio: u8,
 
   palette: PaletteGeneration,
-  number_generation: u16,
+  number_generation: u32,
 
   format: [image::ImageFormat; NB_FORMAT],
   current_format: usize,
@@ -57,6 +58,20 @@ impl TexturesSettings {
     }
   }
 
+  /// Return an error if <i>pixel_ratio</i> is not one of the settings
+  pub fn set_pixel_ratio(&mut self, pixel_ratio: u16) -> Result<(), Error> {
+    match self.pixel_ratio.iter().position(|&ratio| ratio == pixel_ratio) {
+      Some(position) => {
+        self.current_pixel_ratio = position;
+        Ok(())
+      },
+      None => Err(Error::InvalidArgument(format!(
+        "pixel_ratio default must be one of {}.", self.pixel_ratio.iter()
+          .map(|ratio| ratio.to_string()).collect::<Vec<String>>()
+          .join(", ")))),
+    }
+  }
+
   pub fn decr_pixel_ratio(&mut self) {
     if self.current_pixel_ratio > 0 {
       self.current_pixel_ratio -= 1;
@@ -71,11 +86,11 @@ impl TexturesSettings {
     self.palette
   }
 
-  pub fn get_number(&self) -> u16 {
+  pub fn get_number(&self) -> u32 {
     self.number_generation
   }
 
-  pub fn set_number(&mut self, number: u16) {
+  pub fn set_number(&mut self, number: u32) {
     self.number_generation = number;
   }
 
@@ -2,6 +2,7 @@ extern crate std;
 use std::ops::{Deref, DerefMut};
 
 use crate::log::tools::setter;
+use crate::task::generation::MAX_NUMBER_GENERATIONS;
 
 /// Represents a customizable number of generated textures
 pub struct GenerationNumber {
@@ -28,13 +29,21 @@ impl DerefMut for GenerationNumber {
 impl GenerationNumber {
   pub fn new() -> GenerationNumber {
     GenerationNumber {
-      cursor: setter::Cursor::new(vec!['0', '0', '1'], 1, 500),
+      cursor: setter::Cursor::new(vec!['0', '0', '0', '0', '1'], 1,
+        MAX_NUMBER_GENERATIONS),
     }
   }
 
   pub fn get_value(&self) -> String {
     self.value.iter().collect()
   }
+
+  /// Writes <i>number</i> with as many digits as the maximum value
+  pub fn set_number(&mut self, number: u32) {
+    let digits = self.value.len();
+    self.value = format!("{:0digits$}", number, digits = digits).chars()
+      .collect();
+  }
 }
 
 impl setter::Setter for GenerationNumber {
@@ -45,7 +54,7 @@ impl setter::Setter for GenerationNumber {
     let pos = self.pos;
     value.remove(pos);
     value.insert(pos, number);
-    let value = value.parse::<u16>().unwrap();
+    let value = value.parse::<u32>().unwrap();
     if (value <= self.max_value) && (value >= self.min_value) {
       self.value[pos] = number;
       self.cursor_right();
@@ -60,38 +69,44 @@ mod tests {
   use crate::log::tools::setter::Setter;
 
   #[test]
-  fn it_sets_number_with_the_char_sequences_56789400_0512345678901234567890_00_0() {
+  fn it_sets_number_with_the_char_sequences_5678940000_0512345678901234567890_11_00_1_00() {
     let mut generationnumber = GenerationNumber::new();
-    let first_number = "56789400";
-    let first_res = String::from("400");
+    let first_number = "5678940000";
+    let first_res = String::from("40000");
     for number in first_number.chars() {
       generationnumber.set_value(number);
     }
     let first_test = first_res == generationnumber.get_value();
-    generationnumber.cursor_left();
-    generationnumber.cursor_left();
+    for _ in 0..4 {
+      generationnumber.cursor_left();
+    }
     let second_number = "0512345678901234567890";
-    let second_res = String::from("500");
+    let second_res = String::from("50000");
     for number in second_number.chars() {
       generationnumber.set_value(number);
     }
     let second_test = second_res == generationnumber.get_value();
-    generationnumber.cursor_left();
-    generationnumber.cursor_left();
+    for _ in 0..4 {
+      generationnumber.cursor_left();
+    }
     generationnumber.set_value('1');
     generationnumber.set_value('1');
     generationnumber.cursor_left();
     generationnumber.cursor_left();
     generationnumber.set_value('0');
     generationnumber.set_value('0');
-    let third_res = String::from("010");
+    let third_res = String::from("01000");
     let third_test = third_res == generationnumber.get_value();
-    generationnumber.cursor_right();
+    for _ in 0..3 {
+      generationnumber.cursor_right();
+    }
     generationnumber.set_value('1');
-    generationnumber.cursor_left();
+    for _ in 0..3 {
+      generationnumber.cursor_left();
+    }
     generationnumber.set_value('0');
     generationnumber.set_value('0');
-    let forth_res = String::from("001");
+    let forth_res = String::from("00001");
     let forth_test = forth_res == generationnumber.get_value();
     assert!(first_test && second_test && third_test && forth_test);
   }
@@ -0,0 +1,142 @@
+/// Zoom levels of the grid in the edit view, from the farthest one
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Level {
+
+  /// Two rows of cells per character, drawn with half blocks, for big grids
+  HalfBlock,
+
+  /// One character per cell, which looks twice as tall as wide
+  Character,
+
+  /// Two characters per cell, which looks about square
+  Square,
+
+  /// Two by two characters per cell
+  Detail,
+}
+
+const LEVELS: [Level; 4] =
+  [Level::HalfBlock, Level::Character, Level::Square, Level::Detail];
+
+/// Represents the zoom of the grid. A cell of the grid is drawn with a
+/// number of characters, or a character draws a number of cells, in each
+/// direction.
+pub struct Zoom {
+  level: usize,
+}
+
+impl Zoom {
+
+  pub fn new() -> Zoom {
+    Zoom {
+      level: 1,
+    }
+  }
+
+  pub fn zoom_in(&mut self) {
+    if self.level + 1 < LEVELS.len() {
+      self.level += 1;
+    }
+  }
+
+  pub fn zoom_out(&mut self) {
+    if self.level > 0 {
+      self.level -= 1;
+    }
+  }
+
+  /// Returns the horizontal then the vertical scale as <i>(characters,
+  /// cells)</i>: <i>characters</i> characters draw <i>cells</i> cells
+  pub fn get_scale(&self) -> ((u16, u16), (u16, u16)) {
+    match LEVELS[self.level] {
+      Level::HalfBlock => ((1, 1), (1, 2)),
+      Level::Character => ((1, 1), (1, 1)),
+      Level::Square => ((2, 1), (1, 1)),
+      Level::Detail => ((2, 1), (2, 1)),
+    }
+  }
+
+  pub fn is_halfblock(&self) -> bool {
+    LEVELS[self.level] == Level::HalfBlock
+  }
+
+  /// Returns the size in characters of an area of cells, with a character
+  /// partly drawn counted
+  pub fn to_characters(&self, (width, height): (u16, u16)) -> (u16, u16) {
+    let ((columns, width_cells), (rows, height_cells)) = self.get_scale();
+    ((width * columns).div_ceil(width_cells),
+      (height * rows).div_ceil(height_cells))
+  }
+
+  /// Returns the size in cells of an area of characters, without a cell
+  /// partly drawn
+  pub fn to_cells(&self, (width, height): (u16, u16)) -> (u16, u16) {
+    let ((columns, width_cells), (rows, height_cells)) = self.get_scale();
+    (width * width_cells / columns, height * height_cells / rows)
+  }
+
+  /// Returns the position of a click at <i>(x, y)</i> in the terminal with
+  /// the grid scrolled by <i>(scroll_x, scroll_y)</i> cells, in units of
+  /// <i>1 / cells</i> character. This position is exactly corrected later,
+  /// once the position of the grid in the terminal is known.
+  pub fn get_position(&self, (x, y): (u16, u16),
+    (scroll_x, scroll_y): (u16, u16)) -> (u16, u16) {
+      let ((columns, width_cells), (rows, height_cells)) = self.get_scale();
+      (x * width_cells + scroll_x * columns,
+        y * height_cells + scroll_y * rows)
+  }
+
+  /// Returns the cell of a <i>position</i> got by <i>get_position</i> when
+  /// the grid starts at <i>(left, top)</i> in the terminal
+  pub fn get_cell(&self, (x, y): (u16, u16), (left, top): (u16, u16))
+    -> (u16, u16) {
+      let ((columns, width_cells), (rows, height_cells)) = self.get_scale();
+      ((x - left * width_cells) / columns, (y - top * height_cells) / rows)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+
+  #[test]
+  fn it_stays_between_the_farthest_and_the_nearest_levels() {
+    let mut zoom = Zoom::new();
+    for _ in 0..5 {
+      zoom.zoom_out();
+    }
+    let farthest = zoom.get_scale();
+    for _ in 0..5 {
+      zoom.zoom_in();
+    }
+    assert!((farthest == ((1, 1), (1, 2))) &&
+      (zoom.get_scale() == ((2, 1), (2, 1))));
+  }
+
+  #[test]
+  fn it_converts_cells_and_characters() {
+    let mut zoom = Zoom::new();
+    zoom.zoom_out();
+    let halfblock = (zoom.to_characters((5, 5)), zoom.to_cells((5, 5)));
+    zoom.zoom_in();
+    zoom.zoom_in();
+    let square = (zoom.to_characters((5, 5)), zoom.to_cells((5, 5)));
+    assert!((halfblock == ((5, 3), (5, 10))) &&
+      (square == ((10, 5), (2, 5))));
+  }
+
+  #[test]
+  fn it_maps_a_click_back_to_its_cell() {
+    let mut zoom = Zoom::new();
+    zoom.zoom_in();
+    zoom.zoom_in();
+    let detail = zoom.get_cell(zoom.get_position((13, 8), (2, 1)), (10, 4));
+    zoom.zoom_out();
+    zoom.zoom_out();
+    zoom.zoom_out();
+    let halfblock =
+      zoom.get_cell(zoom.get_position((13, 8), (2, 1)), (10, 4));
+    assert!((detail == (3, 3)) && (halfblock == (5, 9)));
+  }
+}
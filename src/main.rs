@@ -8,6 +8,13 @@ extern crate better_panic;
 extern crate std;
 use std::panic;
 
+extern crate image;
+
+extern crate liriodendron;
+use liriodendron::error::{Error, Result};
+use liriodendron::template;
+use liriodendron::texture::{Mask, Settings, Texture};
+
 extern crate structopt;
 use structopt::StructOpt;
 
@@ -16,9 +23,9 @@ use tui::Terminal;
 use tui::backend::CrosstermBackend;
 
 mod args;
+mod config;
 mod log;
 mod userinterface;
-mod utils;
 mod task;
 
 use userinterface::{errors, events, views};
@@ -31,38 +38,88 @@ fn custom_panic() {
   }));
 }
 
+/// Prints <i>error</i> and ends the process with its exit code
+fn exit(error: &Error) -> ! {
+  eprintln!("Error: {}", error);
+  std::process::exit(error.exit_code())
+}
+
+/// Generates textures from imported grids without the user interface
+fn generate(args: &args::Args, config: &config::Config, number: u32)
+  -> Result<()> {
+    let mut templates = template::load(args.get_import(),
+      &args.get_legend(config)?)?;
+    if templates.is_empty() {
+      return Err(Error::InvalidArgument(String::from("import argument is \
+        needed to generate textures without the user interface.")))
+    }
+    let mut settings = Settings::new();
+    let defaults = &config.defaults;
+    if defaults.exclude_hidden_grids {
+      templates.retain(|template| !template.hidden);
+    }
+    if let Some(pixel_ratio) = defaults.pixel_ratio {
+      settings.pixel_ratio = u32::from(pixel_ratio);
+    }
+    if let Some(ratio) = defaults.body_empty_ratio {
+      settings.body_empty_ratio = f64::from(ratio) / 100.;
+    }
+    if let Some(ratio) = defaults.body_border_ratio {
+      settings.body_border_ratio = f64::from(ratio) / 100.;
+    }
+    let texture = Texture::new(&Mask::from_templates(&templates), &settings)?;
+    task::generation::Generation::with_texture(texture, number,
+      image::ImageFormat::Png, &args.get_output(config)?)?
+      .run_all(args.get_threads_number(config)?)
+}
+
 fn main() {
 
   better_panic::install();
 
   let args = args::Args::from_args();
+  let config = config::Config::load(args.get_config())
+    .unwrap_or_else(|error| exit(&error));
 
   if args.is_ui_used() {
-    let (threads_number, max_waiting_tasks) =
-      (args.get_threads_number(), args.get_max_waiting_tasks());
+    let (threads_number, max_waiting_tasks) = (args.get_threads_number(
+      &config), args.get_max_waiting_tasks(&config));
     let (max_actions, max_filled_cells) =
-      (args.get_max_actions(), args.get_max_filled_cells());
-    if threads_number.is_ok() && max_waiting_tasks.is_ok() &&
-      max_actions.is_ok() && max_filled_cells.is_ok() {
+      (args.get_max_actions(&config), args.get_max_filled_cells(&config));
+    let (max_grids, legend) =
+      (args.get_max_grids(&config), args.get_legend(&config));
+    let output = args.get_output(&config);
+    match (max_grids, output) {
+      (Ok(max_grids), Ok(output)) if threads_number.is_ok() &&
+        max_waiting_tasks.is_ok() && max_actions.is_ok() &&
+        max_filled_cells.is_ok() && legend.is_ok() => {
+        let mut log = log::Log::new(max_filled_cells.unwrap(),
+          max_actions.unwrap(), max_grids);
+
+        if let Err(error) = log.set_defaults(&config.defaults)
+          .and_then(|_| template::load(args.get_import(), &legend.unwrap()))
+          .and_then(|templates| log.grids_import(templates)) {
+            exit(&error);
+        }
+
         let mut tasks = task::tasksmanager::TasksManager::new(
-          threads_number.unwrap(), max_waiting_tasks.unwrap());
+          threads_number.unwrap(), max_waiting_tasks.unwrap(), output);
 
         let stdout = std::io::stdout();
         let backend = CrosstermBackend::new(stdout);
         let mut terminal = Terminal::new(backend).unwrap();
 
-        let mut log =
-          log::Log::new(max_filled_cells.unwrap(), max_actions.unwrap());
-
+        userinterface::theme::set(config.theme);
         userinterface::init();
         custom_panic();
 
         let mut ui = userinterface::UserInterface::new();
-        let mut generation_error =
-          format!("Error: {:?}", std::io::ErrorKind::Other);
+        let mut generation_error = None;
+        let mut task_cursor = 0;
 
         loop {
           tasks.check_queue();
+          tasks.receive_progress();
           match ui.get_error() {
             errors::UserError::NoneError => {
               match ui.get_event() {
@@ -70,16 +127,26 @@ fn main() {
                   match ui.get_view() {
                     views::View::Edit => {
                       views::edit::render::render(&mut terminal, &mut log,
-                        tasks.get_todo_and_max_tasks());
-                      ui.set(views::edit::events::catch_events(&mut log));
+                        tasks.get_todo_and_max_tasks(), &config.keys);
+                      ui.set(views::edit::events::catch_events(&mut log,
+                        &config.keys));
                     },
                     views::View::Parameters => {
                       views::parameters::render::render(&mut terminal,
-                        &mut log, tasks.get_todo_and_max_tasks());
+                        &mut log, tasks.get_todo_and_max_tasks(),
+                        &config.keys);
                       ui.set(views::parameters::events::catch_events(
-                        &mut log));
+                        &mut log, &config.keys));
                     },
                   };
+
+                  // Lost textures of a finished task are reported once the
+                  // user is back to the editing views
+                  if let (events::UserEvent::Continue, Some(_)) =
+                    (ui.get_event(), tasks.get_failure()) {
+                      ui.set_popupview((events::UserEvent::GenerationError,
+                        errors::UserError::ResizeCheck));
+                  }
                 },
                 events::UserEvent::SetBrushColor => {
                   views::setbrushcolor::render::render(
@@ -99,6 +166,41 @@ fn main() {
                   ui.set_popupview(
                     views::settextureformat::events::catch_events(&mut log));
                 },
+                events::UserEvent::Export => {
+                  views::export::render::render(&mut terminal, &mut log);
+                  ui.set_popupview(views::export::events::catch_events(
+                    &mut log));
+                },
+                events::UserEvent::ExportGrids => {
+                  match template::export(&log.grids_gettemplates(),
+                    log.exportsettings_getformat()) {
+                      Ok(_) => {
+                        log.history_setsaved();
+                        ui.set_event(events::UserEvent::Continue);
+                      },
+                      Err(error) => {
+                        ui.set_event(events::UserEvent::GenerationError);
+                        generation_error = Some(error);
+                      },
+                  }
+                },
+                events::UserEvent::Tasks => {
+                  let tasks_info = tasks.get_tasks_info();
+                  views::tasks::render::render(&mut terminal, &tasks_info,
+                    task_cursor, tasks.is_paused());
+                  ui.set_popupview(views::tasks::events::catch_events(
+                    &mut task_cursor, tasks_info.len()));
+                },
+                events::UserEvent::CancelTask => {
+                  if let Some(task) = tasks.get_tasks_info().get(task_cursor) {
+                    tasks.cancel_task(task.id);
+                  }
+                  ui.set_event(events::UserEvent::Tasks);
+                },
+                events::UserEvent::PauseTasks => {
+                  tasks.set_paused(!tasks.is_paused());
+                  ui.set_event(events::UserEvent::Tasks);
+                },
                 events::UserEvent::SwitchGridsOrder => {
                   views::switchgridsorder::render::render(&mut terminal,
                     &mut log);
@@ -116,6 +218,16 @@ fn main() {
                   ui.set_popupview(views::gridname::events::catch_events(
                     &mut log, ui.get_event()));
                 },
+                events::UserEvent::ResizeGrid => {
+                  views::resizegrid::render::render(&mut terminal, &mut log);
+                  ui.set_popupview(views::resizegrid::events::catch_events(
+                    &mut log));
+                },
+                events::UserEvent::History => {
+                  views::history::render::render(&mut terminal, &mut log);
+                  ui.set_popupview(views::history::events::catch_events(
+                    &mut log));
+                },
                 events::UserEvent::GridNumberError => {
                   views::gridnumbererror::render::render(&mut terminal);
                   ui.set_popupview(
@@ -132,15 +244,39 @@ fn main() {
                     },
                     (Err(error), _) => {
                       ui.set_event(events::UserEvent::GenerationError);
-                      generation_error = format!("Error: {:?}", error.kind());
+                      generation_error = Some(error);
                     },
                   }
                 },
                 events::UserEvent::GenerationError => {
-                  views::generationerror::render::render(
-                    &mut terminal, &generation_error);
-                  ui.set_popupview(
-                    views::generationerror::events::catch_events());
+                  let failure = tasks.get_failure();
+                  views::generationerror::render::render(&mut terminal,
+                    generation_error.as_ref(), failure);
+                  ui.set_popupview(views::generationerror::events::catch_events(
+                    generation_error.is_none() &&
+                      failure.is_some_and(|failure| failure.can_retry())));
+                },
+                events::UserEvent::DismissError => {
+                  if generation_error.take().is_none() {
+                    tasks.dismiss_failure();
+                  }
+                  ui.set_popupview((events::UserEvent::Continue,
+                    errors::UserError::ResizeCheck));
+                },
+                events::UserEvent::RetryTask => {
+                  match tasks.retry_failure() {
+                    (Ok(_), thread_is_available) => {
+                      if thread_is_available {
+                        ui.set_event(events::UserEvent::Continue);
+                      } else {
+                        ui.set_event(events::UserEvent::UnavailableThread);
+                      }
+                    },
+                    (Err(error), _) => {
+                      ui.set_event(events::UserEvent::GenerationError);
+                      generation_error = Some(error);
+                    },
+                  }
                 },
                 events::UserEvent::LogOverflow => {
                   views::logoverflow::render::render(&mut terminal);
@@ -156,11 +292,17 @@ fn main() {
                   ui.set_popupview(views::exit::events::catch_events());
 
                   if let events::UserEvent::Shutdown = ui.get_event() {
-                    tasks.start_sender_channel();
+                    tasks.set_paused(false);
                     tasks.compute_todotasks();
-                    tasks.spawn_unlocking_thread();
                   }
                 },
+                events::UserEvent::CancelAndShutdown => {
+                  tasks.cancel_all();
+                  ui.set_event(events::UserEvent::Shutdown);
+                  tasks.set_paused(false);
+                  tasks.compute_todotasks();
+                },
+                events::UserEvent::FinishWithoutInterface => break,
                 events::UserEvent::Shutdown => {
                   if tasks.is_running() {
                     views::shutdown::render::render(&mut terminal,
@@ -168,7 +310,6 @@ fn main() {
                       tasks.get_done_and_todo_tasks());
                     ui.set_error(views::shutdown::events::catch_events());
                   } else {
-                    tasks.shutdown();
                     break;
                   }
                 },
@@ -187,23 +328,37 @@ fn main() {
         }
 
         userinterface::finish();
-    } else {
-      if threads_number.is_err() {
-        let error = threads_number.unwrap_err();
-        println!("Error {:?}: {}", error.kind(), error);
-      }
-      if max_waiting_tasks.is_err() {
-        let error = max_waiting_tasks.unwrap_err();
-        println!("Error {:?}: {}", error.kind(), error);
-      }
-      if max_actions.is_err() {
-        let error = max_actions.unwrap_err();
-        println!("Error {:?}: {}", error.kind(), error);
-      }
-      if max_filled_cells.is_err() {
-        let error = max_filled_cells.unwrap_err();
-        println!("Error {:?}: {}", error.kind(), error);
-      }
+        if let events::UserEvent::FinishWithoutInterface = ui.get_event() {
+          let (nb_tasks, _) = tasks.get_todo_and_max_tasks();
+          if nb_tasks > 0 {
+            println!("Liriodendron finishes {} tasks without interface, the \
+              terminal is available once they end...", nb_tasks);
+            tasks.finish_without_interface();
+          }
+        }
+        for failure in tasks.take_failures() {
+          eprintln!("Error: task {} lost {} textures in {} ({}): {}",
+            failure.id, failure.names.len(), failure.output,
+            failure.names.join(", "), failure.error);
+        }
+        tasks.shutdown();
+      },
+      (max_grids, output) => {
+        let errors = vec![threads_number.err(), max_waiting_tasks.err(),
+          max_actions.err(), max_filled_cells.err(), max_grids.err(),
+          legend.err(), output.err()];
+        let errors = errors.into_iter().flatten().collect::<Vec<Error>>();
+        let (last_error, errors) = errors.split_last().unwrap();
+        for error in errors {
+          eprintln!("Error: {}", error);
+        }
+        exit(last_error);
+      },
+    }
+  } else if let Some(number) = args.get_generate(&config) {
+    if let Err(error) = number.and_then(|number|
+      generate(&args, &config, number)) {
+      exit(&error);
     }
   } else {
     println!("Type \"liriodendron -h\" or \"liriodendron --help\" to see \
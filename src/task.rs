@@ -6,7 +6,35 @@ pub mod generation;
 /// Module for threads and user's tasks management
 pub mod tasksmanager;
 
+/// Module for the threads running tasks
+pub mod threadpool;
+
+extern crate std;
+use std::sync::Arc;
+
+use liriodendron::error::Result;
+
 trait Task {
-  fn run(&self, current_subtask: u16);
-  fn get_nb_subtasks(&self) -> u16;
+
+  /// Return an error if the result of <i>current_subtask</i> is lost
+  fn run(&self, current_subtask: u32) -> Result<()>;
+
+  fn get_nb_subtasks(&self) -> u32;
+
+  /// Called once every subtask is run
+  fn finish(&self) -> Result<()>;
+
+  /// Describes where the task writes its results
+  fn get_output(&self) -> String;
+
+  /// Return <i>true</i> if no other task may write in the same output at
+  /// the same time
+  fn has_exclusive_output(&self) -> bool;
+
+  /// Name of the result of <i>subtask</i>
+  fn get_name(&self, subtask: u32) -> String;
+
+  /// Makes a task running again <i>subtasks</i>, whose results were lost
+  fn retry(&self, subtasks: Vec<u32>)
+    -> Result<Arc<dyn Task + Send + Sync>>;
 }
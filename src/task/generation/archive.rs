@@ -0,0 +1,306 @@
+extern crate chrono;
+use chrono::{Datelike, Local, Timelike, Utc};
+
+extern crate crc32fast;
+use crc32fast::Hasher;
+
+extern crate std;
+use std::convert::TryFrom;
+use std::io::Write;
+
+use liriodendron::error::{Error, Result};
+
+/// Size of a tar block
+const TAR_BLOCK: usize = 512;
+
+/// Name of the file listing the textures of an archive
+const MANIFEST_NAME: &str = "manifest.txt";
+
+const ZIP_LOCAL_HEADER: u32 = 0x04034b50;
+const ZIP_CENTRAL_HEADER: u32 = 0x02014b50;
+const ZIP_END_HEADER: u32 = 0x06054b50;
+const ZIP_VERSION: u16 = 20;
+
+/// Formats of written textures
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StreamFormat {
+
+  /// Encoded images written one after the other
+  Images,
+
+  /// Tar archive where each texture is a file
+  Tar,
+
+  /// Uncompressed zip archive where each texture is a file
+  Zip,
+}
+
+impl StreamFormat {
+  pub fn parse(format: &str) -> Result<StreamFormat> {
+    match format.to_lowercase().as_str() {
+      "images" => Ok(StreamFormat::Images),
+      "tar" => Ok(StreamFormat::Tar),
+      "zip" => Ok(StreamFormat::Zip),
+      _ => Err(Error::InvalidArgument(
+        format!("format \"{}\" must be images, tar or zip.", format))),
+    }
+  }
+
+  pub fn extension(&self) -> &str {
+    match self {
+      StreamFormat::Images => "",
+      StreamFormat::Tar => "tar",
+      StreamFormat::Zip => "zip",
+    }
+  }
+}
+
+/// Zip central directory's record of a file
+struct ZipEntry {
+  name: String,
+  crc: u32,
+  size: u32,
+  offset: u32,
+}
+
+/// Writes files in <i>writer</i> depending of <i>format</i>. Archives end
+/// with a manifest listing their files.
+pub struct StreamWriter<W: Write> {
+  writer: W,
+  format: StreamFormat,
+
+  /// Names of written files
+  names: Vec<String>,
+
+  /// Number of written bytes
+  offset: u64,
+
+  zip_entries: Vec<ZipEntry>,
+
+  /// MS-DOS time and date of the zip files
+  zip_datetime: (u16, u16),
+}
+
+impl<W: Write> StreamWriter<W> {
+
+  pub fn new(writer: W, format: StreamFormat) -> StreamWriter<W> {
+    let now = Local::now();
+    StreamWriter {
+      writer,
+      format,
+      names: Vec::new(),
+      offset: 0,
+      zip_entries: Vec::new(),
+      zip_datetime: (
+        ((now.hour() << 11) | (now.minute() << 5) | (now.second() / 2)) as u16,
+        (((now.year().max(1980) - 1980) << 9) as u32 | (now.month() << 5) |
+          now.day()) as u16),
+    }
+  }
+
+  /// Writes the file called <i>name</i>
+  pub fn write(&mut self, name: &str, bytes: &[u8]) -> Result<()> {
+    match self.format {
+      StreamFormat::Images => self.write_bytes(bytes)?,
+      StreamFormat::Tar => {
+        self.write_bytes(&tar_header(name, bytes.len())?)?;
+        self.write_bytes(bytes)?;
+        self.write_bytes(&vec![0; tar_padding(bytes.len())])?;
+      },
+      StreamFormat::Zip => {
+        let (offset, size) = match (u32::try_from(self.offset),
+          u32::try_from(bytes.len())) {
+            (Ok(offset), Ok(size)) if self.zip_entries.len() < 0xffff =>
+              (offset, size),
+            _ => return Err(Error::Overflow(String::from(
+              "zip archives are limited to 65535 files and 4 GiB."))),
+        };
+        let mut hasher = Hasher::new();
+        hasher.update(bytes);
+        let entry = ZipEntry {
+          name: String::from(name),
+          crc: hasher.finalize(),
+          size,
+          offset,
+        };
+        let header = self.zip_local_header(&entry);
+        self.write_bytes(&header)?;
+        self.write_bytes(bytes)?;
+        self.zip_entries.push(entry);
+      },
+    }
+    self.names.push(String::from(name));
+    Ok(self.writer.flush()?)
+  }
+
+  /// Writes the manifest and closes the archive
+  pub fn finish(&mut self) -> Result<()> {
+    if self.format != StreamFormat::Images {
+      let manifest = self.names.iter()
+        .map(|name| format!("{}\n", name)).collect::<String>();
+      self.write(MANIFEST_NAME, manifest.as_bytes())?;
+    }
+    match self.format {
+      StreamFormat::Images => {},
+      StreamFormat::Tar => self.write_bytes(&[0; TAR_BLOCK * 2])?,
+      StreamFormat::Zip => {
+        let start = self.offset;
+        let mut central_directory = Vec::new();
+        for entry in self.zip_entries.iter() {
+          central_directory.extend(self.zip_central_header(entry));
+        }
+        self.write_bytes(&central_directory)?;
+        let nb_entries = self.zip_entries.len() as u16;
+        let mut end = Vec::with_capacity(22);
+        end.extend(&ZIP_END_HEADER.to_le_bytes());
+        end.extend(&[0; 4]);
+        end.extend(&nb_entries.to_le_bytes());
+        end.extend(&nb_entries.to_le_bytes());
+        end.extend(&(central_directory.len() as u32).to_le_bytes());
+        end.extend(&(start as u32).to_le_bytes());
+        end.extend(&[0; 2]);
+        self.write_bytes(&end)?;
+      },
+    }
+    Ok(self.writer.flush()?)
+  }
+
+  fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+    self.writer.write_all(bytes)?;
+    self.offset += bytes.len() as u64;
+    Ok(())
+  }
+
+  fn zip_local_header(&self, entry: &ZipEntry) -> Vec<u8> {
+    let mut header = Vec::with_capacity(30 + entry.name.len());
+    header.extend(&ZIP_LOCAL_HEADER.to_le_bytes());
+    header.extend(&ZIP_VERSION.to_le_bytes());
+    header.extend(&[0; 4]);
+    header.extend(&self.zip_datetime.0.to_le_bytes());
+    header.extend(&self.zip_datetime.1.to_le_bytes());
+    header.extend(&entry.crc.to_le_bytes());
+    header.extend(&entry.size.to_le_bytes());
+    header.extend(&entry.size.to_le_bytes());
+    header.extend(&(entry.name.len() as u16).to_le_bytes());
+    header.extend(&[0; 2]);
+    header.extend(entry.name.as_bytes());
+    header
+  }
+
+  fn zip_central_header(&self, entry: &ZipEntry) -> Vec<u8> {
+    let mut header = Vec::with_capacity(46 + entry.name.len());
+    header.extend(&ZIP_CENTRAL_HEADER.to_le_bytes());
+    header.extend(&ZIP_VERSION.to_le_bytes());
+    header.extend(&ZIP_VERSION.to_le_bytes());
+    header.extend(&[0; 4]);
+    header.extend(&self.zip_datetime.0.to_le_bytes());
+    header.extend(&self.zip_datetime.1.to_le_bytes());
+    header.extend(&entry.crc.to_le_bytes());
+    header.extend(&entry.size.to_le_bytes());
+    header.extend(&entry.size.to_le_bytes());
+    header.extend(&(entry.name.len() as u16).to_le_bytes());
+    header.extend(&[0; 12]);
+    header.extend(&entry.offset.to_le_bytes());
+    header.extend(entry.name.as_bytes());
+    header
+  }
+}
+
+/// Number of bytes completing a file of <i>size</i> bytes to a tar block
+fn tar_padding(size: usize) -> usize {
+  (TAR_BLOCK - size % TAR_BLOCK) % TAR_BLOCK
+}
+
+/// Makes the ustar header of a file called <i>name</i>
+fn tar_header(name: &str, size: usize) -> Result<[u8; TAR_BLOCK]> {
+  if name.len() > 99 {
+    return Err(Error::Overflow(
+      format!("{} is too long to be a tar file name.", name)))
+  }
+  let mut header = [0; TAR_BLOCK];
+  let mtime = Utc::now().timestamp().max(0);
+  let fields: [(usize, String); 7] = [
+    (0, String::from(name)),
+    (100, String::from("0000644")),
+    (108, String::from("0000000")),
+    (116, String::from("0000000")),
+    (124, format!("{:011o}", size)),
+    (136, format!("{:011o}", mtime)),
+    (257, String::from("ustar")),
+  ];
+  for (offset, field) in fields.iter() {
+    header[*offset..*offset + field.len()].copy_from_slice(field.as_bytes());
+  }
+  header[156] = b'0';
+  header[263..265].copy_from_slice(b"00");
+  header[148..156].copy_from_slice(b"        ");
+  let checksum: u32 = header.iter().map(|&byte| u32::from(byte)).sum();
+  header[148..155].copy_from_slice(format!("{:06o}\0", checksum).as_bytes());
+  Ok(header)
+}
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+
+  #[test]
+  fn it_parses_stream_formats() {
+    assert!((StreamFormat::parse("TAR").unwrap() == StreamFormat::Tar) &&
+      (StreamFormat::parse("images").unwrap() == StreamFormat::Images) &&
+      (StreamFormat::parse("zip").unwrap() == StreamFormat::Zip) &&
+      StreamFormat::parse("7z").is_err());
+  }
+
+  #[test]
+  fn it_makes_a_valid_tar_header() {
+    let header = tar_header("1.png", 700).unwrap();
+    let checksum: u32 = header.iter().enumerate()
+      .map(|(index, &byte)| if (148..156).contains(&index) { 32 }
+        else { u32::from(byte) })
+      .sum();
+    assert!((&header[0..5] == b"1.png") &&
+      (&header[124..135] == b"00000001274") &&
+      (&header[257..262] == b"ustar") &&
+      (header[148..154] == *format!("{:06o}", checksum).as_bytes()) &&
+      (tar_padding(700) == 324) && (tar_padding(1024) == 0));
+  }
+
+  #[test]
+  fn it_writes_a_tar_archive_with_a_manifest() {
+    let mut writer = StreamWriter::new(Vec::new(), StreamFormat::Tar);
+    writer.write("1.png", &[1; 600]).unwrap();
+    writer.finish().unwrap();
+    let archive = writer.writer;
+    let manifest = TAR_BLOCK * 3;
+    assert!((archive.len() == TAR_BLOCK * 7) && (&archive[0..5] == b"1.png")
+      && (&archive[manifest..manifest + 12] == b"manifest.txt") &&
+      (&archive[manifest + TAR_BLOCK..manifest + TAR_BLOCK + 6] == b"1.png\n")
+      && archive[TAR_BLOCK * 5..].iter().all(|&byte| byte == 0));
+  }
+
+  #[test]
+  fn it_writes_a_zip_archive_with_a_manifest() {
+    let mut writer = StreamWriter::new(Vec::new(), StreamFormat::Zip);
+    writer.write("1.png", b"abc").unwrap();
+    writer.finish().unwrap();
+    let archive = writer.writer;
+    let end = archive.len() - 22;
+    let mut hasher = Hasher::new();
+    hasher.update(b"abc");
+    assert!((archive[0..4] == ZIP_LOCAL_HEADER.to_le_bytes()) &&
+      (archive[14..18] == hasher.finalize().to_le_bytes()) &&
+      (&archive[30..35] == b"1.png") && (&archive[35..38] == b"abc") &&
+      (archive[end..end + 4] == ZIP_END_HEADER.to_le_bytes()) &&
+      (archive[end + 10..end + 12] == 2u16.to_le_bytes()));
+  }
+
+  #[test]
+  fn it_writes_images_one_after_the_other() {
+    let mut writer = StreamWriter::new(Vec::new(), StreamFormat::Images);
+    writer.write("1.png", b"abc").unwrap();
+    writer.write("2.png", b"de").unwrap();
+    writer.finish().unwrap();
+    assert!(writer.writer == b"abcde");
+  }
+}
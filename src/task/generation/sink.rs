@@ -0,0 +1,300 @@
+extern crate chrono;
+use chrono::{Datelike, Timelike, Utc};
+
+extern crate image;
+use image::{DynamicImage, ImageFormat, RgbaImage};
+
+extern crate std;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use liriodendron::error::Result;
+
+use super::archive::{StreamFormat, StreamWriter};
+
+/// Path of the standard output for the <i>output</i> argument
+pub const STDOUT_PATH: &str = "-";
+
+/// Destination of generated textures
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Output {
+
+  /// A new directory named with the current date
+  Directory,
+
+  /// A file, a named pipe or the standard output
+  Stream(PathBuf, StreamFormat),
+
+  /// A new tar or zip archive named with the current date
+  Archive(StreamFormat),
+}
+
+impl Output {
+
+  /// Output of the task <i>id</i> of the <i>user interface</i>. A file
+  /// path is suffixed by the id from the second task, so the tasks do not
+  /// overwrite each other, while a named pipe or a device is shared by
+  /// tasks running one after the other.
+  pub fn for_task(&self, id: usize) -> Output {
+    match self {
+      Output::Stream(path, format) if (id > 1) &&
+        fs::metadata(path).map_or(true, |metadata| metadata.is_file()) =>
+          Output::Stream(suffixed(path, &format!("_{}", id)), *format),
+      output => output.clone(),
+    }
+  }
+}
+
+/// Returns <i>path</i> with <i>suffix</i> added to its file stem
+fn suffixed(path: &Path, suffix: &str) -> PathBuf {
+  let mut name = path.file_stem().unwrap_or_default().to_os_string();
+  name.push(suffix);
+  if let Some(extension) = path.extension() {
+    name.push(".");
+    name.push(extension);
+  }
+  path.with_file_name(name)
+}
+
+/// Writes generated textures. A sink is shared by the subtasks of a
+/// generation.
+pub trait Sink: Send + Sync {
+
+  /// Writes the texture called <i>name</i>
+  fn write(&self, name: &str, image: &RgbaImage, format: ImageFormat)
+    -> Result<()>;
+
+  /// Ends the output once every texture is written
+  fn finish(&self) -> Result<()>;
+
+  /// Path of the directory, the file or the stream written by the sink
+  fn get_path(&self) -> String;
+
+  /// Return <i>true</i> if the textures of another sink with the same path
+  /// would be mixed with this sink's ones
+  fn is_exclusive(&self) -> bool;
+
+  /// Opens the sink writing the textures generated again after a failure
+  fn retry(&self) -> Result<Box<dyn Sink>>;
+}
+
+/// Opens the sink of <i>output</i>. A directory or an archive is created
+/// immediately, while a stream is opened by the first written texture, so
+/// opening a named pipe does not wait for its reader.
+pub fn open(output: &Output) -> Result<Box<dyn Sink>> {
+  match output {
+    Output::Directory => Ok(Box::new(DirectorySink::new()?)),
+    Output::Stream(path, format) =>
+      Ok(Box::new(StreamSink::new(path.clone(), *format))),
+    Output::Archive(format) => {
+      let path = PathBuf::from(format!("{}.{}", generation_name(),
+        format.extension()));
+      let file = OpenOptions::new().write(true).create_new(true)
+        .open(&path)?;
+      Ok(Box::new(StreamSink::with_writer(path, *format, Box::new(file))))
+    },
+  }
+}
+
+/// Name of a generation's directory or archive, made of the current date
+fn generation_name() -> String {
+  let now = Utc::now();
+  format!("liriodendron_generation_{}-{}-{}_{}:{}:{}.{}", now.day(),
+    now.month(), now.year(), now.hour(), now.minute(), now.second(),
+    now.nanosecond())
+}
+
+/// Encodes <i>image</i> in <i>format</i>
+fn encode(image: &RgbaImage, format: ImageFormat) -> Result<Vec<u8>> {
+  let mut bytes = Vec::new();
+  DynamicImage::ImageRgba8(image.clone()).write_to(&mut bytes, format)?;
+  Ok(bytes)
+}
+
+/// Writes each texture in a new directory
+pub struct DirectorySink {
+  directory_name: String,
+}
+
+impl DirectorySink {
+
+  /// Return an error if the created directory name is already taken
+  pub fn new() -> Result<DirectorySink> {
+    let dir = generation_name();
+    fs::create_dir(&dir)?;
+    Ok(DirectorySink {
+      directory_name: dir,
+    })
+  }
+}
+
+impl Sink for DirectorySink {
+  fn write(&self, name: &str, image: &RgbaImage, format: ImageFormat)
+    -> Result<()> {
+      Ok(image.save_with_format(Path::new(&self.directory_name)
+        .join(format!("{}.{}", name, format.extensions_str()[0])), format)?)
+  }
+
+  fn finish(&self) -> Result<()> {
+    Ok(())
+  }
+
+  fn get_path(&self) -> String {
+    self.directory_name.clone()
+  }
+
+  fn is_exclusive(&self) -> bool {
+    false
+  }
+
+  /// Writes in the same directory
+  fn retry(&self) -> Result<Box<dyn Sink>> {
+    Ok(Box::new(DirectorySink {
+      directory_name: self.directory_name.clone(),
+    }))
+  }
+}
+
+/// Writes textures one after the other in a file, a named pipe or the
+/// standard output. The writer is locked by each subtask writing a texture.
+pub struct StreamSink {
+  path: PathBuf,
+  format: StreamFormat,
+
+  /// Opened by the first written texture
+  writer: Mutex<Option<StreamWriter<Box<dyn Write + Send>>>>,
+}
+
+impl StreamSink {
+
+  pub fn new(path: PathBuf, format: StreamFormat) -> StreamSink {
+    StreamSink {
+      path,
+      format,
+      writer: Mutex::new(None),
+    }
+  }
+
+  /// Makes a sink writing in an already opened <i>writer</i>
+  pub fn with_writer(path: PathBuf, format: StreamFormat,
+    writer: Box<dyn Write + Send>) -> StreamSink {
+      StreamSink {
+        path,
+        format,
+        writer: Mutex::new(Some(StreamWriter::new(writer, format))),
+      }
+  }
+
+  fn open_writer(&self) -> Result<StreamWriter<Box<dyn Write + Send>>> {
+    let writer: Box<dyn Write + Send> = if self.path == Path::new(STDOUT_PATH)
+    {
+      Box::new(io::stdout())
+    } else {
+      Box::new(OpenOptions::new().write(true).create(true)
+        .truncate(true).open(&self.path)?)
+    };
+    Ok(StreamWriter::new(writer, self.format))
+  }
+}
+
+impl Sink for StreamSink {
+  fn write(&self, name: &str, image: &RgbaImage, format: ImageFormat)
+    -> Result<()> {
+      let bytes = encode(image, format)?;
+      let mut writer = self.writer.lock().unwrap();
+      if writer.is_none() {
+        *writer = Some(self.open_writer()?);
+      }
+      writer.as_mut().unwrap().write(
+        &format!("{}.{}", name, format.extensions_str()[0]), &bytes)
+  }
+
+  /// Closes an archive with its manifest. A stream without any texture,
+  /// like the one of a task cancelled while waiting, is not opened.
+  fn finish(&self) -> Result<()> {
+    match self.writer.lock().unwrap().as_mut() {
+      Some(writer) => writer.finish(),
+      None => Ok(()),
+    }
+  }
+
+  fn get_path(&self) -> String {
+    self.path.display().to_string()
+  }
+
+  fn is_exclusive(&self) -> bool {
+    true
+  }
+
+  /// Writes in a new file whose name ends with "_retry", or in the standard
+  /// output again
+  fn retry(&self) -> Result<Box<dyn Sink>> {
+    let path = if self.path == Path::new(STDOUT_PATH) {
+      self.path.clone()
+    } else {
+      suffixed(&self.path, "_retry")
+    };
+    Ok(Box::new(StreamSink::new(path, self.format)))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+
+  #[test]
+  fn it_streams_textures_in_a_tar_archive() {
+    let path = std::env::temp_dir().join("liriodendron_sink_test.tar");
+    let sink = StreamSink::new(path.clone(), StreamFormat::Tar);
+    let image = RgbaImage::new(2, 2);
+    sink.write("1", &image, ImageFormat::Png).unwrap();
+    sink.write("2", &image, ImageFormat::Png).unwrap();
+    sink.finish().unwrap();
+    let archive = fs::read(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    let size = encode(&image, ImageFormat::Png).unwrap().len();
+    let entry = 512 + size.div_ceil(512) * 512;
+    assert!((archive.len() == entry * 2 + 512 * 4) &&
+      (&archive[0..5] == b"1.png") && (&archive[entry..entry + 5] == b"2.png")
+      && (&archive[entry * 2..entry * 2 + 12] == b"manifest.txt") &&
+      (&archive[entry * 2 + 512..entry * 2 + 524] == b"1.png\n2.png\n"));
+  }
+
+  #[test]
+  fn it_does_not_open_a_stream_without_textures() {
+    let path = std::env::temp_dir().join("liriodendron_sink_test_empty.tar");
+    let sink = StreamSink::new(path.clone(), StreamFormat::Tar);
+    assert!(sink.finish().is_ok() && !path.exists());
+  }
+
+  #[test]
+  fn it_suffixes_the_file_of_each_task_but_shares_a_device() {
+    let file = Output::Stream(PathBuf::from("out/textures.tar"),
+      StreamFormat::Tar);
+    let device = Output::Stream(PathBuf::from("/dev/null"),
+      StreamFormat::Tar);
+    let third = Output::Stream(PathBuf::from("out/textures_3.tar"),
+      StreamFormat::Tar);
+    assert!((file.for_task(1) == file) && (file.for_task(3) == third) &&
+      (device.for_task(2) == device) &&
+      (Output::Directory.for_task(2) == Output::Directory));
+  }
+
+  #[test]
+  fn it_retries_in_a_new_file_or_the_same_directory() {
+    let stream = StreamSink::new(PathBuf::from("out/textures.tar"),
+      StreamFormat::Tar);
+    let stdout = StreamSink::new(PathBuf::from(STDOUT_PATH),
+      StreamFormat::Zip);
+    let directory = DirectorySink {
+      directory_name: String::from("textures"),
+    };
+    assert!((stream.retry().unwrap().get_path() ==
+      Path::new("out/textures_retry.tar").display().to_string()) &&
+      (stdout.retry().unwrap().get_path() == STDOUT_PATH) &&
+      (directory.retry().unwrap().get_path() == "textures"));
+  }
+}
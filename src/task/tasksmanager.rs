@@ -1,89 +1,313 @@
-extern crate futures;
-use futures::future::{Future, lazy};
-
 extern crate std;
 use std::collections::{HashMap, VecDeque};
-use std::marker::Send;
-use std::sync::{Arc, Mutex};
+use std::marker::{Send, Sync};
+use std::sync::{Arc, Condvar, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::thread;
+use std::time::{Duration, Instant};
 
-extern crate tokio_threadpool;
-use tokio_threadpool::Builder;
+use liriodendron::error::{Error, Result};
 
 use crate::log;
-use crate::task::{Task, generation};
+use crate::task::{Task, generation, threadpool::ThreadPool};
 
 const TASKS_QUEUE_FULL: bool = true;
 
+/// Number of chunks per thread a task's subtasks are divided in
+const CHUNKS_PER_THREAD: u32 = 4;
+
+/// Maximum number of subtasks run by a thread before it reports its chunk
+/// as done
+const MAX_CHUNK_SIZE: u32 = 64;
+
+/// States of a task in the tasks list
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TaskState {
+  Waiting,
+  Running,
+
+  /// Aborted task whose running subtasks are ending
+  Cancelled,
+}
+
+/// Summary of a task displayed in the tasks monitor
+pub struct TaskInfo {
+  pub id: usize,
+  pub state: TaskState,
+  pub done_subtasks: u32,
+  pub nb_subtasks: u32,
+  pub output: String,
+
+  /// Time since the task started
+  pub elapsed: Option<Duration>,
+
+  /// Estimated time before the task ends, once a subtask is done
+  pub eta: Option<Duration>,
+}
+
+/// Lost results of a finished task, reported until they are retried or
+/// dismissed
+pub struct TaskFailure {
+  pub id: usize,
+  pub output: String,
+
+  /// Names of the lost results
+  pub names: Vec<String>,
+
+  /// Error of the first failed subtask, or of the task's end
+  pub error: Error,
+
+  task: Arc<dyn Task + Send + Sync>,
+  subtasks: Vec<u32>,
+}
+
+impl TaskFailure {
+
+  /// A failure can be retried if results of subtasks are lost
+  pub fn can_retry(&self) -> bool {
+    !self.subtasks.is_empty()
+  }
+}
+
+/// Distributes the subtasks of a running task in chunks to the threads
+/// which claim them
+#[derive(Clone)]
+struct Schedule {
+  id: usize,
+  task: Arc<dyn Task + Send + Sync>,
+
+  /// First subtask of the next claimed chunk
+  next_subtask: Arc<AtomicU32>,
+
+  /// Number of run subtasks, the thread running the last one finishes the
+  /// task
+  done_subtasks: Arc<AtomicU32>,
+
+  chunk_size: u32,
+
+  /// When <i>true</i>, remaining subtasks are skipped
+  is_cancelled: Arc<AtomicBool>,
+
+  /// Set when the task leaves the queue
+  start: Option<Instant>,
+
+  /// Subtasks whose results are lost, with their error
+  failures: Arc<Mutex<Vec<(u32, Error)>>>,
+}
+
+impl Schedule {
+  fn new(id: usize, task: Arc<dyn Task + Send + Sync>,
+    threads_number: usize) -> Schedule {
+      let chunks = CHUNKS_PER_THREAD * threads_number as u32;
+      let chunk_size =
+        (task.get_nb_subtasks() / chunks).clamp(1, MAX_CHUNK_SIZE);
+      Schedule {
+        id,
+        task,
+        next_subtask: Arc::new(AtomicU32::new(1)),
+        done_subtasks: Arc::new(AtomicU32::new(0)),
+        chunk_size,
+        is_cancelled: Arc::new(AtomicBool::new(false)),
+        start: None,
+        failures: Arc::new(Mutex::new(Vec::new())),
+      }
+  }
+
+  /// Return the first and the last subtasks of the next chunk, or
+  /// <i>None</i> when every subtask is claimed
+  fn claim(&self) -> Option<(u32, u32)> {
+    let nb_subtasks = self.task.get_nb_subtasks();
+    self.next_subtask.fetch_update(Ordering::SeqCst, Ordering::SeqCst,
+      |next| if next > nb_subtasks { None } else {
+        Some(next + self.chunk_size) })
+      .ok()
+      .map(|first| (first, (first + self.chunk_size - 1).min(nb_subtasks)))
+  }
+
+  fn is_finished(&self) -> bool {
+    self.done_subtasks.load(Ordering::SeqCst) >= self.task.get_nb_subtasks()
+  }
+
+  fn is_cancelled(&self) -> bool {
+    self.is_cancelled.load(Ordering::SeqCst)
+  }
+
+  /// Runs <i>subtask</i> unless the task is cancelled, and keeps its error
+  fn run(&self, subtask: u32) {
+    if !self.is_cancelled() {
+      if let Err(error) = self.task.run(subtask) {
+        self.failures.lock().unwrap().push((subtask, error));
+      }
+    }
+  }
+
+  /// Return <i>true</i> if it was the last subtask of the task
+  fn done_subtask(&self) -> bool {
+    self.done_subtasks(1)
+  }
+
+  /// Return <i>true</i> if they were the last subtasks of the task
+  fn done_subtasks(&self, nb_subtasks: u32) -> bool {
+    let done = self.done_subtasks.fetch_add(nb_subtasks, Ordering::SeqCst);
+    (done < self.task.get_nb_subtasks()) &&
+      (done + nb_subtasks >= self.task.get_nb_subtasks())
+  }
+
+  /// Skips the subtasks which are not run yet. Unclaimed subtasks are done
+  /// immediately, claimed ones are skipped by their thread.
+  ///
+  /// Return <i>true</i> if no subtask is left to the threads
+  fn cancel(&self) -> bool {
+    self.is_cancelled.store(true, Ordering::SeqCst);
+    let nb_subtasks = self.task.get_nb_subtasks();
+    let next = self.next_subtask.swap(nb_subtasks + 1, Ordering::SeqCst);
+    next <= nb_subtasks && self.done_subtasks(nb_subtasks - next + 1)
+  }
+
+  fn get_info(&self, state: TaskState) -> TaskInfo {
+    let nb_subtasks = self.task.get_nb_subtasks();
+    let done_subtasks =
+      self.done_subtasks.load(Ordering::SeqCst).min(nb_subtasks);
+    let elapsed = self.start.map(|start| start.elapsed());
+    TaskInfo {
+      id: self.id,
+      state: if self.is_cancelled() { TaskState::Cancelled } else { state },
+      done_subtasks,
+      nb_subtasks,
+      output: self.task.get_output(),
+      elapsed,
+      eta: elapsed.filter(|_| done_subtasks > 0).map(|elapsed|
+        elapsed.mul_f64(f64::from(nb_subtasks - done_subtasks) /
+          f64::from(done_subtasks))),
+    }
+  }
+}
+
+/// Closes the output of a task and reports its lost results
+fn close_task(schedule: &Schedule, failures: &Mutex<Vec<TaskFailure>>) {
+  let finish_error = schedule.task.finish().err();
+  let mut failed_subtasks =
+    std::mem::take(&mut *schedule.failures.lock().unwrap());
+  failed_subtasks.sort_unstable_by_key(|&(subtask, _)| subtask);
+  let subtasks = failed_subtasks.iter().map(|&(subtask, _)| subtask)
+    .collect::<Vec<u32>>();
+  let error = failed_subtasks.into_iter().map(|(_, error)| error).next()
+    .or(finish_error);
+  if let Some(error) = error {
+    failures.lock().unwrap().push(TaskFailure {
+      id: schedule.id,
+      output: schedule.task.get_output(),
+      names: subtasks.iter().map(|&subtask| schedule.task.get_name(subtask))
+        .collect(),
+      error,
+      task: Arc::clone(&schedule.task),
+      subtasks,
+    });
+  }
+}
+
+/// Ends a task whose subtasks are all done
+fn finish_task(schedule: &Schedule, running_tasks: &Mutex<usize>,
+  done_tasks: &Mutex<usize>, failures: &Mutex<Vec<TaskFailure>>) {
+    close_task(schedule, failures);
+    *running_tasks.lock().unwrap() -= 1;
+    *done_tasks.lock().unwrap() += 1;
+}
+
+/// Progression of a thread's chunk of subtasks
+type Progress = (thread::ThreadId, (u32, u32));
+
 /// Multi-threaded struct to support expensive users queries and avoid
 /// blocking tasks
 pub struct TasksManager {
 
-  /// Running threads counter
-  nb_running_threads: Arc<Mutex<usize>>,
+  /// Running threads counter, notified each time a thread ends its work
+  nb_running_threads: Arc<(Mutex<usize>, Condvar)>,
 
-  /// Queue's length
-  waiting_tasks: Arc<Mutex<usize>>,
+  /// Started tasks which are not finished
+  nb_running_tasks: Arc<Mutex<usize>>,
 
-  /// When <i>true</i>, threads send their progression
-  is_sending: Arc<Mutex<bool>>,
+  /// Finished tasks counter
+  nb_done_tasks: Arc<Mutex<usize>>,
 
-  threadpool: tokio_threadpool::ThreadPool,
-  sender: Sender<Option<(thread::ThreadId, (u16, u16))>>,
-  receiver: Receiver<Option<(thread::ThreadId, (u16, u16))>>,
+  /// Failures reported by the threads finishing tasks
+  reported_failures: Arc<Mutex<Vec<TaskFailure>>>,
+
+  /// Failures waiting for the user
+  failures: VecDeque<TaskFailure>,
+
+  threadpool: ThreadPool,
+  sender: Sender<Progress>,
+  receiver: Receiver<Progress>,
 
   /// Waiting tasks are stocked in this queue
-  tasks: VecDeque::<Box<dyn Task + Send>>,
+  tasks: VecDeque::<Schedule>,
+
+  /// Running tasks which are not finished
+  schedules: Vec<Schedule>,
+
+  /// Identifier of the next queued task
+  next_id: usize,
+
+  /// When <i>true</i>, waiting tasks are not started
+  is_paused: bool,
 
   todo_tasks: usize,
-  done_tasks: usize,
 
   /// Stocks threads' progression
-  threads_monitor: HashMap::<thread::ThreadId, (u16, u16)>,
+  threads_monitor: HashMap::<thread::ThreadId, (u32, u32)>,
 
   /// Customizable member
   threads_number: usize,
 
   /// Customizable member
   max_tasks: usize,
+
+  /// Customizable member
+  output: generation::sink::Output,
 }
 
 impl TasksManager {
 
-  pub fn new(threads_number: usize, max_tasks: usize) -> TasksManager {
-    let (sender, receiver): (Sender<Option<(thread::ThreadId, (u16, u16))>>,
-      Receiver<Option<(thread::ThreadId, (u16, u16))>>) = channel();
+  pub fn new(threads_number: usize, max_tasks: usize,
+    output: generation::sink::Output) -> TasksManager {
+    let (sender, receiver): (Sender<Progress>, Receiver<Progress>) =
+      channel();
     TasksManager {
-      nb_running_threads: Arc::new(Mutex::new(0)),
-      waiting_tasks: Arc::new(Mutex::new(0)),
-      is_sending: Arc::new(Mutex::new(false)),
-      threadpool: Builder::new().pool_size(threads_number).build(),
+      nb_running_threads: Arc::new((Mutex::new(0), Condvar::new())),
+      nb_running_tasks: Arc::new(Mutex::new(0)),
+      nb_done_tasks: Arc::new(Mutex::new(0)),
+      reported_failures: Arc::new(Mutex::new(Vec::new())),
+      failures: VecDeque::new(),
+      threadpool: ThreadPool::new(threads_number),
       sender: sender,
       receiver: receiver,
 
-      tasks:
-        VecDeque::<Box<dyn Task + Send>>::with_capacity(max_tasks),
+      tasks: VecDeque::<Schedule>::with_capacity(max_tasks),
+      schedules: Vec::with_capacity(threads_number),
+      next_id: 1,
+      is_paused: false,
       todo_tasks: 0,
-      done_tasks: 0,
-      threads_monitor: HashMap::<thread::ThreadId, (u16, u16)>::with_capacity(
+      threads_monitor: HashMap::<thread::ThreadId, (u32, u32)>::with_capacity(
         threads_number + max_tasks),
 
       threads_number: threads_number,
       max_tasks: max_tasks,
+      output,
     }
   }
 
   pub fn new_generation_task(&mut self, log: &log::Log) ->
-    (std::io::Result<()>, bool) {
-      let res = generation::Generation::new(log);
+    (Result<()>, bool) {
+      let res = generation::Generation::new(log,
+        &self.output.for_task(self.next_id));
       match res {
         Ok(generation) => {
           if self.tasks.len() < self.max_tasks {
-            let mut lock = self.waiting_tasks.lock().unwrap();
-            self.tasks.push_back(Box::new(generation));
-            *lock = self.tasks.len();
-            std::mem::drop(lock);
+            self.tasks.push_back(Schedule::new(self.next_id,
+              Arc::new(generation), self.threads_number));
+            self.next_id += 1;
             return (Ok(()), TASKS_QUEUE_FULL)
           } else {
             return (Ok(()), !TASKS_QUEUE_FULL)
@@ -94,130 +318,245 @@ impl TasksManager {
   }
 
   fn get_nb_running_threads(&self) -> usize {
-    let lock = self.nb_running_threads.lock().unwrap();
+    let lock = self.nb_running_threads.0.lock().unwrap();
     let nb_running_threads = *lock;
     std::mem::drop(lock);
     nb_running_threads
   }
 
+  fn get_nb_running_tasks(&self) -> usize {
+    let lock = self.nb_running_tasks.lock().unwrap();
+    let nb_running_tasks = *lock;
+    std::mem::drop(lock);
+    nb_running_tasks
+  }
+
   pub fn get_todo_and_max_tasks(&self) -> (usize, usize) {
-    (self.get_nb_running_threads() + self.tasks.len(),
+    (self.get_nb_running_tasks() + self.tasks.len(),
       self.max_tasks + self.threads_number)
   }
 
   pub fn get_done_and_todo_tasks(&self) -> (usize, usize) {
-    (self.done_tasks, self.todo_tasks)
+    let lock = self.nb_done_tasks.lock().unwrap();
+    let done_tasks = *lock;
+    std::mem::drop(lock);
+    (done_tasks, self.todo_tasks)
   }
 
-  /// Checks if a task is in the queue. If a thread is available, it runs
-  /// the task and pop it from the queue.
+  /// Gives a chunk of subtasks to each available thread. A new task from
+  /// the queue is started before running tasks get more threads, unless
+  /// the queue is paused. A task waits while its exclusive output is
+  /// written by a running task.
   pub fn check_queue(&mut self) {
-    if self.get_nb_running_threads() < self.threads_number {
-      if let Some(task) = self.tasks.pop_front() {
-
-        // These 2 mutexes are locked in the same time to not allow
-        // waiting_tasks = 0 and nb_running_threads = 0 while there are
-        // a last task in the queue
-        let mut lock = self.waiting_tasks.lock().unwrap();
-        let mut running_threads = self.nb_running_threads.lock().unwrap();
-        *running_threads += 1;
-        *lock = self.tasks.len();
-        std::mem::drop(running_threads);
-        std::mem::drop(lock);
-
-        let thread_sender = Sender::clone(&self.sender);
-        let mutex_threads = Arc::clone(&self.nb_running_threads);
-        let mutex_issending = Arc::clone(&self.is_sending);
-
-        self.threadpool.spawn(lazy(move || {
-
-          let mut current_subtask = 1;
-          loop {
-            if current_subtask > task.get_nb_subtasks() {
-              break
-            }
-            task.run(current_subtask);
-            let is_sending = mutex_issending.lock().unwrap();
-            if *is_sending {
-              thread_sender.send(Some((thread::current().id(),
-                (current_subtask, task.get_nb_subtasks())))).unwrap();
+    self.schedules.retain(|schedule| !schedule.is_finished());
+    self.collect_failures();
+    while self.get_nb_running_threads() < self.threads_number {
+      let waiting_task = if self.is_paused { None } else {
+        self.take_startable_task() };
+      let (schedule, chunk) = match waiting_task {
+        Some(mut schedule) => {
+          schedule.start = Some(Instant::now());
+          let chunk = schedule.claim();
+          *self.nb_running_tasks.lock().unwrap() += 1;
+          self.schedules.push(schedule.clone());
+          (schedule, chunk)
+        },
+        None => match self.schedules.iter()
+          .find_map(|schedule| schedule.claim()
+            .map(|chunk| (schedule.clone(), Some(chunk)))) {
+              Some(claimed) => claimed,
+              None => break,
+          },
+      };
+      *self.nb_running_threads.0.lock().unwrap() += 1;
+
+      let thread_sender = Sender::clone(&self.sender);
+      let mutex_threads = Arc::clone(&self.nb_running_threads);
+      let mutex_tasks = Arc::clone(&self.nb_running_tasks);
+      let mutex_done = Arc::clone(&self.nb_done_tasks);
+      let mutex_failures = Arc::clone(&self.reported_failures);
+
+      self.threadpool.spawn(move || {
+
+        let mut chunk = chunk;
+        while let Some((first_subtask, last_subtask)) = chunk {
+          for current_subtask in first_subtask..=last_subtask {
+            schedule.run(current_subtask);
+            thread_sender.send((thread::current().id(),
+              (current_subtask - first_subtask + 1,
+              last_subtask - first_subtask + 1))).unwrap();
+            if schedule.done_subtask() {
+              finish_task(&schedule, &mutex_tasks, &mutex_done,
+                &mutex_failures);
             }
-            std::mem::drop(is_sending);
-            current_subtask += 1;
           }
+          chunk = schedule.claim();
+        }
 
-          let mut running_threads = mutex_threads.lock().unwrap();
-          *running_threads -= 1;
-          std::mem::drop(running_threads);
-
-          Ok(())
-        }));
-      }
+        let (running_threads, thread_done) = &*mutex_threads;
+        *running_threads.lock().unwrap() -= 1;
+        thread_done.notify_all();
+      });
     }
   }
 
-  pub fn start_sender_channel(&mut self) {
-    let mut is_sending = self.is_sending.lock().unwrap();
-    *is_sending = true;
-    std::mem::drop(is_sending);
+  /// Removes from the queue the first waiting task whose exclusive output
+  /// is not written by a running task
+  fn take_startable_task(&mut self) -> Option<Schedule> {
+    let schedules = &self.schedules;
+    self.tasks.iter()
+      .position(|waiting| !waiting.task.has_exclusive_output() ||
+        !schedules.iter().any(|schedule| !schedule.is_finished() &&
+          (schedule.task.get_output() == waiting.task.get_output())))
+      .and_then(|index| self.tasks.remove(index))
   }
 
-  pub fn compute_todotasks(&mut self) {
-    self.todo_tasks = self.get_nb_running_threads() + self.tasks.len();
-  }
-
-  /// Unlocks a blocking std::sync::mpsc::Receiver.recv() call
-  pub fn spawn_unlocking_thread(&self) {
-    let thread_sender = Sender::clone(&self.sender);
-    let mutex_threads = Arc::clone(&self.nb_running_threads);
-    let mutex_tasks = Arc::clone(&self.waiting_tasks);
-    thread::spawn(move || {
-      let mut break_loop;
-      loop {
-        break_loop = true;
-
-        // These 2 mutexes are locked in the same time to not allow
-        // waiting_tasks = 0 and running_threads = 0 while there are
-        // a last task
-        let waiting_tasks = mutex_tasks.lock().unwrap();
-        let running_threads = mutex_threads.lock().unwrap();
-        break_loop = break_loop && (*running_threads == 0);
-        break_loop = break_loop && (*waiting_tasks == 0);
-        std::mem::drop(running_threads);
-        std::mem::drop(waiting_tasks);
-
-        if break_loop {
-          thread_sender.send(None).unwrap();
-          break;
+  /// Return running tasks then waiting tasks
+  pub fn get_tasks_info(&self) -> Vec<TaskInfo> {
+    self.schedules.iter()
+      .filter(|schedule| !schedule.is_finished())
+      .map(|schedule| schedule.get_info(TaskState::Running))
+      .chain(self.tasks.iter()
+        .map(|schedule| schedule.get_info(TaskState::Waiting)))
+      .collect()
+  }
+
+  /// Removes a waiting task from the queue or aborts a running task at its
+  /// next subtask. A cancelled task is finished, so its output is closed.
+  pub fn cancel_task(&mut self, id: usize) {
+    if let Some(index) = self.tasks.iter().position(|schedule|
+      schedule.id == id) {
+        let schedule = self.tasks.remove(index).unwrap();
+        close_task(&schedule, &self.reported_failures);
+    } else if let Some(schedule) = self.schedules.iter().find(|schedule|
+      schedule.id == id) {
+        if schedule.cancel() {
+          finish_task(schedule, &self.nb_running_tasks, &self.nb_done_tasks,
+            &self.reported_failures);
         }
+    }
+  }
+
+  /// Cancels every waiting and running task
+  pub fn cancel_all(&mut self) {
+    let ids = self.get_tasks_info().iter().map(|info| info.id)
+      .collect::<Vec<usize>>();
+    for id in ids {
+      self.cancel_task(id);
+    }
+  }
+
+  /// Moves the failures reported by the threads to the user's ones
+  fn collect_failures(&mut self) {
+    let mut reported_failures = self.reported_failures.lock().unwrap();
+    self.failures.extend(reported_failures.drain(..));
+  }
+
+  /// Return the oldest failure the user has not dismissed
+  pub fn get_failure(&self) -> Option<&TaskFailure> {
+    self.failures.front()
+  }
+
+  pub fn dismiss_failure(&mut self) {
+    self.failures.pop_front();
+  }
+
+  /// Queues a task running again the failed subtasks of the oldest failure,
+  /// which is dismissed once its retry is queued
+  pub fn retry_failure(&mut self) -> (Result<()>, bool) {
+    let failure = match self.failures.front() {
+      Some(failure) if failure.can_retry() => failure,
+      _ => return (Ok(()), TASKS_QUEUE_FULL),
+    };
+    if self.tasks.len() >= self.max_tasks {
+      return (Ok(()), !TASKS_QUEUE_FULL)
+    }
+    match failure.task.retry(failure.subtasks.clone()) {
+      Ok(task) => {
+        self.tasks.push_back(Schedule::new(self.next_id, task,
+          self.threads_number));
+        self.next_id += 1;
+        self.failures.pop_front();
+        (Ok(()), TASKS_QUEUE_FULL)
+      },
+      Err(error) => (Err(error), TASKS_QUEUE_FULL),
+    }
+  }
+
+  /// Return every failure the user has not dismissed
+  pub fn take_failures(&mut self) -> Vec<TaskFailure> {
+    self.collect_failures();
+    self.failures.drain(..).collect()
+  }
+
+  pub fn is_paused(&self) -> bool {
+    self.is_paused
+  }
+
+  /// Pauses or resumes the start of waiting tasks
+  pub fn set_paused(&mut self, is_paused: bool) {
+    self.is_paused = is_paused;
+  }
+
+  /// Runs the remaining tasks without the user interface. The process, and
+  /// so the terminal, is blocked until they end.
+  pub fn finish_without_interface(&mut self) {
+    self.is_paused = false;
+    loop {
+      self.check_queue();
+      self.receive_progress();
+      if !self.is_running() {
+        break
       }
-    });
+
+      // A waiting task starts once a thread is available
+      let max_running_threads = if self.tasks.is_empty() { 0 } else {
+        self.threads_number - 1 };
+      self.wait_for_threads(max_running_threads);
+    }
+  }
+
+  pub fn compute_todotasks(&mut self) {
+    self.todo_tasks = self.get_nb_running_tasks() + self.tasks.len();
+  }
+
+  /// Blocks until at most <i>max_running_threads</i> threads are running,
+  /// without polling: each thread notifies the end of its work
+  pub fn wait_for_threads(&self, max_running_threads: usize) {
+    let (running_threads, thread_done) = &*self.nb_running_threads;
+    let lock = running_threads.lock().unwrap();
+    let lock = thread_done.wait_while(lock,
+      |running_threads| *running_threads > max_running_threads).unwrap();
+    std::mem::drop(lock);
   }
 
   pub fn shutdown(self) {
-    self.threadpool.shutdown().wait().unwrap();
+    self.threadpool.shutdown();
   }
 
-  pub fn get_threads_monitor(&self) -> &HashMap<thread::ThreadId, (u16, u16)> {
+  pub fn get_threads_monitor(&self) -> &HashMap<thread::ThreadId, (u32, u32)> {
     &self.threads_monitor
   }
 
-  /// While the receiver is receiving data from the threadpool, a thread is
-  /// running
-  pub fn is_running(&mut self) -> bool {
-    let is_receiving;
-    if let Ok(Some((thread_id, (gen_done, gen_todo)))) =
-      self.receiver.recv() {
-        is_receiving = true;
-        self.threads_monitor.insert(thread_id, (gen_done, gen_todo));
-        if gen_done == gen_todo {
-          self.threads_monitor.remove(&thread_id);
-          self.done_tasks += 1;
-        }
-    } else {
-      is_receiving = false;
+  /// Monitors the progression sent by the threads without waiting for it
+  pub fn receive_progress(&mut self) {
+    while let Ok((thread_id, progress)) = self.receiver.try_recv() {
+      self.monitor(thread_id, progress);
     }
-    is_receiving
+  }
+
+  fn monitor(&mut self, thread_id: thread::ThreadId,
+    (gen_done, gen_todo): (u32, u32)) {
+      self.threads_monitor.insert(thread_id, (gen_done, gen_todo));
+      if gen_done == gen_todo {
+        self.threads_monitor.remove(&thread_id);
+      }
+  }
+
+  /// Tells whether a thread is running or a task is waiting, without
+  /// blocking the caller
+  pub fn is_running(&self) -> bool {
+    (self.get_nb_running_threads() > 0) || !self.tasks.is_empty()
   }
 }
 
@@ -269,8 +608,9 @@ mod tests {
   #[test]
   fn it_adds_a_new_generation_task() {
     if is_single_threaded_test(std::env::args()) {
-      let log = log::Log::new(0, 0);
-      let mut tasksmanager = TasksManager::new(1, 1);
+      let log = log::Log::new(0, 0, 10);
+      let mut tasksmanager = TasksManager::new(1, 1,
+        generation::sink::Output::Directory);
       let init_len = tasksmanager.tasks.len();
       let (_, task_added) = tasksmanager.new_generation_task(&log);
       assert!((init_len == 0) && (tasksmanager.tasks.len() == 1) &&
@@ -285,8 +625,9 @@ mod tests {
   #[test]
   fn it_does_not_add_a_new_generation_task_when_task_queue_is_full() {
     if is_single_threaded_test(std::env::args()) {
-      let log = log::Log::new(0, 0);
-      let mut tasksmanager = TasksManager::new(1, 1);
+      let log = log::Log::new(0, 0, 10);
+      let mut tasksmanager = TasksManager::new(1, 1,
+        generation::sink::Output::Directory);
       let (_, task_added1) = tasksmanager.new_generation_task(&log);
       let init_len = tasksmanager.tasks.len();
       let (_, task_added2) = tasksmanager.new_generation_task(&log);
@@ -302,8 +643,9 @@ mod tests {
   #[test]
   fn it_runs_a_generation_task() {
     if is_single_threaded_test(std::env::args()) {
-      let log = log::Log::new(0, 0);
-      let mut tasksmanager = TasksManager::new(1, 1);
+      let log = log::Log::new(0, 0, 10);
+      let mut tasksmanager = TasksManager::new(1, 1,
+        generation::sink::Output::Directory);
       let (_, task_added) = tasksmanager.new_generation_task(&log);
       let init_len = tasksmanager.tasks.len();
       tasksmanager.check_queue();
@@ -320,20 +662,15 @@ mod tests {
   #[test]
   fn it_can_not_run_a_generation_task_because_threads_are_busy() {
     if is_single_threaded_test(std::env::args()) {
-      let log = log::Log::new(0, 0);
-      let mut tasksmanager = TasksManager::new(1, 2);
-      let (_, task_added1) = tasksmanager.new_generation_task(&log);
-      let (_, task_added2) = tasksmanager.new_generation_task(&log);
-      let init_len = tasksmanager.tasks.len();
-
+      let mut tasksmanager = TasksManager::new(1, 2,
+        generation::sink::Output::Directory);
       let (sender, receiver) = channel();
-
-      let mutex_issending = Arc::clone(&tasksmanager.is_sending);
-      let simulate_heavy_task = thread::spawn(move || {
-        let is_sending = mutex_issending.lock().unwrap();
-        receiver.recv().unwrap();
-        std::mem::drop(is_sending);
+      let heavy_task = Arc::new(HeavyTask {
+        receiver: Mutex::new(receiver),
       });
+      tasksmanager.tasks.push_back(Schedule::new(1, heavy_task, 1));
+      tasksmanager.tasks.push_back(Schedule::new(2, counting_task(1), 1));
+      let init_len = tasksmanager.tasks.len();
 
       tasksmanager.check_queue();
       let second_len = tasksmanager.tasks.len();
@@ -345,14 +682,13 @@ mod tests {
       let third_len = tasksmanager.tasks.len();
 
       sender.send(()).unwrap();
-      simulate_heavy_task.join().unwrap();
+      tasksmanager.wait_for_threads(0);
 
       tasksmanager.check_queue();
 
       assert!((init_len == 2) && (second_len == 1) && (third_len == 1) &&
-        (tasksmanager.tasks.len() == 0) && task_added1 && task_added2);
+        (tasksmanager.tasks.len() == 0));
       tasksmanager.shutdown();
-      remove_generation_dirs();
     } else {
       assert!(false, "tests in taskmanager module must be run with this \
         cargo test option: \"--test-threads=1\"");
@@ -360,15 +696,23 @@ mod tests {
   }
 
   #[test]
-  fn it_spawns_a_thread_to_unlock_a_blocking_recv_call() {
+  fn it_tells_if_tasks_are_running_without_blocking() {
     if is_single_threaded_test(std::env::args()) {
-      let tasksmanager = TasksManager::new(1, 1);
-      tasksmanager.spawn_unlocking_thread();
-      let unlock_blocking_recv = match tasksmanager.receiver.recv().unwrap() {
-        None => true,
-        _ => false,
-      };
-      assert!(unlock_blocking_recv);
+      let mut tasksmanager = TasksManager::new(1, 1,
+        generation::sink::Output::Directory);
+      let (sender, receiver) = channel();
+      let heavy_task = Arc::new(HeavyTask {
+        receiver: Mutex::new(receiver),
+      });
+      let is_idle = !tasksmanager.is_running();
+      tasksmanager.tasks.push_back(Schedule::new(1, heavy_task, 1));
+      let is_waiting = tasksmanager.is_running();
+      tasksmanager.check_queue();
+      let is_busy = tasksmanager.is_running();
+      sender.send(()).unwrap();
+      tasksmanager.wait_for_threads(0);
+      assert!(is_idle && is_waiting && is_busy && !tasksmanager.is_running());
+      tasksmanager.shutdown();
     } else {
       assert!(false, "tests in taskmanager module must be run with this \
         cargo test option: \"--test-threads=1\"");
@@ -376,21 +720,20 @@ mod tests {
   }
 
   #[test]
-  fn it_spawns_threads_that_does_not_use_sender_channel() {
+  fn it_receives_progress_without_waiting() {
     if is_single_threaded_test(std::env::args()) {
-      let log = log::Log::new(0, 0);
-      let mut tasksmanager = TasksManager::new(1, 2);
-      let (_, task_added) = tasksmanager.new_generation_task(&log);
+      let mut tasksmanager = TasksManager::new(1, 1,
+        generation::sink::Output::Directory);
+      tasksmanager.tasks.push_back(Schedule::new(1, counting_task(2), 1));
       tasksmanager.check_queue();
-      tasksmanager.spawn_unlocking_thread();
-      let threads_are_using_sender_channel =
-        match tasksmanager.receiver.recv().unwrap() {
-          None => false,
-          _ => true,
-        };
-      assert!(!threads_are_using_sender_channel && task_added);
+      while tasksmanager.get_todo_and_max_tasks().0 > 0 {
+        thread::sleep(time::Duration::from_millis(1));
+      }
+      tasksmanager.receive_progress();
+      let is_drained = tasksmanager.receiver.try_recv().is_err();
+      tasksmanager.receive_progress();
+      assert!(is_drained && tasksmanager.get_threads_monitor().is_empty());
       tasksmanager.shutdown();
-      remove_generation_dirs();
     } else {
       assert!(false, "tests in taskmanager module must be run with this \
         cargo test option: \"--test-threads=1\"");
@@ -400,16 +743,13 @@ mod tests {
   #[test]
   fn it_spawns_threads_using_sender_channel() {
     if is_single_threaded_test(std::env::args()) {
-      let log = log::Log::new(0, 0);
-      let mut tasksmanager = TasksManager::new(1, 2);
+      let log = log::Log::new(0, 0, 10);
+      let mut tasksmanager = TasksManager::new(1, 2,
+        generation::sink::Output::Directory);
       let (_, task_added) = tasksmanager.new_generation_task(&log);
-      tasksmanager.start_sender_channel();
       tasksmanager.check_queue();
       let threads_are_using_sender_channel =
-        match tasksmanager.receiver.recv().unwrap() {
-          None => false,
-          _ => true,
-        };
+        tasksmanager.receiver.recv().is_ok();
       assert!(threads_are_using_sender_channel && task_added);
       tasksmanager.shutdown();
       remove_generation_dirs();
@@ -422,24 +762,19 @@ mod tests {
   #[test]
   fn it_receives_all_data_sended_by_threads_with_a_singlethreaded_threadpool() {
     if is_single_threaded_test(std::env::args()) {
-      let log = log::Log::new(0, 0);
-      let mut tasksmanager = TasksManager::new(1, 5);
+      let log = log::Log::new(0, 0, 10);
+      let mut tasksmanager = TasksManager::new(1, 5,
+        generation::sink::Output::Directory);
       let (_, task_added1) = tasksmanager.new_generation_task(&log);
       let (_, task_added2) = tasksmanager.new_generation_task(&log);
       let (_, task_added3) = tasksmanager.new_generation_task(&log);
       let (_, task_added4) = tasksmanager.new_generation_task(&log);
       let (_, task_added5) = tasksmanager.new_generation_task(&log);
-      tasksmanager.start_sender_channel();
       while tasksmanager.tasks.len() > 0 {
         tasksmanager.check_queue();
       }
-      let mut received_counter = 0;
-      while tasksmanager.is_running() {
-        received_counter += 1;
-        if received_counter == 5 {
-          tasksmanager.spawn_unlocking_thread();
-        }
-      }
+      tasksmanager.wait_for_threads(0);
+      let received_counter = tasksmanager.receiver.try_iter().count();
       assert!((received_counter == 5) && task_added1 && task_added2 &&
         task_added3 && task_added4 && task_added5);
       tasksmanager.shutdown();
@@ -453,24 +788,19 @@ mod tests {
   #[test]
   fn it_receives_all_data_sended_by_threads_with_a_multiplethreaded_threadpool() {
     if is_single_threaded_test(std::env::args()) {
-      let log = log::Log::new(0, 0);
-      let mut tasksmanager = TasksManager::new(5, 5);
+      let log = log::Log::new(0, 0, 10);
+      let mut tasksmanager = TasksManager::new(5, 5,
+        generation::sink::Output::Directory);
       let (_, task_added1) = tasksmanager.new_generation_task(&log);
       let (_, task_added2) = tasksmanager.new_generation_task(&log);
       let (_, task_added3) = tasksmanager.new_generation_task(&log);
       let (_, task_added4) = tasksmanager.new_generation_task(&log);
       let (_, task_added5) = tasksmanager.new_generation_task(&log);
-      tasksmanager.start_sender_channel();
       while tasksmanager.tasks.len() > 0 {
         tasksmanager.check_queue();
       }
-      let mut received_counter = 0;
-      while tasksmanager.is_running() {
-        received_counter += 1;
-        if received_counter == 5 {
-          tasksmanager.spawn_unlocking_thread();
-        }
-      }
+      tasksmanager.wait_for_threads(0);
+      let received_counter = tasksmanager.receiver.try_iter().count();
       assert!((received_counter == 5) && task_added1 && task_added2 &&
         task_added3 && task_added4 && task_added5);
       tasksmanager.shutdown();
@@ -480,4 +810,261 @@ mod tests {
         cargo test option: \"--test-threads=1\"");
     }
   }
+
+  /// Counts its run subtasks and its finish calls, subtasks which are
+  /// multiples of <i>failing_multiple</i> fail
+  struct CountingTask {
+    nb_subtasks: u32,
+    runs: AtomicU32,
+    finishes: AtomicU32,
+    failing_multiple: Option<u32>,
+  }
+
+  impl Task for CountingTask {
+    fn run(&self, current_subtask: u32) -> Result<()> {
+      self.runs.fetch_add(1, Ordering::SeqCst);
+      match self.failing_multiple {
+        Some(multiple) if current_subtask % multiple == 0 =>
+          Err(Error::from(
+            std::io::Error::from(std::io::ErrorKind::PermissionDenied))),
+        _ => Ok(()),
+      }
+    }
+
+    fn get_nb_subtasks(&self) -> u32 {
+      self.nb_subtasks
+    }
+
+    fn finish(&self) -> Result<()> {
+      self.finishes.fetch_add(1, Ordering::SeqCst);
+      Ok(())
+    }
+
+    fn get_output(&self) -> String {
+      String::from("counting")
+    }
+
+    fn has_exclusive_output(&self) -> bool {
+      true
+    }
+
+    fn get_name(&self, subtask: u32) -> String {
+      subtask.to_string()
+    }
+
+    fn retry(&self, subtasks: Vec<u32>)
+      -> Result<Arc<dyn Task + Send + Sync>> {
+        Ok(counting_task(subtasks.len() as u32))
+    }
+  }
+
+  /// Runs its subtask once it receives a message
+  struct HeavyTask {
+    receiver: Mutex<Receiver<()>>,
+  }
+
+  impl Task for HeavyTask {
+    fn run(&self, _current_subtask: u32) -> Result<()> {
+      self.receiver.lock().unwrap().recv().unwrap();
+      Ok(())
+    }
+
+    fn get_nb_subtasks(&self) -> u32 {
+      1
+    }
+
+    fn finish(&self) -> Result<()> {
+      Ok(())
+    }
+
+    fn get_output(&self) -> String {
+      String::new()
+    }
+
+    fn has_exclusive_output(&self) -> bool {
+      false
+    }
+
+    fn get_name(&self, subtask: u32) -> String {
+      subtask.to_string()
+    }
+
+    fn retry(&self, _subtasks: Vec<u32>)
+      -> Result<Arc<dyn Task + Send + Sync>> {
+        Err(Error::InvalidArgument(String::from("heavy tasks are not \
+          retried.")))
+    }
+  }
+
+  fn counting_task(nb_subtasks: u32) -> Arc<CountingTask> {
+    Arc::new(CountingTask {
+      nb_subtasks,
+      runs: AtomicU32::new(0),
+      finishes: AtomicU32::new(0),
+      failing_multiple: None,
+    })
+  }
+
+  #[test]
+  fn it_reports_and_retries_the_failed_subtasks() {
+    if is_single_threaded_test(std::env::args()) {
+      let mut tasksmanager = TasksManager::new(2, 1,
+        generation::sink::Output::Directory);
+      let task = Arc::new(CountingTask {
+        nb_subtasks: 20,
+        runs: AtomicU32::new(0),
+        finishes: AtomicU32::new(0),
+        failing_multiple: Some(7),
+      });
+      tasksmanager.tasks.push_back(Schedule::new(1, task, 2));
+      tasksmanager.check_queue();
+      tasksmanager.wait_for_threads(0);
+      tasksmanager.check_queue();
+      let (names, is_denied, can_retry) = match tasksmanager.get_failure() {
+        Some(failure) => (failure.names.clone(), match &failure.error {
+          Error::Io(error) =>
+            error.kind() == std::io::ErrorKind::PermissionDenied,
+          _ => false,
+        }, failure.can_retry()),
+        None => (Vec::new(), false, false),
+      };
+      let (retried, _) = tasksmanager.retry_failure();
+      let info = tasksmanager.get_tasks_info();
+      assert!((names == vec!["7", "14"]) &&
+        is_denied && can_retry &&
+        retried.is_ok() && tasksmanager.get_failure().is_none() &&
+        (info.len() == 1) && (info[0].nb_subtasks == 2));
+      tasksmanager.shutdown();
+    } else {
+      assert!(false, "tests in taskmanager module must be run with this \
+        cargo test option: \"--test-threads=1\"");
+    }
+  }
+
+  #[test]
+  fn it_divides_subtasks_in_chunks() {
+    let schedule = Schedule::new(1, counting_task(1000), 4);
+    let mut chunks = Vec::new();
+    while let Some(chunk) = schedule.claim() {
+      chunks.push(chunk);
+    }
+    let small_schedule = Schedule::new(2, counting_task(3), 4);
+    assert!((chunks.len() == 17) && (chunks[0] == (1, 62)) &&
+      (chunks[16] == (993, 1000)) && schedule.claim().is_none() &&
+      (small_schedule.claim() == Some((1, 1))) &&
+      (small_schedule.chunk_size == 1));
+  }
+
+  #[test]
+  fn it_spreads_a_task_across_threads() {
+    if is_single_threaded_test(std::env::args()) {
+      let mut tasksmanager = TasksManager::new(4, 1,
+        generation::sink::Output::Directory);
+      let task = counting_task(300);
+      tasksmanager.tasks.push_back(Schedule::new(1, Arc::clone(&task) as
+        Arc<dyn Task + Send + Sync>, 4));
+      tasksmanager.check_queue();
+      let init_len = tasksmanager.tasks.len();
+      tasksmanager.shutdown();
+      assert!((init_len == 0) && (task.runs.load(Ordering::SeqCst) == 300) &&
+        (task.finishes.load(Ordering::SeqCst) == 1));
+    } else {
+      assert!(false, "tests in taskmanager module must be run with this \
+        cargo test option: \"--test-threads=1\"");
+    }
+  }
+
+  #[test]
+  fn it_skips_the_subtasks_of_a_cancelled_schedule() {
+    let task = counting_task(10);
+    let schedule = Schedule::new(1, Arc::clone(&task) as
+      Arc<dyn Task + Send + Sync>, 1);
+    let chunk = schedule.claim();
+    let is_done = schedule.cancel();
+    let mut is_finished = false;
+    for _ in 1..=2 {
+      is_finished = is_finished || schedule.done_subtask();
+    }
+    let info = schedule.get_info(TaskState::Running);
+    assert!((chunk == Some((1, 2))) && !is_done && is_finished &&
+      schedule.claim().is_none() && (info.state == TaskState::Cancelled) &&
+      (info.done_subtasks == 10));
+  }
+
+  #[test]
+  fn it_cancels_a_waiting_task() {
+    if is_single_threaded_test(std::env::args()) {
+      let log = log::Log::new(0, 0, 10);
+      let mut tasksmanager = TasksManager::new(1, 2,
+        generation::sink::Output::Directory);
+      let (first, _) = tasksmanager.new_generation_task(&log);
+      let (second, _) = tasksmanager.new_generation_task(&log);
+      let ids = tasksmanager.get_tasks_info().iter().map(|info| info.id)
+        .collect::<Vec<usize>>();
+      tasksmanager.cancel_task(ids[0]);
+      let info = tasksmanager.get_tasks_info();
+      assert!(first.is_ok() && second.is_ok() && (ids == vec![1, 2]) &&
+        (info.len() == 1) && (info[0].id == 2) &&
+        (info[0].state == TaskState::Waiting) &&
+        (tasksmanager.tasks.len() == 1));
+      remove_generation_dirs();
+    } else {
+      assert!(false, "tests in taskmanager module must be run with this \
+        cargo test option: \"--test-threads=1\"");
+    }
+  }
+
+  #[test]
+  fn it_does_not_start_waiting_tasks_while_paused() {
+    if is_single_threaded_test(std::env::args()) {
+      let log = log::Log::new(0, 0, 10);
+      let mut tasksmanager = TasksManager::new(1, 1,
+        generation::sink::Output::Directory);
+      let (added, _) = tasksmanager.new_generation_task(&log);
+      tasksmanager.set_paused(true);
+      tasksmanager.check_queue();
+      let paused_len = tasksmanager.tasks.len();
+      tasksmanager.set_paused(false);
+      tasksmanager.check_queue();
+      assert!(added.is_ok() && (paused_len == 1) &&
+        tasksmanager.tasks.is_empty());
+      tasksmanager.shutdown();
+      remove_generation_dirs();
+    } else {
+      assert!(false, "tests in taskmanager module must be run with this \
+        cargo test option: \"--test-threads=1\"");
+    }
+  }
+
+  #[test]
+  fn it_does_not_start_a_task_writing_in_a_busy_exclusive_output() {
+    let mut tasksmanager = TasksManager::new(1, 2,
+      generation::sink::Output::Directory);
+    let (_sender, receiver) = channel();
+    let heavy_task = Arc::new(HeavyTask {
+      receiver: Mutex::new(receiver),
+    });
+    tasksmanager.schedules.push(Schedule::new(1, counting_task(4), 1));
+    tasksmanager.tasks.push_back(Schedule::new(2, counting_task(4), 1));
+    tasksmanager.tasks.push_back(Schedule::new(3, heavy_task, 1));
+    let started = tasksmanager.take_startable_task().map(|task| task.id);
+    tasksmanager.schedules.clear();
+    let next = tasksmanager.take_startable_task().map(|task| task.id);
+    assert!((started == Some(3)) && (next == Some(2)) &&
+      tasksmanager.tasks.is_empty());
+  }
+
+  #[test]
+  fn it_estimates_the_remaining_time_of_a_task() {
+    let mut schedule = Schedule::new(1, counting_task(4), 1);
+    let waiting_info = schedule.get_info(TaskState::Waiting);
+    schedule.start = Some(Instant::now() - Duration::from_secs(10));
+    let started_info = schedule.get_info(TaskState::Running);
+    schedule.done_subtask();
+    let info = schedule.get_info(TaskState::Running);
+    let eta = info.eta.unwrap().as_secs();
+    assert!(waiting_info.elapsed.is_none() && waiting_info.eta.is_none() &&
+      started_info.elapsed.is_some() && started_info.eta.is_none() &&
+      (30..32).contains(&eta) && (info.output == "counting"));
+  }
 }
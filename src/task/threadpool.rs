@@ -0,0 +1,78 @@
+extern crate std;
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+
+/// Work run by a thread of the pool
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Fixed number of threads running jobs in the order they are spawned
+pub struct ThreadPool {
+
+  /// Dropped to stop the threads once their jobs are done
+  sender: Option<Sender<Job>>,
+
+  threads: Vec<thread::JoinHandle<()>>,
+}
+
+impl ThreadPool {
+
+  pub fn new(threads_number: usize) -> ThreadPool {
+    let (sender, receiver): (Sender<Job>, Receiver<Job>) = channel();
+    let receiver = Arc::new(Mutex::new(receiver));
+    let threads = (0..threads_number.max(1)).map(|_| {
+      let receiver = Arc::clone(&receiver);
+      thread::spawn(move || loop {
+
+        // The lock is released before the job runs, so other threads can
+        // receive the next jobs
+        let job = receiver.lock().unwrap().recv();
+        match job {
+          Ok(job) => job(),
+          Err(_) => break,
+        }
+      })
+    }).collect();
+    ThreadPool {
+      sender: Some(sender),
+      threads,
+    }
+  }
+
+  pub fn spawn<F>(&self, job: F)
+    where F: FnOnce() + Send + 'static,
+  {
+    if let Some(sender) = &self.sender {
+      sender.send(Box::new(job)).unwrap();
+    }
+  }
+
+  /// Waits for the spawned jobs and stops the threads
+  pub fn shutdown(mut self) {
+    self.sender = None;
+    for thread in self.threads.drain(..) {
+      thread.join().unwrap();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+  use std::sync::atomic::{AtomicUsize, Ordering};
+
+  #[test]
+  fn it_runs_every_spawned_job_before_shutting_down() {
+    let threadpool = ThreadPool::new(3);
+    let counter = Arc::new(AtomicUsize::new(0));
+    for _ in 0..20 {
+      let counter = Arc::clone(&counter);
+      threadpool.spawn(move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+      });
+    }
+    threadpool.shutdown();
+    assert!(counter.load(Ordering::SeqCst) == 20);
+  }
+}
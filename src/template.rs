@@ -0,0 +1,540 @@
+//! Grids templates imported from or exported to images and text masks
+
+extern crate chrono;
+use chrono::{Datelike, Timelike, Utc};
+
+extern crate image;
+use image::{AnimationDecoder, ImageFormat, Rgba, RgbaImage};
+use image::gif::GifDecoder;
+use image::png::PngDecoder;
+
+extern crate std;
+use std::convert::TryFrom;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use crate::error::Error;
+use crate::utils::FullPixel;
+
+/// Symbol of an empty cell in a text mask
+const EMPTY_SYMBOL: char = '.';
+
+/// Symbols of the <i>FullPixel</i> variants in a text mask
+const PIXEL_SYMBOLS: [(char, FullPixel); 4] = [
+  ('#', FullPixel::Body),
+  ('o', FullPixel::Border),
+  ('%', FullPixel::BodyBorder),
+  ('?', FullPixel::BodyEmpty),
+];
+
+/// Lines of a text mask setting the flags of its grid
+const HIDDEN_FLAG: &str = "hidden";
+const LOCKED_FLAG: &str = "locked";
+
+/// Symbols given to specific colors in a text mask, in order of appearance
+const COLOR_SYMBOLS: &str =
+  "ABCDEFGHIJKLMNPQRSTUVWXYZabcdefghijklmnpqrstuvwxyz0123456789";
+
+/// Formats of the exported grids
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MaskFormat {
+  Png,
+  Text,
+}
+
+impl MaskFormat {
+  fn extension(&self) -> &str {
+    match self {
+      MaskFormat::Png => "png",
+      MaskFormat::Text => "txt",
+    }
+  }
+}
+
+/// Colors of the <i>FullPixel</i> variants in an exported image. They match
+/// the grid's colors and the default legend, so exported images can be
+/// imported again.
+const PIXEL_COLORS: [(FullPixel, (u8, u8, u8)); 4] = [
+  (FullPixel::Body, (255, 255, 0)),
+  (FullPixel::Border, (255, 0, 0)),
+  (FullPixel::BodyBorder, (0, 0, 255)),
+  (FullPixel::BodyEmpty, (0, 255, 0)),
+];
+
+/// Returns the color of <i>pixel</i> in an exported image. A specific color
+/// equal to a variant's color has its blue channel moved by one step, so it
+/// is not imported again as this variant.
+fn pixel_color(pixel: FullPixel) -> (u8, u8, u8) {
+  match pixel {
+    FullPixel::SpecificColor(red, green, blue) => {
+      let taken = PIXEL_COLORS.iter()
+        .any(|&(_, color)| color == (red, green, blue));
+      if taken {
+        (red, green, if blue == 0 { 1 } else { blue - 1 })
+      } else {
+        (red, green, blue)
+      }
+    },
+    _ => PIXEL_COLORS.iter().find(|&&(variant, _)| variant == pixel)
+      .map_or((0, 0, 0), |&(_, color)| color),
+  }
+}
+
+fn parse_color(color: &str) -> Result<(u8, u8, u8), Error> {
+  let value = u32::from_str_radix(color, 16).ok()
+    .filter(|_| color.len() == 6)
+    .ok_or_else(|| Error::InvalidArgument(
+      format!("color \"{}\" must be a RRGGBB hexadecimal color.", color)))?;
+  Ok(((value >> 16) as u8, (value >> 8) as u8, value as u8))
+}
+
+/// Maps the colors of an imported image to <i>FullPixel</i> variants
+pub struct Legend {
+  colors: Vec<((u8, u8, u8), FullPixel)>,
+}
+
+impl Legend {
+
+  /// Parses a legend written like this: <i>ffff00=body,ff0000=border</i>.
+  /// Possible pixels are <i>body</i>, <i>border</i>, <i>bodyborder</i> and
+  /// <i>bodyempty</i>.
+  pub fn parse(legend: &str) -> Result<Legend, Error> {
+    let mut colors = Vec::new();
+    for entry in legend.split(',').filter(|entry| !entry.is_empty()) {
+      let (color, pixel) = match entry.find('=') {
+        Some(pos) => (&entry[..pos], &entry[pos + 1..]),
+        None => return Err(Error::InvalidArgument(
+          format!("legend entry \"{}\" must look like \"ffff00=body\".",
+            entry))),
+      };
+      let color = parse_color(color)?;
+      let pixel = match pixel.to_lowercase().as_str() {
+        "body" => FullPixel::Body,
+        "border" => FullPixel::Border,
+        "bodyborder" => FullPixel::BodyBorder,
+        "bodyempty" => FullPixel::BodyEmpty,
+        _ => return Err(Error::InvalidArgument(
+          format!("legend pixel \"{}\" must be body, border, bodyborder or \
+            bodyempty.", pixel))),
+      };
+      colors.push((color, pixel));
+    }
+    Ok(Legend { colors })
+  }
+
+  /// Returns the pixel matching <i>color</i>. Transparent colors are empty
+  /// cells and colors outside of the legend are specific colors.
+  pub fn get_pixel(&self, color: &Rgba<u8>) -> Option<FullPixel> {
+    let Rgba{ 0: rgba } = *color;
+    if rgba[3] == 0 {
+      None
+    } else {
+      let rgb = (rgba[0], rgba[1], rgba[2]);
+      Some(self.colors.iter().find(|(color, _)| *color == rgb)
+        .map_or(FullPixel::SpecificColor(rgb.0, rgb.1, rgb.2),
+          |&(_, pixel)| pixel))
+    }
+  }
+}
+
+/// A grid read from or written to an image or a text mask
+#[derive(Clone)]
+pub struct Template {
+  pub name: String,
+  pub width: u16,
+  pub height: u16,
+
+  /// Filled cells in tuples <i>(cell X coordinate, cell Y coordinate, cell
+  /// content)</i>
+  pub cells: Vec<(u16, u16, FullPixel)>,
+
+  /// Flags of the grid in the user interface, only kept by text masks
+  pub hidden: bool,
+  pub locked: bool,
+}
+
+impl Template {
+
+  fn new(name: String, image: &RgbaImage, legend: &Legend)
+    -> Result<Template, Error> {
+      let (width, height) = image.dimensions();
+      let (width, height) =
+        match (u16::try_from(width), u16::try_from(height)) {
+          (Ok(width), Ok(height)) => (width, height),
+          _ => return Err(Error::Overflow(
+            format!("{} is too large.", name))),
+        };
+      let cells = image.enumerate_pixels()
+        .filter_map(|(x, y, color)| legend.get_pixel(color)
+          .map(|pixel| (u16::try_from(x).unwrap(), u16::try_from(y).unwrap(),
+            pixel)))
+        .collect();
+      Ok(Template {
+        name,
+        width,
+        height,
+        cells,
+        hidden: false,
+        locked: false,
+      })
+  }
+
+  /// Reads a text mask: one row of symbols per grid row, then an empty line,
+  /// the <i>hidden</i> and <i>locked</i> lines of the set flags and one
+  /// <i>symbol=RRGGBB</i> line per specific color
+  pub fn from_text(name: String, text: &str) -> Result<Template, Error> {
+    let mut lines = text.lines().map(|line| line.trim_end());
+    let rows = lines.by_ref().take_while(|line| !line.is_empty())
+      .collect::<Vec<&str>>();
+    let mut colors = Vec::new();
+    let (mut hidden, mut locked) = (false, false);
+    for entry in lines.filter(|line| !line.is_empty()) {
+      if entry == HIDDEN_FLAG {
+        hidden = true;
+        continue;
+      } else if entry == LOCKED_FLAG {
+        locked = true;
+        continue;
+      }
+      let mut chars = entry.chars();
+      match (chars.next(), chars.next()) {
+        (Some(symbol), Some('=')) =>
+          colors.push((symbol, parse_color(chars.as_str())?)),
+        _ => return Err(Error::Encoding(
+          format!("{}: color entry \"{}\" must look like \"A=ff8800\".",
+            name, entry))),
+      }
+    }
+    let width = rows.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+    let (width, height) =
+      match (u16::try_from(width), u16::try_from(rows.len())) {
+        (Ok(width), Ok(height)) => (width, height),
+        _ => return Err(Error::Overflow(
+          format!("{} is too large.", name))),
+      };
+    let mut cells = Vec::new();
+    for (y, row) in rows.iter().enumerate() {
+      for (x, symbol) in row.chars().enumerate() {
+        if symbol == EMPTY_SYMBOL {
+          continue;
+        }
+        let pixel = PIXEL_SYMBOLS.iter().find(|(s, _)| *s == symbol)
+          .map(|&(_, pixel)| pixel)
+          .or_else(|| colors.iter().find(|(s, _)| *s == symbol)
+            .map(|&(_, (red, green, blue))|
+              FullPixel::SpecificColor(red, green, blue)))
+          .ok_or_else(|| Error::Encoding(
+            format!("{}: unknown symbol \"{}\".", name, symbol)))?;
+        cells.push((x as u16, y as u16, pixel));
+      }
+    }
+    Ok(Template {
+      name,
+      width,
+      height,
+      cells,
+      hidden,
+      locked,
+    })
+  }
+
+  /// Draws the template with the grid's colors. Empty cells are transparent.
+  /// The hidden and locked flags are not drawn, so they are lost.
+  pub fn to_image(&self) -> RgbaImage {
+    let mut image =
+      RgbaImage::new(u32::from(self.width), u32::from(self.height));
+    for &(x, y, pixel) in self.cells.iter() {
+      let (red, green, blue) = pixel_color(pixel);
+      image.put_pixel(u32::from(x), u32::from(y),
+        Rgba([red, green, blue, 255]));
+    }
+    image
+  }
+
+  /// Writes the template as a text mask. Returns an error when the template
+  /// has more specific colors than available symbols.
+  pub fn to_text(&self) -> Result<String, Error> {
+    let width = usize::from(self.width);
+    let mut rows = vec![vec![EMPTY_SYMBOL; width]; usize::from(self.height)];
+    let mut pixels = vec![None; width * usize::from(self.height)];
+    for &(x, y, pixel) in self.cells.iter() {
+      pixels[usize::from(y) * width + usize::from(x)] = Some(pixel);
+    }
+    let mut colors: Vec<(char, (u8, u8, u8))> = Vec::new();
+    let mut color_symbols = COLOR_SYMBOLS.chars();
+    for (index, pixel) in pixels.into_iter().enumerate() {
+      let symbol = match pixel {
+        None => continue,
+        Some(FullPixel::SpecificColor(red, green, blue)) => {
+          match colors.iter().find(|(_, color)| *color == (red, green, blue)) {
+            Some(&(symbol, _)) => symbol,
+            None => {
+              let symbol = color_symbols.next()
+                .ok_or_else(|| Error::Overflow(
+                  format!("{} has more than {} specific colors.", self.name,
+                    COLOR_SYMBOLS.len())))?;
+              colors.push((symbol, (red, green, blue)));
+              symbol
+            },
+          }
+        },
+        Some(pixel) => PIXEL_SYMBOLS.iter().find(|(_, p)| *p == pixel)
+          .unwrap().0,
+      };
+      rows[index / width][index % width] = symbol;
+    }
+    let mut text = rows.into_iter()
+      .map(|row| row.into_iter().collect::<String>() + "\n")
+      .collect::<String>();
+    if !colors.is_empty() || self.hidden || self.locked {
+      text.push('\n');
+      for (flag, name) in [(self.hidden, HIDDEN_FLAG),
+        (self.locked, LOCKED_FLAG)].iter() {
+          if *flag {
+            text.push_str(name);
+            text.push('\n');
+          }
+      }
+      for (symbol, (red, green, blue)) in colors {
+        text.push_str(
+          &format!("{}={:02x}{:02x}{:02x}\n", symbol, red, green, blue));
+      }
+    }
+    Ok(text)
+  }
+}
+
+fn image_error(path: &Path, error: image::ImageError) -> Error {
+  Error::from(error)
+    .context(&format!("{} can not be imported", path.display()))
+}
+
+/// Reads the frames of an image. Animated GIF and PNG files have one frame per
+/// layer.
+fn read_frames(path: &Path) -> Result<Vec<RgbaImage>, Error> {
+  let format = ImageFormat::from_path(path)
+    .map_err(|error| image_error(path, error))?;
+  let reader = BufReader::new(File::open(path)?);
+  match format {
+    ImageFormat::Gif => {
+      let decoder = GifDecoder::new(reader)
+        .map_err(|error| image_error(path, error))?;
+      let frames = decoder.into_frames().collect_frames()
+        .map_err(|error| image_error(path, error))?;
+      Ok(frames.into_iter().map(|frame| frame.into_buffer()).collect())
+    },
+    ImageFormat::Png => {
+      let decoder = PngDecoder::new(reader)
+        .map_err(|error| image_error(path, error))?;
+      if decoder.is_apng() {
+        let frames = decoder.apng().into_frames().collect_frames()
+          .map_err(|error| image_error(path, error))?;
+        Ok(frames.into_iter().map(|frame| frame.into_buffer()).collect())
+      } else {
+        let image = image::DynamicImage::from_decoder(decoder)
+          .map_err(|error| image_error(path, error))?;
+        Ok(vec![image.to_rgba()])
+      }
+    },
+    _ => {
+      let image = image::open(path).map_err(|error| image_error(path, error))?;
+      Ok(vec![image.to_rgba()])
+    },
+  }
+}
+
+/// Reads each image of <i>paths</i> and returns one template per layer.
+/// Files with a <i>txt</i> extension are read as text masks.
+pub fn load(paths: &[PathBuf], legend: &Legend)
+  -> Result<Vec<Template>, Error> {
+    let mut templates = Vec::new();
+    for path in paths {
+      let stem = path.file_stem().map_or(String::from("Template"),
+        |stem| stem.to_string_lossy().into_owned());
+      let extension = MaskFormat::Text.extension();
+      if path.extension().map(|e| e.eq_ignore_ascii_case(extension)) ==
+        Some(true) {
+          templates.push(
+            Template::from_text(stem, &fs::read_to_string(path)?)?);
+          continue;
+      }
+      let frames = read_frames(path)?;
+      let nb_frames = frames.len();
+      for (index, frame) in frames.iter().enumerate() {
+        let name = if nb_frames > 1 {
+          format!("{}-{}", stem, index + 1)
+        } else {
+          stem.clone()
+        };
+        templates.push(Template::new(name, frame, legend)?);
+      }
+    }
+    Ok(templates)
+}
+
+/// Returns a file name made of <i>name</i>'s alphanumeric characters,
+/// different from <i>taken</i> names
+fn file_name(name: &str, taken: &[String]) -> String {
+  let stem = name.chars()
+    .map(|c| if c.is_alphanumeric() || (c == '-') { c } else { '_' })
+    .collect::<String>();
+  let mut file_name = stem.clone();
+  let mut index = 1;
+  while taken.contains(&file_name) {
+    index += 1;
+    file_name = format!("{}-{}", stem, index);
+  }
+  file_name
+}
+
+/// Writes one file per template in a new directory and returns the name of
+/// the directory
+pub fn export(templates: &[Template], format: MaskFormat)
+  -> Result<String, Error> {
+    let now = Utc::now();
+    let dir = format!("liriodendron_export_{}-{}-{}_{}:{}:{}.{}",
+      now.day(), now.month(), now.year(), now.hour(), now.minute(),
+      now.second(), now.nanosecond());
+    fs::create_dir(&dir)?;
+    let mut file_names = Vec::with_capacity(templates.len());
+    for template in templates {
+      file_names.push(file_name(&template.name, &file_names));
+      let path = Path::new(&dir)
+        .join(format!("{}.{}", file_names.last().unwrap(), format.extension()));
+      match format {
+        MaskFormat::Png => template.to_image()
+          .save_with_format(&path, ImageFormat::Png)
+          .map_err(|error| Error::from(error)
+            .context(&format!("{} can not be exported", path.display())))?,
+        MaskFormat::Text => fs::write(&path, template.to_text()?)?,
+      }
+    }
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+
+  #[test]
+  fn it_parses_a_legend() {
+    let legend = Legend::parse("ffff00=body,FF0000=border").unwrap();
+    assert!((legend.get_pixel(&Rgba([255, 255, 0, 255])) ==
+      Some(FullPixel::Body)) &&
+      (legend.get_pixel(&Rgba([255, 0, 0, 255])) == Some(FullPixel::Border)) &&
+      (legend.get_pixel(&Rgba([1, 2, 3, 255])) ==
+        Some(FullPixel::SpecificColor(1, 2, 3))) &&
+      (legend.get_pixel(&Rgba([255, 255, 0, 0])).is_none()));
+  }
+
+  #[test]
+  fn it_does_not_parse_a_legend_with_an_unknown_pixel() {
+    assert!(Legend::parse("ffff00=body,ff0000=cell").is_err() &&
+      Legend::parse("fff=body").is_err() && Legend::parse("body").is_err());
+  }
+
+  #[test]
+  fn it_makes_a_template_from_an_image() {
+    let legend = Legend::parse("ffff00=body").unwrap();
+    let mut image = RgbaImage::new(3, 2);
+    image.put_pixel(1, 1, Rgba([255, 255, 0, 255]));
+    image.put_pixel(2, 0, Rgba([0, 0, 9, 255]));
+    let template =
+      Template::new(String::from("mask"), &image, &legend).unwrap();
+    assert!((template.width == 3) && (template.height == 2) &&
+      (template.cells.len() == 2) &&
+      template.cells.contains(&(1, 1, FullPixel::Body)) &&
+      template.cells.contains(&(2, 0, FullPixel::SpecificColor(0, 0, 9))));
+  }
+
+  #[test]
+  fn it_loads_one_template_per_image() {
+    let legend = Legend::parse("ff0000=border").unwrap();
+    let path = std::env::temp_dir().join("liriodendron_template_test.png");
+    let mut image = RgbaImage::new(4, 5);
+    image.put_pixel(3, 4, Rgba([255, 0, 0, 255]));
+    image.save(&path).unwrap();
+    let templates = load(std::slice::from_ref(&path), &legend).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!((templates.len() == 1) &&
+      (templates[0].name == "liriodendron_template_test") &&
+      (templates[0].width == 4) && (templates[0].height == 5) &&
+      (templates[0].cells == vec![(3, 4, FullPixel::Border)]));
+  }
+
+  #[test]
+  fn it_writes_and_reads_a_text_mask() {
+    let template = Template {
+      name: String::from("mask"),
+      width: 3,
+      height: 2,
+      cells: vec![(0, 0, FullPixel::Body), (1, 0, FullPixel::Border),
+        (2, 0, FullPixel::SpecificColor(255, 136, 0)),
+        (1, 1, FullPixel::BodyEmpty), (2, 1, FullPixel::BodyBorder)],
+      hidden: false,
+      locked: true,
+    };
+    let text = template.to_text().unwrap();
+    let read = Template::from_text(String::from("mask"), &text).unwrap();
+    assert!((text == "#oA\n.?%\n\nlocked\nA=ff8800\n") &&
+      (read.width == 3) && (read.height == 2) && (read.cells.len() == 5) &&
+      !read.hidden && read.locked &&
+      template.cells.iter().all(|cell| read.cells.contains(cell)));
+  }
+
+  #[test]
+  fn it_does_not_read_a_text_mask_with_an_unknown_symbol() {
+    assert!(Template::from_text(String::from("mask"), "#B\n").is_err() &&
+      Template::from_text(String::from("mask"), "#\n\nB:ff0000\n").is_err());
+  }
+
+  #[test]
+  fn it_draws_a_template_with_the_default_legend_colors() {
+    let legend = Legend::parse("ffff00=body,ff0000=border,0000ff=bodyborder,\
+      00ff00=bodyempty").unwrap();
+    let template = Template {
+      name: String::from("mask"),
+      width: 2,
+      height: 2,
+      cells: vec![(0, 0, FullPixel::BodyBorder), (1, 1, FullPixel::Body),
+        (1, 0, FullPixel::SpecificColor(1, 2, 3))],
+      hidden: false,
+      locked: false,
+    };
+    let read =
+      Template::new(String::from("mask"), &template.to_image(), &legend)
+        .unwrap();
+    assert!((read.cells.len() == 3) &&
+      template.cells.iter().all(|cell| read.cells.contains(cell)));
+  }
+
+  #[test]
+  fn it_does_not_import_a_specific_color_as_a_legend_pixel() {
+    let legend = Legend::parse("ffff00=body,ff0000=border,0000ff=bodyborder,\
+      00ff00=bodyempty").unwrap();
+    let template = Template {
+      name: String::from("mask"),
+      width: 2,
+      height: 1,
+      cells: vec![(0, 0, FullPixel::SpecificColor(255, 255, 0)),
+        (1, 0, FullPixel::SpecificColor(0, 0, 255))],
+      hidden: false,
+      locked: false,
+    };
+    let read =
+      Template::new(String::from("mask"), &template.to_image(), &legend)
+        .unwrap();
+    assert!((read.cells.len() == 2) &&
+      read.cells.contains(&(0, 0, FullPixel::SpecificColor(255, 255, 1))) &&
+      read.cells.contains(&(1, 0, FullPixel::SpecificColor(0, 0, 254))));
+  }
+
+  #[test]
+  fn it_makes_different_file_names() {
+    let taken = vec![String::from("a_b")];
+    assert!((file_name("a/b", &taken) == "a_b-2") &&
+      (file_name("Grid-1", &taken) == "Grid-1"));
+  }
+}
@@ -0,0 +1,1210 @@
+//! Textures rendered in memory from grids' masks
+
+extern crate image;
+use image::{Rgba, RgbaImage};
+
+extern crate rand;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+extern crate std;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+/// Module for colors generation
+pub mod palette;
+
+use crate::error::Error;
+use crate::template::Template;
+use crate::utils::FullPixel;
+
+const TRANSPARENT_PIXEL: Rgba<u8> = Rgba([0; 4]);
+
+const DEFAULT_PIXEL_RATIO: u32 = 4;
+const DEFAULT_BORDER_RATIO: u8 = 3;
+const DEFAULT_BODY_RATIO: f64 = 0.5;
+
+/// Grids to render. The first grid has the highest priority: a cell filled
+/// on several grids is rendered with the pixel of the first one.
+#[derive(Clone)]
+pub struct Mask {
+  width: u32,
+  height: u32,
+
+  /// Filled cells of each grid
+  grids: Vec<HashMap<(u32, u32), FullPixel>>,
+}
+
+impl Mask {
+
+  /// Makes a mask with one empty grid
+  pub fn new(width: u32, height: u32) -> Mask {
+    Mask {
+      width,
+      height,
+      grids: vec![HashMap::new()],
+    }
+  }
+
+  /// Makes a mask with one grid per template. The mask takes the size of the
+  /// largest template.
+  pub fn from_templates(templates: &[Template]) -> Mask {
+    let mut mask = Mask {
+      width: templates.iter().map(|t| u32::from(t.width)).max().unwrap_or(0),
+      height: templates.iter().map(|t| u32::from(t.height)).max().unwrap_or(0),
+      grids: Vec::with_capacity(templates.len()),
+    };
+    for template in templates {
+      mask.grids.push(template.cells.iter()
+        .map(|&(x, y, pixel)| ((u32::from(x), u32::from(y)), pixel))
+        .collect());
+    }
+    mask
+  }
+
+  pub fn get_width(&self) -> u32 {
+    self.width
+  }
+
+  pub fn get_height(&self) -> u32 {
+    self.height
+  }
+
+  pub fn get_nb_grids(&self) -> usize {
+    self.grids.len()
+  }
+
+  /// Adds an empty grid after the others and returns its ID
+  pub fn add_grid(&mut self) -> usize {
+    self.grids.push(HashMap::new());
+    self.grids.len() - 1
+  }
+
+  pub fn get_cell(&self, grid_id: usize, (x, y): (u32, u32))
+    -> Option<FullPixel> {
+      self.grids.get(grid_id).and_then(|grid| grid.get(&(x, y)).copied())
+  }
+
+  /// Fills the cell <i>(x, y)</i> of the grid <i>grid_id</i>. Returns an
+  /// error if the grid or the cell is outside of the mask.
+  pub fn set_cell(&mut self, grid_id: usize, (x, y): (u32, u32),
+    pixel: FullPixel) -> Result<(), Error> {
+      self.check_cell(grid_id, (x, y))?;
+      self.grids[grid_id].insert((x, y), pixel);
+      Ok(())
+  }
+
+  /// Empties the cell <i>(x, y)</i> of the grid <i>grid_id</i>. Returns an
+  /// error if the grid or the cell is outside of the mask.
+  pub fn erase_cell(&mut self, grid_id: usize, (x, y): (u32, u32))
+    -> Result<(), Error> {
+      self.check_cell(grid_id, (x, y))?;
+      self.grids[grid_id].remove(&(x, y));
+      Ok(())
+  }
+
+  fn check_cell(&self, grid_id: usize, (x, y): (u32, u32))
+    -> Result<(), Error> {
+      if grid_id >= self.grids.len() {
+        Err(Error::InvalidArgument(
+          format!("grid {} does not exist.", grid_id)))
+      } else if (x >= self.width) || (y >= self.height) {
+        Err(Error::InvalidArgument(
+          format!("cell ({}, {}) is outside of the {}x{} mask.", x, y,
+            self.width, self.height)))
+      } else {
+        Ok(())
+      }
+  }
+
+  /// Returns filled cells in a HashMap of key-tuples <i>(cell X coordinate,
+  /// cell Y coordinate)</i> and value-tuples <i>(grid ID, cell content)</i>
+  fn get_cells(&self) -> HashMap<(u32, u32), (usize, FullPixel)> {
+    let mut cells = HashMap::new();
+    for (id, grid) in self.grids.iter().enumerate().rev() {
+      cells.extend(grid.iter().map(|(&key, &pixel)| (key, (id, pixel))));
+    }
+    cells
+  }
+}
+
+/// Parameters of rendered textures
+#[derive(Clone, Copy)]
+pub struct Settings {
+
+  /// Ratio between grid's cell and texture's pixels
+  pub pixel_ratio: u32,
+
+  /// Ratio between <i>FullPixel::Border</i> color and <i>FullPixel::Body</i>
+  /// color
+  pub border_ratio: u8,
+
+  pub palette: palette::PaletteGeneration,
+
+  /// Probability for a <i>FullPixel::BodyEmpty</i> cell to be empty
+  pub body_empty_ratio: f64,
+
+  /// Probability for a <i>FullPixel::BodyBorder</i> cell to be a border
+  pub body_border_ratio: f64,
+}
+
+impl Settings {
+
+  pub fn new() -> Settings {
+    Settings {
+      pixel_ratio: DEFAULT_PIXEL_RATIO,
+      border_ratio: DEFAULT_BORDER_RATIO,
+      palette: palette::PaletteGeneration::RandomPredefinedColors,
+      body_empty_ratio: DEFAULT_BODY_RATIO,
+      body_border_ratio: DEFAULT_BODY_RATIO,
+    }
+  }
+}
+
+impl Default for Settings {
+  fn default() -> Settings {
+    Settings::new()
+  }
+}
+
+/// Renders textures of a mask
+pub struct Texture {
+  grids: HashMap<(u32, u32), (usize, FullPixel)>,
+  grids_rows: u32,
+  grids_cols: u32,
+  nb_grids: usize,
+  pixel_ratio: u32,
+  border_ratio: u8,
+  palette_generation: palette::PaletteGeneration,
+  body_de_ratio: f64,
+  body_dr_ratio: f64,
+}
+
+impl Texture {
+
+  /// Returns an error if <i>settings</i> ratios are null
+  pub fn new(mask: &Mask, settings: &Settings) -> Result<Texture, Error> {
+    if (settings.pixel_ratio == 0) || (settings.border_ratio == 0) {
+      return Err(Error::InvalidArgument(
+        String::from("pixel ratio and border ratio must be greater than 0.")))
+    }
+    Ok(Texture {
+      grids: mask.get_cells(),
+      grids_rows: mask.width,
+      grids_cols: mask.height,
+      nb_grids: mask.grids.len(),
+      pixel_ratio: settings.pixel_ratio,
+      border_ratio: settings.border_ratio,
+      palette_generation: settings.palette,
+      body_de_ratio: settings.body_empty_ratio,
+      body_dr_ratio: settings.body_border_ratio,
+    })
+  }
+
+  /// Renders one texture. The same <i>seed</i> always renders the same
+  /// texture.
+  pub fn render(&self, seed: u64) -> RgbaImage {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut image = image::ImageBuffer::from_pixel(
+      self.get_img_width(), self.get_img_height(), TRANSPARENT_PIXEL);
+    let mut palette = Vec::<palette::Palette>::with_capacity(self.nb_grids);
+    let mut rd = Vec::<f64>::with_capacity(
+      usize::try_from(self.grids_rows * self.grids_cols).unwrap());
+    for _ in 0..(self.grids_rows * self.grids_cols) {
+      rd.push(rng.gen_range(0., 1.));
+    }
+    for _ in 0..self.nb_grids {
+      palette.push(self.palette_generation.run(self.border_ratio, &mut rng));
+    }
+    for row in 0..self.grids_rows {
+      for col in 0..self.grids_cols {
+        self.fix_pixel(&mut image, &palette, (row, col), &rd);
+      }
+    }
+    image
+  }
+
+  fn get_img_width(&self) -> u32 {
+    self.grids_rows * self.pixel_ratio
+  }
+
+  fn get_img_height(&self) -> u32 {
+    self.grids_cols * self.pixel_ratio
+  }
+
+  fn fix_pixel(&self, image: &mut RgbaImage, palette: &Vec<palette::Palette>,
+    (row, col): (u32, u32), rd: &Vec<f64>) {
+      match self.grids.get(&(row, col)) {
+        None => {
+          self.fix_empty_pixel(image, palette, (row, col), rd);
+        },
+        Some((id, pixel)) => {
+          match pixel {
+            FullPixel::BodyEmpty => {
+              if rd[usize::try_from(row * self.grids_cols + col).unwrap()]
+                > self.body_de_ratio {
+                  self.fix_full_pixel(image, palette.get(*id).unwrap(),
+                    (row, col), FullPixel::Body);
+              } else {
+                self.fix_empty_pixel(image, palette, (row, col), rd);
+              }
+            },
+            FullPixel::BodyBorder => {
+              if rd[usize::try_from(row * self.grids_cols + col).unwrap()]
+                > self.body_dr_ratio {
+                  self.fix_full_pixel(image, palette.get(*id).unwrap(),
+                    (row, col), FullPixel::Body);
+              } else {
+                self.fix_full_pixel(image, palette.get(*id).unwrap(),
+                  (row, col), FullPixel::Border);
+              }
+            },
+            _ => self.fix_full_pixel(image, palette.get(*id).unwrap(),
+                  (row, col), *pixel),
+          };
+        },
+      };
+  }
+
+  fn fix_empty_pixel(&self, image: &mut RgbaImage,
+    palette: &Vec<palette::Palette>, (row, col): (u32, u32), rd: &Vec<f64>) {
+      let mut high_priority_id: Option<usize> = None;
+      if row > 0 {
+        if let Some((neighbour_id, pixel)) = self.grids.get(&(row - 1, col)) {
+          if self.is_body_pixel((row - 1, col), pixel, rd) {
+            self.check_higher_priority_id(&mut high_priority_id, neighbour_id);
+          }
+        }
+      }
+      if row + 1 < self.grids_rows {
+        if let Some((neighbour_id, pixel)) = self.grids.get(&(row + 1, col)) {
+          if self.is_body_pixel((row + 1, col), pixel, rd) {
+            self.check_higher_priority_id(&mut high_priority_id, neighbour_id);
+          }
+        }
+      }
+      if col > 0 {
+        if let Some((neighbour_id, pixel)) = self.grids.get(&(row, col - 1)) {
+          if self.is_body_pixel((row, col - 1), pixel, rd) {
+            self.check_higher_priority_id(&mut high_priority_id, neighbour_id);
+          }
+        }
+      }
+      if col + 1 < self.grids_cols {
+        if let Some((neighbour_id, pixel)) = self.grids.get(&(row, col + 1)) {
+          if self.is_body_pixel((row, col + 1), pixel, rd) {
+            self.check_higher_priority_id(&mut high_priority_id, neighbour_id);
+          }
+        }
+      }
+
+      if let Some(id) = high_priority_id {
+        self.fix_full_pixel(
+          image, palette.get(id).unwrap(), (row, col), FullPixel::Border);
+      }
+  }
+
+  fn is_body_pixel(&self, (row, col): (u32, u32), pixel: &FullPixel,
+    rd: &Vec<f64>) -> bool{
+      match pixel {
+        FullPixel::Body => return true,
+        FullPixel::BodyEmpty
+          if rd[usize::try_from(row * self.grids_cols + col).unwrap()] >
+            self.body_de_ratio =>
+              return true,
+        FullPixel::BodyBorder
+          if rd[usize::try_from(row * self.grids_cols + col).unwrap()] >
+            self.body_dr_ratio =>
+            return true,
+        _ => return false,
+      }
+  }
+
+  fn check_higher_priority_id(&self, id: &mut Option<usize>,
+    neighbour_id: &usize) {
+      match id {
+        None => *id = Some(*neighbour_id),
+        Some(lower_priority_id) if *lower_priority_id > *neighbour_id =>
+          *id = Some(*neighbour_id),
+        Some(_) => {},
+      };
+  }
+
+  fn fix_full_pixel(&self, image: &mut RgbaImage, palette: &palette::Palette,
+    (row, col): (u32, u32), pixel: FullPixel) {
+      match pixel {
+        FullPixel::Body => {
+          self.colorize_image(image, (row, col), palette.get_body_color());
+        },
+        FullPixel::Border => self.colorize_image(image, (row, col),
+          palette.get_border_color()),
+        FullPixel::SpecificColor(r, g, b) => self.colorize_image(image,
+            (row, col), Rgba([r, g, b, 255])),
+        _ => {},
+      }
+  }
+
+  fn colorize_image(&self, image: &mut RgbaImage, (x, y): (u32, u32),
+    color: Rgba<u8>) {
+      for offset_x in 0..self.pixel_ratio {
+        for offset_y in 0..self.pixel_ratio {
+          let pixel =
+            image.get_pixel_mut(x * self.pixel_ratio + offset_x,
+              y * self.pixel_ratio + offset_y);
+          *pixel = color;
+        }
+      }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+  use rand::thread_rng;
+
+  #[test]
+  fn it_colorizes_the_image_correctly() {
+    let texture = Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let (a, b) = (3, 4);
+    texture.colorize_image(&mut image, (a, b), Rgba([255; 4]));
+    let mut image_is_correctly_colorized = true;
+    for (x, y, pixel) in image.enumerate_pixels() {
+      if (x >= a * texture.pixel_ratio) &&
+        (x < (a + 1) * texture.pixel_ratio) &&
+        (y >= b * texture.pixel_ratio) &&
+        (y < (b + 1) * texture.pixel_ratio) {
+          image_is_correctly_colorized = image_is_correctly_colorized &&
+            (*pixel == Rgba([255; 4]));
+      } else {
+        image_is_correctly_colorized = image_is_correctly_colorized &&
+          (*pixel == TRANSPARENT_PIXEL);
+      }
+    }
+    assert!(image_is_correctly_colorized);
+  }
+
+  #[test]
+  fn it_checks_grid_id_of_the_cell() {
+    let texture = Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut id = None;
+    let mut neighbour_id = 2;
+    texture.check_higher_priority_id(&mut id, &neighbour_id);
+    let mut first_check = false;
+    if let Some(id) = id {
+      if id == neighbour_id {
+        first_check = true;
+      }
+    }
+    neighbour_id = 1;
+    texture.check_higher_priority_id(&mut id, &neighbour_id);
+    let mut second_check = false;
+    if let Some(id) = id {
+      if id == neighbour_id {
+        second_check = true;
+      }
+    }
+    neighbour_id = 2;
+    texture.check_higher_priority_id(&mut id, &neighbour_id);
+    let mut third_check = false;
+    if let Some(id) = id {
+      if id == 1 {
+        third_check = true;
+      }
+    }
+    assert!(first_check && second_check && third_check);
+  }
+
+  #[test]
+  fn it_returns_if_it_is_a_body_pixel() {
+    let texture = Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let rd = vec![0.6, 0.2, 0.7, 0.4, 0.0];
+    assert!(texture.is_body_pixel((0, 0), &FullPixel::BodyEmpty, &rd) &&
+      !texture.is_body_pixel((0, 1), &FullPixel::BodyEmpty, &rd) &&
+      texture.is_body_pixel((0, 2), &FullPixel::BodyBorder, &rd) &&
+      !texture.is_body_pixel((0, 3), &FullPixel::BodyBorder, &rd) &&
+      texture.is_body_pixel((0, 4), &FullPixel::Body, &rd));
+  }
+
+  #[test]
+  fn it_fixes_body_pixel() {
+    let texture = Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let palette = texture.palette_generation.run(3, &mut thread_rng());
+    let (a, b) = (2, 8);
+    texture.fix_full_pixel(&mut image, &palette, (a, b), FullPixel::Body);
+    let mut fix_body_pixel = true;
+    for (x, y, pixel) in image.enumerate_pixels() {
+      if (x >= a * texture.pixel_ratio) &&
+        (x < (a + 1) * texture.pixel_ratio) &&
+        (y >= b * texture.pixel_ratio) &&
+        (y < (b + 1) * texture.pixel_ratio) {
+          fix_body_pixel =
+            fix_body_pixel && (*pixel == palette.get_body_color());
+      } else {
+        fix_body_pixel = fix_body_pixel && (*pixel == TRANSPARENT_PIXEL);
+      }
+    }
+    assert!(fix_body_pixel);
+  }
+
+  #[test]
+  fn it_fixes_border_pixel() {
+    let texture = Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let palette = texture.palette_generation.run(3, &mut thread_rng());
+    let (a, b) = (7, 0);
+    texture.fix_full_pixel(&mut image, &palette, (a, b), FullPixel::Border);
+    let mut fix_border_pixel = true;
+    for (x, y, pixel) in image.enumerate_pixels() {
+      if (x >= a * texture.pixel_ratio) &&
+        (x < (a + 1) * texture.pixel_ratio) &&
+        (y >= b * texture.pixel_ratio) &&
+        (y < (b + 1) * texture.pixel_ratio) {
+          fix_border_pixel =
+            fix_border_pixel && (*pixel == palette.get_border_color());
+      } else {
+        fix_border_pixel = fix_border_pixel && (*pixel == TRANSPARENT_PIXEL);
+      }
+    }
+    assert!(fix_border_pixel);
+  }
+
+  #[test]
+  fn it_fixes_specific_color_pixel() {
+    let texture = Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let palette = texture.palette_generation.run(3, &mut thread_rng());
+    let (a, b) = (7, 0);
+    let (red, green, blue) = (100, 150, 30);
+    let specific_pixel = FullPixel::SpecificColor(red, green, blue);
+    let color_expected = Rgba([red, green, blue, 255]);
+    texture.fix_full_pixel(&mut image, &palette, (a, b), specific_pixel);
+    let mut fix_specific_color_pixel = true;
+    for (x, y, pixel) in image.enumerate_pixels() {
+      if (x >= a * texture.pixel_ratio) &&
+        (x < (a + 1) * texture.pixel_ratio) &&
+        (y >= b * texture.pixel_ratio) &&
+        (y < (b + 1) * texture.pixel_ratio) {
+          fix_specific_color_pixel =
+            fix_specific_color_pixel && (*pixel == color_expected);
+      } else {
+        fix_specific_color_pixel =
+          fix_specific_color_pixel && (*pixel == TRANSPARENT_PIXEL);
+      }
+    }
+    assert!(fix_specific_color_pixel);
+  }
+
+  #[test]
+  fn it_does_not_fix_empty_pixel_with_empty_pixel_neighbours() {
+    let texture = Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let mut rd = Vec::<f64>::with_capacity(
+      usize::try_from(texture.grids_rows * texture.grids_cols).unwrap());
+    let mut rng = thread_rng();
+    for _ in 0..(texture.grids_rows * texture.grids_cols) {
+      rd.push(rng.gen_range(0., 1.));
+    }
+    let palette = vec![texture.palette_generation.run(3, &mut thread_rng())];
+    let (a, b) = (5, 1);
+    texture.fix_empty_pixel(&mut image, &palette, (a, b), &rd);
+    let mut fix_empty_pixel = true;
+    for (_, _, pixel) in image.enumerate_pixels() {
+      fix_empty_pixel = fix_empty_pixel && (*pixel == TRANSPARENT_PIXEL);
+    }
+    assert!(fix_empty_pixel);
+  }
+
+  #[test]
+  fn it_does_not_fix_empty_pixel_with_1_border_pixel_neighbour() {
+    let texture = Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let mut rd = Vec::<f64>::with_capacity(
+      usize::try_from(texture.grids_rows * texture.grids_cols).unwrap());
+    let mut rng = thread_rng();
+    for _ in 0..(texture.grids_rows * texture.grids_cols) {
+      rd.push(rng.gen_range(0., 1.));
+    }
+    let palette = vec![texture.palette_generation.run(3, &mut thread_rng())];
+    let (a, b) = (3, 6);
+    texture.fix_full_pixel(
+      &mut image, palette.get(0).unwrap(), (a, b + 1), FullPixel::Border);
+    texture.fix_empty_pixel(&mut image, &palette, (a, b), &rd);
+    let mut fix_empty_pixel = true;
+    for (x, y, pixel) in image.enumerate_pixels() {
+      if (x >= a * texture.pixel_ratio) &&
+        (x < (a + 1) * texture.pixel_ratio) &&
+        (y >= (b + 1) * texture.pixel_ratio) &&
+        (y < (b + 2) * texture.pixel_ratio) {
+          fix_empty_pixel = fix_empty_pixel &&
+            (*pixel == palette.get(0).unwrap().get_border_color());
+      } else {
+        fix_empty_pixel = fix_empty_pixel && (*pixel == TRANSPARENT_PIXEL);
+      }
+    }
+    assert!(fix_empty_pixel);
+  }
+
+  #[test]
+  fn it_fixes_empty_pixels_into_border_pixels_with_higher_priority_grid_with_several_grids() {
+    let mut texture =
+      Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let rd = Vec::<f64>::with_capacity(
+      usize::try_from(texture.grids_rows * texture.grids_cols).unwrap());
+    let palette = vec![texture.palette_generation.run(3, &mut thread_rng()),
+      texture.palette_generation.run(3, &mut thread_rng()),
+      texture.palette_generation.run(3, &mut thread_rng())];
+
+    texture.grids.insert((0, 0), (0, FullPixel::Body));
+    texture.grids.insert((2, 0), (1, FullPixel::Body));
+    texture.grids.insert((1, 1), (1, FullPixel::Body));
+    texture.grids.insert((0, 2), (1, FullPixel::Body));
+    texture.grids.insert((2, 2), (2, FullPixel::Body));
+    texture.fix_empty_pixel(&mut image, &palette, (1, 2), &rd);
+    texture.fix_empty_pixel(&mut image, &palette, (1, 2), &rd);
+    texture.fix_empty_pixel(&mut image, &palette, (1, 2), &rd);
+
+    texture.fix_empty_pixel(&mut image, &palette, (2, 1), &rd);
+    texture.fix_empty_pixel(&mut image, &palette, (2, 1), &rd);
+    texture.fix_empty_pixel(&mut image, &palette, (2, 1), &rd);
+
+    texture.fix_empty_pixel(&mut image, &palette, (1, 0), &rd);
+    texture.fix_empty_pixel(&mut image, &palette, (1, 0), &rd);
+    texture.fix_empty_pixel(&mut image, &palette, (1, 0), &rd);
+
+    texture.fix_empty_pixel(&mut image, &palette, (0, 1), &rd);
+    texture.fix_empty_pixel(&mut image, &palette, (0, 1), &rd);
+    texture.fix_empty_pixel(&mut image, &palette, (0, 1), &rd);
+
+    let mut fix_empty_pixels = true;
+    for (x, y, pixel) in image.enumerate_pixels() {
+      if (x < texture.pixel_ratio) && (y >= texture.pixel_ratio) &&
+        (y < 2 * texture.pixel_ratio) {
+          fix_empty_pixels = fix_empty_pixels &&
+            (*pixel == palette.get(0).unwrap().get_border_color());
+      } else if (x >= texture.pixel_ratio) &&
+        (x < 2 * texture.pixel_ratio) && (y < texture.pixel_ratio) {
+          fix_empty_pixels = fix_empty_pixels &&
+            (*pixel == palette.get(0).unwrap().get_border_color());
+      } else if (x >= 2 * texture.pixel_ratio) &&
+        (x < 3 * texture.pixel_ratio) &&
+        (y >= texture.pixel_ratio) && (y < 2 * texture.pixel_ratio) {
+          fix_empty_pixels = fix_empty_pixels &&
+            (*pixel == palette.get(1).unwrap().get_border_color());
+      } else if (x >= texture.pixel_ratio) &&
+        (x < 2 * texture.pixel_ratio) && (y >= 3 * texture.pixel_ratio) &&
+        (y < 2 * texture.pixel_ratio) {
+          fix_empty_pixels = fix_empty_pixels &&
+            (*pixel == palette.get(1).unwrap().get_border_color());
+      }
+    }
+    assert!(fix_empty_pixels);
+  }
+
+  #[test]
+  fn it_fixes_empty_pixel_with_1_bodyborder_pixel_neighbour() {
+    let mut texture =
+      Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let mut rd = Vec::<f64>::with_capacity(
+      usize::try_from(texture.grids_rows * texture.grids_cols).unwrap());
+    let (a, b) = (3, 6);
+    let mut rng = thread_rng();
+    let is_body = rng.gen_range(0.6, 1.);
+    for i in 0..(texture.grids_rows * texture.grids_cols) {
+      if i == texture.grids_cols * a + b + 1 {
+        rd.push(is_body);
+      } else {
+        rd.push(rng.gen_range(0., 1.));
+      }
+    }
+    texture.grids.insert((a, b + 1), (0, FullPixel::BodyBorder));
+    let palette = vec![texture.palette_generation.run(3, &mut thread_rng())];
+    texture.fix_empty_pixel(&mut image, &palette, (a, b), &rd);
+    let mut fix_empty_pixel = true;
+    for (x, y, pixel) in image.enumerate_pixels() {
+      if (x >= a * texture.pixel_ratio) &&
+        (x < (a + 1) * texture.pixel_ratio) &&
+        (y >= b * texture.pixel_ratio) &&
+        (y < (b + 1) * texture.pixel_ratio) {
+          fix_empty_pixel = fix_empty_pixel &&
+            (*pixel == palette.get(0).unwrap().get_border_color());
+      } else {
+        fix_empty_pixel = fix_empty_pixel && (*pixel == TRANSPARENT_PIXEL);
+      }
+    }
+    assert!(fix_empty_pixel);
+  }
+
+  #[test]
+  fn it_does_not_fix_empty_pixel_with_1_bodyborder_pixel_neighbour() {
+    let mut texture =
+      Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let mut rd = Vec::<f64>::with_capacity(
+      usize::try_from(texture.grids_rows * texture.grids_cols).unwrap());
+    let (a, b) = (3, 6);
+    let mut rng = thread_rng();
+    let is_border = rng.gen_range(0., 0.4);
+    for i in 0..(texture.grids_rows * texture.grids_cols) {
+      if i == texture.grids_cols * a + b + 1 {
+        rd.push(is_border);
+      } else {
+        rd.push(rng.gen_range(0., 1.));
+      }
+    }
+    texture.grids.insert((a, b + 1), (0, FullPixel::BodyBorder));
+    let palette = vec![texture.palette_generation.run(3, &mut thread_rng())];
+    texture.fix_empty_pixel(&mut image, &palette, (a, b), &rd);
+    let mut fix_empty_pixel = true;
+    for (_, _, pixel) in image.enumerate_pixels() {
+      fix_empty_pixel = fix_empty_pixel && (*pixel == TRANSPARENT_PIXEL);
+    }
+    assert!(fix_empty_pixel);
+  }
+
+  #[test]
+  fn it_fixes_empty_pixel_with_1_bodyempty_pixel_neighbour() {
+    let mut texture =
+      Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let mut rd = Vec::<f64>::with_capacity(
+      usize::try_from(texture.grids_rows * texture.grids_cols).unwrap());
+    let (a, b) = (3, 6);
+    let mut rng = thread_rng();
+    let is_body = rng.gen_range(0.6, 1.);
+    for i in 0..(texture.grids_rows * texture.grids_cols) {
+      if i == texture.grids_cols * a + b + 1 {
+        rd.push(is_body);
+      } else {
+        rd.push(rng.gen_range(0., 1.));
+      }
+    }
+    texture.grids.insert((a, b + 1), (0, FullPixel::BodyEmpty));
+    let palette = vec![texture.palette_generation.run(3, &mut thread_rng())];
+    texture.fix_empty_pixel(&mut image, &palette, (a, b), &rd);
+    let mut fix_empty_pixel = true;
+    for (x, y, pixel) in image.enumerate_pixels() {
+      if (x >= a * texture.pixel_ratio) &&
+        (x < (a + 1) * texture.pixel_ratio) &&
+        (y >= b * texture.pixel_ratio) &&
+        (y < (b + 1) * texture.pixel_ratio) {
+          fix_empty_pixel =
+            fix_empty_pixel &&
+              (*pixel == palette.get(0).unwrap().get_border_color());
+      } else {
+        fix_empty_pixel = fix_empty_pixel && (*pixel == TRANSPARENT_PIXEL);
+      }
+    }
+    assert!(fix_empty_pixel);
+  }
+
+  #[test]
+  fn it_does_not_fix_empty_pixel_with_1_bodyempty_pixel_neighbour() {
+    let mut texture =
+      Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let mut rd = Vec::<f64>::with_capacity(
+      usize::try_from(texture.grids_rows * texture.grids_cols).unwrap());
+    let (a, b) = (3, 6);
+    let mut rng = thread_rng();
+    let is_empty = rng.gen_range(0., 0.4);
+    for i in 0..(texture.grids_rows * texture.grids_cols) {
+      if i == texture.grids_cols * a + b + 1 {
+        rd.push(is_empty);
+      } else {
+        rd.push(rng.gen_range(0., 1.));
+      }
+    }
+    texture.grids.insert((a, b + 1), (0, FullPixel::BodyEmpty));
+    let palette = vec![texture.palette_generation.run(3, &mut thread_rng())];
+    texture.fix_empty_pixel(&mut image, &palette, (a, b), &rd);
+    let mut fix_empty_pixel = true;
+    for (_, _, pixel) in image.enumerate_pixels() {
+      fix_empty_pixel = fix_empty_pixel && (*pixel == TRANSPARENT_PIXEL);
+    }
+    assert!(fix_empty_pixel);
+  }
+
+  #[test]
+  fn it_fixes_bodyborder_pixel_into_body_pixel() {
+    let mut texture =
+      Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let mut rd = Vec::<f64>::with_capacity(
+      usize::try_from(texture.grids_rows * texture.grids_cols).unwrap());
+    let (a, b) = (3, 6);
+    let mut rng = thread_rng();
+    let is_body = rng.gen_range(0.6, 1.);
+    for i in 0..(texture.grids_rows * texture.grids_cols) {
+      if i == texture.grids_cols * a + b {
+        rd.push(is_body);
+      } else {
+        rd.push(rng.gen_range(0., 1.));
+      }
+    }
+    texture.grids.insert((a, b), (0, FullPixel::BodyBorder));
+    let palette = vec![texture.palette_generation.run(3, &mut thread_rng())];
+    texture.fix_pixel(&mut image, &palette, (a, b), &rd);
+    let mut fix_pixel = true;
+    for (x, y, pixel) in image.enumerate_pixels() {
+      if (x >= a * texture.pixel_ratio) &&
+        (x < (a + 1) * texture.pixel_ratio) &&
+        (y >= b * texture.pixel_ratio) &&
+        (y < (b + 1) * texture.pixel_ratio) {
+          fix_pixel = fix_pixel &&
+            (*pixel == palette.get(0).unwrap().get_body_color());
+      } else {
+        fix_pixel = fix_pixel && (*pixel == TRANSPARENT_PIXEL);
+      }
+    }
+    assert!(fix_pixel);
+  }
+
+  #[test]
+  fn it_fixes_bodyborder_pixel_into_border_pixel() {
+    let mut texture =
+      Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let mut rd = Vec::<f64>::with_capacity(
+      usize::try_from(texture.grids_rows * texture.grids_cols).unwrap());
+    let (a, b) = (3, 6);
+    let mut rng = thread_rng();
+    let is_border = rng.gen_range(0., 0.4);
+    for i in 0..(texture.grids_rows * texture.grids_cols) {
+      if i == texture.grids_cols * a + b {
+        rd.push(is_border);
+      } else {
+        rd.push(rng.gen_range(0., 1.));
+      }
+    }
+    texture.grids.insert((a, b), (0, FullPixel::BodyBorder));
+    let palette = vec![texture.palette_generation.run(3, &mut thread_rng())];
+    texture.fix_pixel(&mut image, &palette, (a, b), &rd);
+    let mut fix_pixel = true;
+    for (x, y, pixel) in image.enumerate_pixels() {
+      if (x >= a * texture.pixel_ratio) &&
+        (x < (a + 1) * texture.pixel_ratio) &&
+        (y >= b * texture.pixel_ratio) &&
+        (y < (b + 1) * texture.pixel_ratio) {
+          fix_pixel = fix_pixel &&
+            (*pixel == palette.get(0).unwrap().get_border_color());
+      } else {
+        fix_pixel = fix_pixel && (*pixel == TRANSPARENT_PIXEL);
+      }
+    }
+    assert!(fix_pixel);
+  }
+
+  #[test]
+  fn it_fixes_bodyempty_pixel_into_body_pixel() {
+    let mut texture =
+      Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let mut rd = Vec::<f64>::with_capacity(
+      usize::try_from(texture.grids_rows * texture.grids_cols).unwrap());
+    let (a, b) = (3, 6);
+    let mut rng = thread_rng();
+    let is_body = rng.gen_range(0.6, 1.);
+    for i in 0..(texture.grids_rows * texture.grids_cols) {
+      if i == texture.grids_cols * a + b {
+        rd.push(is_body);
+      } else {
+        rd.push(rng.gen_range(0., 1.));
+      }
+    }
+    texture.grids.insert((a, b), (0, FullPixel::BodyEmpty));
+    let palette = vec![texture.palette_generation.run(3, &mut thread_rng())];
+    texture.fix_pixel(&mut image, &palette, (a, b), &rd);
+    let mut fix_pixel = true;
+    for (x, y, pixel) in image.enumerate_pixels() {
+      if (x >= a * texture.pixel_ratio) &&
+        (x < (a + 1) * texture.pixel_ratio) &&
+        (y >= b * texture.pixel_ratio) &&
+        (y < (b + 1) * texture.pixel_ratio) {
+          fix_pixel = fix_pixel &&
+            (*pixel == palette.get(0).unwrap().get_body_color());
+      } else {
+        fix_pixel = fix_pixel && (*pixel == TRANSPARENT_PIXEL);
+      }
+    }
+    assert!(fix_pixel);
+  }
+
+  #[test]
+  fn it_fixes_bodyempty_pixel_into_empty_pixel_with_empty_pixels_neightbours() {
+    let mut texture =
+      Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let mut rd = Vec::<f64>::with_capacity(
+      usize::try_from(texture.grids_rows * texture.grids_cols).unwrap());
+    let (a, b) = (3, 6);
+    let mut rng = thread_rng();
+    let is_empty = rng.gen_range(0., 0.4);
+    for i in 0..(texture.grids_rows * texture.grids_cols) {
+      if i == texture.grids_cols * a + b {
+        rd.push(is_empty);
+      } else {
+        rd.push(rng.gen_range(0., 1.));
+      }
+    }
+    texture.grids.insert((a, b), (0, FullPixel::BodyEmpty));
+    let palette = vec![texture.palette_generation.run(3, &mut thread_rng())];
+    texture.fix_pixel(&mut image, &palette, (a, b), &rd);
+    let mut fix_pixel = true;
+    for (_x, _y, pixel) in image.enumerate_pixels() {
+      fix_pixel = fix_pixel && (*pixel == TRANSPARENT_PIXEL);
+    }
+    assert!(fix_pixel);
+  }
+
+  #[test]
+  fn it_fixes_bodyempty_pixel_into_empty_pixel_surrounded_by_border_pixel_neightbours() {
+    let mut texture =
+      Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let mut rd = Vec::<f64>::with_capacity(
+      usize::try_from(texture.grids_rows * texture.grids_cols).unwrap());
+    let (a, b) = (3, 6);
+    let mut rng = thread_rng();
+    let is_empty = rng.gen_range(0., 0.4);
+    for i in 0..(texture.grids_rows * texture.grids_cols) {
+      if i == texture.grids_cols * a + b {
+        rd.push(is_empty);
+      } else {
+        rd.push(rng.gen_range(0., 1.));
+      }
+    }
+    texture.grids.insert((a + 1, b), (0, FullPixel::Border));
+    texture.grids.insert((a - 1, b), (0, FullPixel::Border));
+    texture.grids.insert((a, b + 1), (0, FullPixel::Border));
+    texture.grids.insert((a, b - 1), (0, FullPixel::Border));
+    texture.grids.insert((a, b), (0, FullPixel::BodyEmpty));
+    let palette = vec![texture.palette_generation.run(3, &mut thread_rng())];
+    texture.fix_pixel(&mut image, &palette, (a + 1, b), &rd);
+    texture.fix_pixel(&mut image, &palette, (a - 1, b), &rd);
+    texture.fix_pixel(&mut image, &palette, (a, b + 1), &rd);
+    texture.fix_pixel(&mut image, &palette, (a, b - 1), &rd);
+    texture.fix_pixel(&mut image, &palette, (a, b), &rd);
+    let mut fix_pixel = true;
+    for (x, y, pixel) in image.enumerate_pixels() {
+      if (x >= (a + 1) * texture.pixel_ratio) &&
+        (x < (a + 2) * texture.pixel_ratio) &&
+        (y >= b * texture.pixel_ratio) &&
+        (y < (b + 1) * texture.pixel_ratio) {
+          fix_pixel = fix_pixel &&
+            (*pixel == palette.get(0).unwrap().get_border_color());
+      } else if (x >= (a - 1) * texture.pixel_ratio) &&
+        (x < a * texture.pixel_ratio) &&
+        (y >= b * texture.pixel_ratio) &&
+        (y < (b + 1) * texture.pixel_ratio) {
+          fix_pixel = fix_pixel &&
+            (*pixel == palette.get(0).unwrap().get_border_color());
+      } else if (x >= a * texture.pixel_ratio) &&
+        (x < (a + 1) * texture.pixel_ratio) &&
+        (y >= (b + 1) * texture.pixel_ratio) &&
+        (y < (b + 2) * texture.pixel_ratio) {
+          fix_pixel = fix_pixel &&
+            (*pixel == palette.get(0).unwrap().get_border_color());
+      } else if (x >= a * texture.pixel_ratio) &&
+        (x < (a + 1) * texture.pixel_ratio) &&
+        (y >= (b - 1) * texture.pixel_ratio) &&
+        (y < b * texture.pixel_ratio) {
+          fix_pixel = fix_pixel &&
+            (*pixel == palette.get(0).unwrap().get_border_color());
+      } else {
+        fix_pixel = fix_pixel && (*pixel == TRANSPARENT_PIXEL);
+      }
+    }
+    assert!(fix_pixel);
+  }
+
+  #[test]
+  fn it_fixes_bodyempty_pixel_into_border_pixel_with_1_body_pixel_neightbour() {
+    let mut texture =
+      Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let mut rd = Vec::<f64>::with_capacity(
+      usize::try_from(texture.grids_rows * texture.grids_cols).unwrap());
+    let (a, b) = (3, 6);
+    let mut rng = thread_rng();
+    let is_empty = rng.gen_range(0., 0.4);
+    for i in 0..(texture.grids_rows * texture.grids_cols) {
+      if i == texture.grids_cols * a + b {
+        rd.push(is_empty);
+      } else {
+        rd.push(rng.gen_range(0., 1.));
+      }
+    }
+    texture.grids.insert((a, b), (0, FullPixel::BodyEmpty));
+    texture.grids.insert((a - 1, b), (0, FullPixel::Body));
+    let palette = vec![texture.palette_generation.run(3, &mut thread_rng())];
+    texture.fix_pixel(&mut image, &palette, (a - 1, b), &rd);
+    texture.fix_pixel(&mut image, &palette, (a, b), &rd);
+    let mut fix_pixel = true;
+    for (x, y, pixel) in image.enumerate_pixels() {
+      if (x >= (a - 1) * texture.pixel_ratio) &&
+        (x < a * texture.pixel_ratio) &&
+        (y >= b * texture.pixel_ratio) &&
+        (y < (b + 1) * texture.pixel_ratio) {
+          fix_pixel = fix_pixel &&
+            (*pixel == palette.get(0).unwrap().get_body_color());
+      } else if (x >= a * texture.pixel_ratio) &&
+        (x < (a + 1) * texture.pixel_ratio) &&
+        (y >= b * texture.pixel_ratio) &&
+        (y < (b + 1) * texture.pixel_ratio) {
+          fix_pixel = fix_pixel &&
+            (*pixel == palette.get(0).unwrap().get_border_color());
+      } else {
+        fix_pixel = fix_pixel && (*pixel == TRANSPARENT_PIXEL);
+      }
+    }
+    assert!(fix_pixel);
+  }
+
+  #[test]
+  fn it_fixes_bodyempty_pixel_into_border_pixel_with_1_bodyempty_pixel_neightbour() {
+    let mut texture =
+      Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let mut rd = Vec::<f64>::with_capacity(
+      usize::try_from(texture.grids_rows * texture.grids_cols).unwrap());
+    let (a, b) = (3, 6);
+    let mut rng = thread_rng();
+    let is_empty = rng.gen_range(0., 0.4);
+    let is_body = rng.gen_range(0.6, 1.);
+    for i in 0..(texture.grids_rows * texture.grids_cols) {
+      if i == texture.grids_cols * a + b {
+        rd.push(is_empty);
+      } else if i == texture.grids_cols * (a - 1) + b {
+        rd.push(is_body);
+      } else {
+        rd.push(rng.gen_range(0., 1.));
+      }
+    }
+    texture.grids.insert((a, b), (0, FullPixel::BodyEmpty));
+    texture.grids.insert((a - 1, b), (0, FullPixel::BodyEmpty));
+    let palette = vec![texture.palette_generation.run(3, &mut thread_rng())];
+    texture.fix_pixel(&mut image, &palette, (a - 1, b), &rd);
+    texture.fix_pixel(&mut image, &palette, (a, b), &rd);
+    let mut fix_pixel = true;
+    for (x, y, pixel) in image.enumerate_pixels() {
+      if (x >= (a - 1) * texture.pixel_ratio) &&
+        (x < a * texture.pixel_ratio) &&
+        (y >= b * texture.pixel_ratio) &&
+        (y < (b + 1) * texture.pixel_ratio) {
+          fix_pixel = fix_pixel &&
+            (*pixel == palette.get(0).unwrap().get_body_color());
+      } else if (x >= a * texture.pixel_ratio) &&
+        (x < (a + 1) * texture.pixel_ratio) &&
+        (y >= b * texture.pixel_ratio) &&
+        (y < (b + 1) * texture.pixel_ratio) {
+          fix_pixel = fix_pixel &&
+            (*pixel == palette.get(0).unwrap().get_border_color());
+      } else {
+        fix_pixel = fix_pixel && (*pixel == TRANSPARENT_PIXEL);
+      }
+    }
+    assert!(fix_pixel);
+  }
+
+  #[test]
+  fn it_fixes_bodyempty_pixel_into_empty_pixel_with_1_bodyempty_pixel_neightbour() {
+    let mut texture =
+      Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let mut rd = Vec::<f64>::with_capacity(
+      usize::try_from(texture.grids_rows * texture.grids_cols).unwrap());
+    let (a, b) = (3, 6);
+    let mut rng = thread_rng();
+    let is_empty = rng.gen_range(0., 0.4);
+    for i in 0..(texture.grids_rows * texture.grids_cols) {
+      if i == texture.grids_cols * a + b {
+        rd.push(is_empty);
+      } else if i == texture.grids_cols * (a - 1) + b {
+        rd.push(is_empty);
+      } else {
+        rd.push(rng.gen_range(0., 1.));
+      }
+    }
+    texture.grids.insert((a, b), (0, FullPixel::BodyEmpty));
+    texture.grids.insert((a - 1, b), (0, FullPixel::BodyEmpty));
+    let palette = vec![texture.palette_generation.run(3, &mut thread_rng())];
+    texture.fix_pixel(&mut image, &palette, (a - 1, b), &rd);
+    texture.fix_pixel(&mut image, &palette, (a, b), &rd);
+    let mut fix_pixel = true;
+    for (_x, _y, pixel) in image.enumerate_pixels() {
+      fix_pixel = fix_pixel && (*pixel == TRANSPARENT_PIXEL);
+    }
+    assert!(fix_pixel);
+  }
+
+  #[test]
+  fn it_fixes_bodyempty_pixel_into_border_pixel_with_1_bodyborder_pixel_neightbour() {
+    let mut texture =
+      Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let mut rd = Vec::<f64>::with_capacity(
+      usize::try_from(texture.grids_rows * texture.grids_cols).unwrap());
+    let (a, b) = (3, 6);
+    let mut rng = thread_rng();
+    let is_empty = rng.gen_range(0., 0.4);
+    let is_body = rng.gen_range(0.6, 1.);
+    for i in 0..(texture.grids_rows * texture.grids_cols) {
+      if i == texture.grids_cols * a + b {
+        rd.push(is_empty);
+      } else if i == texture.grids_cols * (a - 1) + b {
+        rd.push(is_body);
+      } else {
+        rd.push(rng.gen_range(0., 1.));
+      }
+    }
+    texture.grids.insert((a, b), (0, FullPixel::BodyEmpty));
+    texture.grids.insert((a - 1, b), (0, FullPixel::BodyBorder));
+    let palette = vec![texture.palette_generation.run(3, &mut thread_rng())];
+    texture.fix_pixel(&mut image, &palette, (a - 1, b), &rd);
+    texture.fix_pixel(&mut image, &palette, (a, b), &rd);
+    let mut fix_pixel = true;
+    for (x, y, pixel) in image.enumerate_pixels() {
+      if (x >= (a - 1) * texture.pixel_ratio) &&
+        (x < a * texture.pixel_ratio) &&
+        (y >= b * texture.pixel_ratio) &&
+        (y < (b + 1) * texture.pixel_ratio) {
+          fix_pixel = fix_pixel &&
+            (*pixel == palette.get(0).unwrap().get_body_color());
+      } else if (x >= a * texture.pixel_ratio) &&
+        (x < (a + 1) * texture.pixel_ratio) &&
+        (y >= b * texture.pixel_ratio) &&
+        (y < (b + 1) * texture.pixel_ratio) {
+          fix_pixel = fix_pixel &&
+            (*pixel == palette.get(0).unwrap().get_border_color());
+      } else {
+        fix_pixel = fix_pixel && (*pixel == TRANSPARENT_PIXEL);
+      }
+    }
+    assert!(fix_pixel);
+  }
+
+  #[test]
+  fn it_fixes_bodyempty_pixel_into_empty_pixel_with_1_bodyborder_pixel_neightbour() {
+    let mut texture =
+      Texture::new(&Mask::new(10, 10), &Settings::new()).unwrap();
+    let mut image = image::ImageBuffer::from_pixel(
+      texture.get_img_width(), texture.get_img_height(), TRANSPARENT_PIXEL);
+    let mut rd = Vec::<f64>::with_capacity(
+      usize::try_from(texture.grids_rows * texture.grids_cols).unwrap());
+    let (a, b) = (3, 6);
+    let mut rng = thread_rng();
+    let is_empty = rng.gen_range(0., 0.4);
+    let is_border = rng.gen_range(0., 0.4);
+    for i in 0..(texture.grids_rows * texture.grids_cols) {
+      if i == texture.grids_cols * a + b {
+        rd.push(is_empty);
+      } else if i == texture.grids_cols * (a - 1) + b {
+        rd.push(is_border);
+      } else {
+        rd.push(rng.gen_range(0., 1.));
+      }
+    }
+    texture.grids.insert((a, b), (0, FullPixel::BodyEmpty));
+    texture.grids.insert((a - 1, b), (0, FullPixel::BodyBorder));
+    let palette = vec![texture.palette_generation.run(3, &mut thread_rng())];
+    texture.fix_pixel(&mut image, &palette, (a - 1, b), &rd);
+    texture.fix_pixel(&mut image, &palette, (a, b), &rd);
+    let mut fix_pixel = true;
+    for (x, y, pixel) in image.enumerate_pixels() {
+      if (x >= (a - 1) * texture.pixel_ratio) &&
+        (x < a * texture.pixel_ratio) &&
+        (y >= b * texture.pixel_ratio) &&
+        (y < (b + 1) * texture.pixel_ratio) {
+          fix_pixel = fix_pixel &&
+            (*pixel == palette.get(0).unwrap().get_border_color());
+      } else {
+        fix_pixel = fix_pixel && (*pixel == TRANSPARENT_PIXEL);
+      }
+    }
+    assert!(fix_pixel);
+  }
+
+  #[test]
+  fn it_renders_the_same_texture_with_the_same_seed() {
+    let mut mask = Mask::new(6, 6);
+    let grid_id = mask.add_grid();
+    mask.set_cell(0, (1, 1), FullPixel::BodyEmpty).unwrap();
+    mask.set_cell(grid_id, (2, 2), FullPixel::BodyBorder).unwrap();
+    mask.set_cell(grid_id, (3, 3), FullPixel::Body).unwrap();
+    let texture = Texture::new(&mask, &Settings::new()).unwrap();
+    let image = texture.render(7);
+    assert!((image == texture.render(7)) && (image.dimensions() == (24, 24)) &&
+      (*image.get_pixel(12, 12) != TRANSPARENT_PIXEL) &&
+      (*image.get_pixel(0, 0) == TRANSPARENT_PIXEL));
+  }
+
+  #[test]
+  fn it_renders_the_first_grid_over_the_others() {
+    let mut mask = Mask::new(2, 1);
+    let grid_id = mask.add_grid();
+    mask.set_cell(0, (0, 0), FullPixel::SpecificColor(1, 2, 3)).unwrap();
+    mask.set_cell(grid_id, (0, 0), FullPixel::SpecificColor(4, 5, 6))
+      .unwrap();
+    mask.set_cell(grid_id, (1, 0), FullPixel::SpecificColor(4, 5, 6))
+      .unwrap();
+    let settings = Settings { pixel_ratio: 1, ..Settings::new() };
+    let image = Texture::new(&mask, &settings).unwrap().render(0);
+    assert!((*image.get_pixel(0, 0) == Rgba([1, 2, 3, 255])) &&
+      (*image.get_pixel(1, 0) == Rgba([4, 5, 6, 255])));
+  }
+
+  #[test]
+  fn it_does_not_set_a_cell_outside_of_the_mask() {
+    let mut mask = Mask::new(2, 2);
+    assert!(mask.set_cell(0, (2, 0), FullPixel::Body).is_err() &&
+      mask.set_cell(1, (0, 0), FullPixel::Body).is_err() &&
+      mask.erase_cell(0, (0, 2)).is_err() &&
+      mask.set_cell(0, (1, 1), FullPixel::Body).is_ok() &&
+      (mask.get_cell(0, (1, 1)) == Some(FullPixel::Body)));
+  }
+
+  #[test]
+  fn it_does_not_make_a_texture_with_a_null_ratio() {
+    let settings = Settings { border_ratio: 0, ..Settings::new() };
+    assert!(Texture::new(&Mask::new(2, 2), &settings).is_err());
+  }
+
+  #[test]
+  fn it_makes_a_mask_from_templates() {
+    let templates = vec![
+      Template { name: String::from("a"), width: 3, height: 1,
+        cells: vec![(2, 0, FullPixel::Border)], hidden: false,
+        locked: false },
+      Template { name: String::from("b"), width: 1, height: 4,
+        cells: Vec::new(), hidden: false, locked: false },
+    ];
+    let mask = Mask::from_templates(&templates);
+    assert!((mask.get_width() == 3) && (mask.get_height() == 4) &&
+      (mask.get_nb_grids() == 2) &&
+      (mask.get_cell(0, (2, 0)) == Some(FullPixel::Border)));
+  }
+}
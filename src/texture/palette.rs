@@ -2,7 +2,7 @@ extern crate image;
 use image::Rgba;
 
 extern crate rand;
-use rand::{thread_rng, Rng};
+use rand::Rng;
 
 /// An RGBA array
 const PREDEFINED_COLORS: [Rgba<u8>; 14] = [
@@ -57,10 +57,9 @@ pub enum PaletteGeneration {
 
 impl PaletteGeneration {
 
-  pub fn run(&self, border_ratio: u8) -> Palette {
+  pub fn run<R: Rng>(&self, border_ratio: u8, rng: &mut R) -> Palette {
     match self {
       PaletteGeneration::RandomPredefinedColors => {
-        let mut rng = thread_rng();
         Palette::new(PREDEFINED_COLORS[rng.gen_range(0, 13)], border_ratio)
       },
     }
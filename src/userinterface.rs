@@ -12,6 +12,9 @@ use std::io::Write;
 pub mod errors;
 pub mod events;
 
+/// Module for the colors of the user interface, looked up by every widget
+pub mod theme;
+
 /// Module for rendering and events catching of the different views
 pub mod views;
 
@@ -74,6 +77,11 @@ impl UserInterface {
           let min_height = max(views::MIN_HEIGHT_EXIT, views::MIN_HEIGHT);
           UserInterface::cmp(terminal_size, (min_width, min_height))
         },
+        events::UserEvent::Export => {
+          let min_width = max(views::MIN_WIDTH_EXPORT, views::MIN_WIDTH);
+          let min_height = max(views::MIN_HEIGHT_EXPORT, views::MIN_HEIGHT);
+          UserInterface::cmp(terminal_size, (min_width, min_height))
+        },
         events::UserEvent::SetBrushColor => {
           let min_width =
             max(views::MIN_WIDTH_SETBRUSHCOLOR, views::MIN_WIDTH);
@@ -100,6 +108,12 @@ impl UserInterface {
           let min_height = max(views::MIN_HEIGHT_GRIDNAME, views::MIN_HEIGHT);
           UserInterface::cmp(terminal_size, (min_width, min_height))
         },
+        events::UserEvent::ResizeGrid => {
+          let min_width = max(views::MIN_WIDTH_RESIZEGRID, views::MIN_WIDTH);
+          let min_height =
+            max(views::MIN_HEIGHT_RESIZEGRID, views::MIN_HEIGHT);
+          UserInterface::cmp(terminal_size, (min_width, min_height))
+        },
         events::UserEvent::GridNumberError => {
           let min_width =
             max(views::MIN_WIDTH_GRIDNUMBERERROR, views::MIN_WIDTH);
@@ -107,6 +121,11 @@ impl UserInterface {
             max(views::MIN_HEIGHT_GRIDNUMBERERROR, views::MIN_HEIGHT);
           UserInterface::cmp(terminal_size, (min_width, min_height))
         },
+        events::UserEvent::History => {
+          let min_width = max(views::MIN_WIDTH_HISTORY, views::MIN_WIDTH);
+          let min_height = max(views::MIN_HEIGHT_HISTORY, views::MIN_HEIGHT);
+          UserInterface::cmp(terminal_size, (min_width, min_height))
+        },
         events::UserEvent::GenerationError => {
           let min_width =
             max(views::MIN_WIDTH_GENERATIONERROR, views::MIN_WIDTH);
@@ -148,7 +167,17 @@ impl UserInterface {
             max(views::MIN_HEIGHT_SWITCHGRIDSERROR, views::MIN_HEIGHT);
           UserInterface::cmp(terminal_size, (min_width, min_height))
         },
-        events::UserEvent::Generate => errors::UserError::NoneError,
+        events::UserEvent::Tasks => {
+          let min_width = max(views::MIN_WIDTH_TASKS, views::MIN_WIDTH);
+          let min_height = max(views::MIN_HEIGHT_TASKS, views::MIN_HEIGHT);
+          UserInterface::cmp(terminal_size, (min_width, min_height))
+        },
+        events::UserEvent::Generate | events::UserEvent::ExportGrids |
+        events::UserEvent::CancelTask | events::UserEvent::PauseTasks |
+        events::UserEvent::DismissError | events::UserEvent::RetryTask |
+        events::UserEvent::CancelAndShutdown |
+        events::UserEvent::FinishWithoutInterface =>
+          errors::UserError::NoneError,
       }
   }
 
@@ -7,20 +7,31 @@ use std::io::Write;
 
 #[derive(Clone, Copy)]
 pub enum UserEvent {
+  CancelAndShutdown,
+  CancelTask,
   Continue,
+  DismissError,
   Exit,
+  Export,
+  ExportGrids,
+  FinishWithoutInterface,
   Generate,
   GenerationError,
   GridName,
   GridNumberError,
+  History,
   LogOverflow,
+  PauseTasks,
   RenameGrid,
+  ResizeGrid,
+  RetryTask,
   SetBrushColor,
   SetGenerationNumber,
   SetTextureFormat,
   Shutdown,
   SwitchGridsError,
   SwitchGridsOrder,
+  Tasks,
   UnavailableThread,
 }
 
@@ -0,0 +1,252 @@
+extern crate std;
+use std::sync::OnceLock;
+
+extern crate tui;
+use tui::style::Color;
+
+use liriodendron::error::{Error, Result};
+use liriodendron::utils::FullPixel;
+
+/// Above this sum of RGB values, a text written on a color is black
+const RGB_SUM_DIVIDED_BY_2: u16 = 255 * 3 / 2;
+
+/// Theme used by every widget, set once before the user interface starts
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Built-in themes. The 16 colors one suits terminals without true colors,
+/// the colorblind one uses the Okabe-Ito palette.
+const THEMES: [(&str, Theme); 3] = [
+  ("default", Theme {
+    body: Color::Yellow,
+    border: Color::Red,
+    body_border: Color::Blue,
+    body_empty: Color::Green,
+    workspace: Color::Rgb(200, 200, 200),
+    background: Color::Black,
+    scroller: Color::Rgb(150, 150, 150),
+    error: Color::Red,
+    warning: Color::Yellow,
+    truecolor: true,
+  }),
+  ("colorblind", Theme {
+    body: Color::Rgb(240, 228, 66),
+    border: Color::Rgb(213, 94, 0),
+    body_border: Color::Rgb(0, 114, 178),
+    body_empty: Color::Rgb(0, 158, 115),
+    workspace: Color::Rgb(200, 200, 200),
+    background: Color::Black,
+    scroller: Color::Rgb(150, 150, 150),
+    error: Color::Rgb(213, 94, 0),
+    warning: Color::Rgb(230, 159, 0),
+    truecolor: true,
+  }),
+  ("16colors", Theme {
+    body: Color::Yellow,
+    border: Color::Red,
+    body_border: Color::Blue,
+    body_empty: Color::Green,
+    workspace: Color::Gray,
+    background: Color::Black,
+    scroller: Color::DarkGray,
+    error: Color::Red,
+    warning: Color::Yellow,
+    truecolor: false,
+  }),
+];
+
+/// Named colors of the configuration file with their usual RGB values, to
+/// find the nearest one of a RGB color
+const COLORS: [(&str, Color, (u8, u8, u8)); 16] = [
+  ("black", Color::Black, (0, 0, 0)),
+  ("red", Color::Red, (205, 0, 0)),
+  ("green", Color::Green, (0, 205, 0)),
+  ("yellow", Color::Yellow, (205, 205, 0)),
+  ("blue", Color::Blue, (0, 0, 238)),
+  ("magenta", Color::Magenta, (205, 0, 205)),
+  ("cyan", Color::Cyan, (0, 205, 205)),
+  ("gray", Color::Gray, (229, 229, 229)),
+  ("darkgray", Color::DarkGray, (127, 127, 127)),
+  ("lightred", Color::LightRed, (255, 0, 0)),
+  ("lightgreen", Color::LightGreen, (0, 255, 0)),
+  ("lightyellow", Color::LightYellow, (255, 255, 0)),
+  ("lightblue", Color::LightBlue, (92, 92, 255)),
+  ("lightmagenta", Color::LightMagenta, (255, 0, 255)),
+  ("lightcyan", Color::LightCyan, (0, 255, 255)),
+  ("white", Color::White, (255, 255, 255)),
+];
+
+/// Colors of the user interface
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+  body: Color,
+  border: Color,
+  body_border: Color,
+  body_empty: Color,
+
+  /// Background around the grids
+  workspace: Color,
+
+  /// Background of the grids, of the scrollers and of one shortcut line out
+  /// of two
+  background: Color,
+
+  scroller: Color,
+  error: Color,
+  warning: Color,
+
+  /// When <i>false</i>, RGB colors are replaced by the nearest of the 16
+  /// named colors
+  truecolor: bool,
+}
+
+impl Default for Theme {
+  fn default() -> Theme {
+    THEMES[0].1
+  }
+}
+
+impl Theme {
+
+  pub fn get_builtin(name: &str) -> Option<Theme> {
+    THEMES.iter().find(|&&(theme_name, _)| theme_name == name)
+      .map(|&(_, theme)| theme)
+  }
+
+  /// Sets the color called <i>name</i>, or <i>truecolor</i>, from the
+  /// configuration file. A color is one of the 16 named colors or
+  /// <i>#rrggbb</i>.
+  pub fn set(&mut self, name: &str, value: &str) -> Result<()> {
+    if name == "truecolor" {
+      self.truecolor = value.parse::<bool>().map_err(|_|
+        Error::InvalidArgument(String::from(
+          "truecolor must be true or false.")))?;
+      return Ok(())
+    }
+    let color = parse_color(value)?;
+    match name {
+      "body" => self.body = color,
+      "border" => self.border = color,
+      "body_border" => self.body_border = color,
+      "body_empty" => self.body_empty = color,
+      "workspace" => self.workspace = color,
+      "background" => self.background = color,
+      "scroller" => self.scroller = color,
+      "error" => self.error = color,
+      "warning" => self.warning = color,
+      _ => return Err(Error::InvalidArgument(format!(
+        "{} is not a color of a theme.", name))),
+    }
+    Ok(())
+  }
+
+  /// Color of a cell brushed with <i>pixel</i>
+  pub fn get_pixel_color(&self, pixel: FullPixel) -> Color {
+    match pixel {
+      FullPixel::Body => self.get_color(self.body),
+      FullPixel::Border => self.get_color(self.border),
+      FullPixel::BodyBorder => self.get_color(self.body_border),
+      FullPixel::BodyEmpty => self.get_color(self.body_empty),
+      FullPixel::SpecificColor(red, green, blue) =>
+        self.get_color(Color::Rgb(red, green, blue)),
+    }
+  }
+
+  /// Color of a cell of another grid shown under the current one, halfway
+  /// between the color of <i>pixel</i> and the background
+  pub fn get_dimmed_color(&self, pixel: FullPixel) -> Color {
+    let color = match pixel {
+      FullPixel::Body => self.body,
+      FullPixel::Border => self.border,
+      FullPixel::BodyBorder => self.body_border,
+      FullPixel::BodyEmpty => self.body_empty,
+      FullPixel::SpecificColor(red, green, blue) =>
+        Color::Rgb(red, green, blue),
+    };
+    match (get_rgb(color), get_rgb(self.background)) {
+      (Some((red, green, blue)), Some((bg_red, bg_green, bg_blue))) => {
+        let half = |x: u8, y: u8| ((u16::from(x) + u16::from(y)) / 2) as u8;
+        self.get_color(Color::Rgb(half(red, bg_red), half(green, bg_green),
+          half(blue, bg_blue)))
+      },
+      _ => self.get_color(color),
+    }
+  }
+
+  /// Color of a text written on the RGB color <i>(red, green, blue)</i>
+  pub fn get_text_color(&self, (red, green, blue): (u8, u8, u8)) -> Color {
+    if [red, green, blue].iter().map(|&x| u16::from(x)).sum::<u16>() >
+      RGB_SUM_DIVIDED_BY_2 {
+        Color::Black
+    } else {
+      Color::White
+    }
+  }
+
+  pub fn get_workspace_color(&self) -> Color {
+    self.get_color(self.workspace)
+  }
+
+  pub fn get_background_color(&self) -> Color {
+    self.get_color(self.background)
+  }
+
+  pub fn get_scroller_color(&self) -> Color {
+    self.get_color(self.scroller)
+  }
+
+  pub fn get_error_color(&self) -> Color {
+    self.get_color(self.error)
+  }
+
+  pub fn get_warning_color(&self) -> Color {
+    self.get_color(self.warning)
+  }
+
+  /// Returns <i>color</i>, or the nearest named color if it is a RGB color
+  /// and the theme has no true colors
+  pub fn get_color(&self, color: Color) -> Color {
+    match color {
+      Color::Rgb(red, green, blue) if !self.truecolor => {
+        let distance = |&(_, _, (r, g, b)): &(&str, Color, (u8, u8, u8))| {
+          [(red, r), (green, g), (blue, b)].iter()
+            .map(|&(x, y)| (i32::from(x) - i32::from(y)).pow(2)).sum::<i32>()
+        };
+        COLORS.iter().min_by_key(|color| distance(color))
+          .map(|&(_, color, _)| color).unwrap()
+      },
+      color => color,
+    }
+  }
+}
+
+/// RGB values of a RGB color or of a named color
+fn get_rgb(color: Color) -> Option<(u8, u8, u8)> {
+  match color {
+    Color::Rgb(red, green, blue) => Some((red, green, blue)),
+    color => COLORS.iter().find(|&&(_, named, _)| named == color)
+      .map(|&(_, _, rgb)| rgb),
+  }
+}
+
+fn parse_color(value: &str) -> Result<Color> {
+  let rgb = value.strip_prefix('#').filter(|hexa| hexa.len() == 6)
+    .and_then(|hexa| u32::from_str_radix(hexa, 16).ok());
+  match (rgb, COLORS.iter().find(|&&(name, _, _)| name == value)) {
+    (Some(rgb), _) =>
+      Ok(Color::Rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)),
+    (None, Some(&(_, color, _))) => Ok(color),
+    (None, None) => Err(Error::InvalidArgument(format!(
+      "{} is not a color, such as red or #ff0000.", value))),
+  }
+}
+
+/// Sets the theme of the user interface, only the first call is effective
+pub fn set(theme: Theme) {
+  let _ = THEME.set(theme);
+}
+
+/// Returns the theme of the user interface, the default one if it is not set
+pub fn get() -> &'static Theme {
+  THEME.get_or_init(Theme::default)
+}
+
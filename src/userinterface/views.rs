@@ -3,18 +3,22 @@ use num::PrimInt;
 
 pub mod edit;
 pub mod exit;
+pub mod export;
 pub mod generationerror;
 pub mod gridname;
 pub mod logoverflow;
 pub mod gridnumbererror;
+pub mod history;
 pub mod parameters;
 pub mod resizeerror;
+pub mod resizegrid;
 pub mod setbrushcolor;
 pub mod setgenerationnumber;
 pub mod settextureformat;
 pub mod shutdown;
 pub mod switchgridserror;
 pub mod switchgridsorder;
+pub mod tasks;
 pub mod unavailablethread;
 
 use crate::userinterface::widgets::{
@@ -23,7 +27,8 @@ use crate::userinterface::widgets::{
   grid::TAB_WIDTH,
   scroller::{ARROWS, SCROLLER},
   brushselector::{SELECTOR_HEIGHT, SELECTOR_WIDTH, RGB_BOX_HEIGHT, NB_BRUSHES},
-  shortcuts::{VERTICAL_BAR, KEYBOARD_AREA_WIDTH, ACTIONS_AREA_WIDTH}
+  shortcuts::{VERTICAL_BAR, KEYBOARD_AREA_WIDTH, ACTIONS_AREA_WIDTH},
+  statusbar::STATUS_BAR_HEIGHT
 };
 
 use crate::userinterface::views::parameters::render::{LARGER_COLOR_ROW,
@@ -48,7 +53,8 @@ pub const MIN_WIDTH_EDIT: u16 = MIN_BRUSH_AREA_WIDTH +
 pub const MIN_BRUSH_AREA_HEIGHT: u16 = SELECTOR_HEIGHT * NB_BRUSHES +
   RGB_BOX_HEIGHT + BORDERS;
 
-pub const MIN_GRID_AREA_HEIGHT: u16 = BORDERS * 2 + SCROLLER + MIN_GRID_SIZE;
+pub const MIN_GRID_AREA_HEIGHT: u16 = BORDERS * 2 + SCROLLER + MIN_GRID_SIZE +
+  STATUS_BAR_HEIGHT;
 
 pub const MIN_SHORTCUTS_AREA_HEIGHT: u16 = 4 * (ARROWS + BORDERS) +
   2 * GAUGE_HEIGHT;
@@ -92,17 +98,37 @@ pub const MIN_WIDTH_GENERATIONERROR: u16 = generationerror::render::WIN_WIDTH;
 pub const MIN_HEIGHT_GENERATIONERROR: u16 =
   generationerror::render::WIN_HEIGHT;
 
+/******************************** Export view *******************************/
+
+pub const MIN_WIDTH_EXPORT: u16 = export::render::WIN_WIDTH;
+pub const MIN_HEIGHT_EXPORT: u16 = export::render::WIN_HEIGHT;
+
+/******************************** Tasks view ********************************/
+
+pub const MIN_WIDTH_TASKS: u16 = tasks::render::WIN_WIDTH;
+pub const MIN_HEIGHT_TASKS: u16 = tasks::render::WIN_HEIGHT;
+
 /******************************* Grid Name view *****************************/
 
 pub const MIN_WIDTH_GRIDNAME: u16 = gridname::render::WIN_WIDTH;
 pub const MIN_HEIGHT_GRIDNAME: u16 = gridname::render::WIN_HEIGHT;
 
+/****************************** Resize Grid view ****************************/
+
+pub const MIN_WIDTH_RESIZEGRID: u16 = resizegrid::render::WIN_WIDTH;
+pub const MIN_HEIGHT_RESIZEGRID: u16 = resizegrid::render::WIN_HEIGHT;
+
 /************************** Grid Number Error view **************************/
 
 pub const MIN_WIDTH_GRIDNUMBERERROR: u16 = gridnumbererror::render::WIN_WIDTH;
 pub const MIN_HEIGHT_GRIDNUMBERERROR: u16 =
   gridnumbererror::render::WIN_HEIGHT;
 
+/******************************** History view ******************************/
+
+pub const MIN_WIDTH_HISTORY: u16 = history::render::WIN_WIDTH;
+pub const MIN_HEIGHT_HISTORY: u16 = history::render::WIN_HEIGHT;
+
 /***************************** Log Overflow view ****************************/
 
 pub const MIN_WIDTH_LOGOVERFLOW: u16 = logoverflow::render::WIN_WIDTH;
@@ -1,60 +1,106 @@
 extern crate crossterm;
-use crossterm::event::{poll, read, Event, KeyCode, KeyModifiers, MouseEvent,
+use crossterm::event::{poll, read, Event, KeyModifiers, MouseEvent,
   MouseButton};
 
 extern crate std;
 use std::time::Duration;
 
+use crate::config::keys::{Action, Context, Keys};
 use crate::log;
 
 use crate::events::UserEvent;
 use crate::errors::UserError;
 use crate::views::View;
 
-pub fn catch_events(log: &mut log::Log) -> (UserEvent, UserError, View) {
+pub fn catch_events(log: &mut log::Log, keys: &Keys)
+  -> (UserEvent, UserError, View) {
   if poll(Duration::from_nanos(1)).unwrap() {
     match read().unwrap() {
       Event::Key(key_event) => {
-        if key_event.modifiers.is_empty() {
-          match key_event.code {
-            KeyCode::Esc => {
+        let cursor_action = if log.cursor_isenabled() {
+          keys.get_action(Context::Cursor, key_event)
+        } else {
+          None
+        };
+        match cursor_action
+          .or_else(|| keys.get_action(Context::Edit, key_event)) {
+            Some(Action::CursorLeft) => {
+              log.cursor_left();
+              return (UserEvent::Continue, UserError::NoneError, View::Edit)
+            },
+            Some(Action::CursorDown) => {
+              log.cursor_down();
+              return (UserEvent::Continue, UserError::NoneError, View::Edit)
+            },
+            Some(Action::CursorUp) => {
+              log.cursor_up();
+              return (UserEvent::Continue, UserError::NoneError, View::Edit)
+            },
+            Some(Action::CursorRight) => {
+              log.cursor_right();
+              return (UserEvent::Continue, UserError::NoneError, View::Edit)
+            },
+            Some(Action::CursorBrush) => {
+              log.cursor_brush();
+            },
+            Some(Action::CursorErase) => {
+              log.cursor_erase();
+            },
+            Some(Action::CursorCyclePixel) => {
+              log.cursor_cyclepixel();
+            },
+            Some(Action::Exit) => {
               return (UserEvent::Exit, UserError::ResizeCheck, View::Edit)
             },
-            KeyCode::Char('a') => {
+            Some(Action::PreviousBrush) => {
               log.brush_previous();
             },
-            KeyCode::Char('c') => {
+            Some(Action::SetBrushColor) => {
               return (UserEvent::SetBrushColor,
                 UserError::ResizeCheck, View::Edit)
             },
-            KeyCode::Char('g') => {
+            Some(Action::Export) => {
+              return (UserEvent::Export, UserError::ResizeCheck, View::Edit)
+            },
+            Some(Action::Generate) => {
               return (UserEvent::SetGenerationNumber,
                 UserError::ResizeCheck, View::Edit)
             },
-            KeyCode::Char('h') => {
+            Some(Action::Tasks) => {
+              return (UserEvent::Tasks, UserError::ResizeCheck, View::Edit)
+            },
+            Some(Action::IncrementHeight) => {
               log.increment_height();
             },
-            KeyCode::Char('n') => {
+            Some(Action::ToggleCursor) => {
+              log.cursor_toggle();
+            },
+            Some(Action::NextGrid) => {
               log.grids_next();
             },
-            KeyCode::Char('q') => {
+            Some(Action::NextBrush) => {
               log.brush_next();
             },
-            KeyCode::Char('s') => {
+            Some(Action::SwitchView) => {
               return (UserEvent::Continue,
                 UserError::ResizeCheck, View::Parameters)
             },
-            KeyCode::Char('r') => {
+            Some(Action::ResizeGrid) => {
+              log.gridsize_reset();
+              return (UserEvent::ResizeGrid,
+                UserError::ResizeCheck, View::Edit)
+            },
+            Some(Action::RenameGrid) => {
               return (UserEvent::RenameGrid,
                 UserError::ResizeCheck, View::Edit)
             },
-            KeyCode::Char('u') => {
+            Some(Action::Undo) => {
               log.undo();
             },
-            KeyCode::Char('w') => {
+            Some(Action::IncrementWidth) => {
               log.increment_width();
             },
-            KeyCode::Char('+') => {
+            Some(Action::AddGrid) => {
               if !log.grids_isfull() {
                 return (UserEvent::GridName,
                   UserError::ResizeCheck, View::Edit)
@@ -63,7 +109,7 @@ pub fn catch_events(log: &mut log::Log) -> (UserEvent, UserError, View) {
                   UserError::ResizeCheck, View::Edit)
               }
             },
-            KeyCode::Char('-') => {
+            Some(Action::DeleteGrid) => {
               if !log.grids_isalone() {
                 log.grids_deletecurrentgrid();
               } else {
@@ -71,32 +117,32 @@ pub fn catch_events(log: &mut log::Log) -> (UserEvent, UserError, View) {
                   UserError::ResizeCheck, View::Edit)
               }
             },
-            KeyCode::Up => {
+            Some(Action::ScrollUp) => {
               log.gridscroll_scrollup();
             },
-            KeyCode::Down => {
+            Some(Action::ScrollDown) => {
               log.gridscroll_scrolldown();
             },
-            KeyCode::Right => {
+            Some(Action::ScrollRight) => {
               log.gridscroll_scrollright();
             },
-            KeyCode::Left => {
+            Some(Action::ScrollLeft) => {
               log.gridscroll_scrollleft();
             },
-            _ => (),
-          }
-        } else if key_event.modifiers == KeyModifiers::SHIFT {
-          match key_event.code {
-            KeyCode::Char('C') => {
+            Some(Action::ClearGrid) => {
               log.clear();
             },
-            KeyCode::Char('H') => {
+            Some(Action::DecrementHeight) => {
               log.decrement_height();
             },
-            KeyCode::Char('N') => {
+            Some(Action::History) => {
+              log.history_resetcursor();
+              return (UserEvent::History, UserError::ResizeCheck, View::Edit)
+            },
+            Some(Action::PreviousGrid) => {
               log.grids_previous();
             },
-            KeyCode::Char('S') => {
+            Some(Action::SwitchGridsOrder) => {
               if !log.grids_isalone() {
                 return (UserEvent::SwitchGridsOrder,
                   UserError::ResizeCheck, View::Edit)
@@ -105,26 +151,77 @@ pub fn catch_events(log: &mut log::Log) -> (UserEvent, UserError, View) {
                   UserError::ResizeCheck, View::Edit)
               }
             },
-            KeyCode::Char('U') => {
+            Some(Action::Redo) => {
               log.redo();
             },
-            KeyCode::Char('W') => {
+            Some(Action::DecrementWidth) => {
               log.decrement_width();
             },
+            Some(Action::ZoomIn) => {
+              log.zoom_in();
+            },
+            Some(Action::ZoomOut) => {
+              log.zoom_out();
+            },
+            Some(Action::ToggleOnionSkin) => {
+              log.overlay_toggleonionskin();
+            },
+            Some(Action::ToggleComposite) => {
+              log.overlay_togglecomposite();
+            },
+            Some(Action::ToggleVisibility) => {
+              log.grids_togglevisibility();
+            },
+            Some(Action::ToggleLock) => {
+              log.grids_togglelock();
+            },
             _ => (),
-          }
         }
       }
-      Event::Mouse(mouse_event) => match mouse_event {
-        MouseEvent::Down(MouseButton::Left, x, y, _) |
+      Event::Mouse(mouse_event) => {
+        match mouse_event {
+          MouseEvent::Down(_, x, y, _) | MouseEvent::Up(_, x, y, _) |
+          MouseEvent::Drag(_, x, y, _) | MouseEvent::ScrollDown(x, y, _) |
+          MouseEvent::ScrollUp(x, y, _) => log.pointer_setposition((x, y)),
+        };
+        match mouse_event {
+          MouseEvent::Down(MouseButton::Left, x, y, _) => {
+            let pressed = log.pointer_press((x, y));
+            if !pressed {
+              log.brush((x, y));
+            }
+          },
           MouseEvent::Drag(MouseButton::Left, x, y, _) => {
-            log.brush((x, y));
-        },
-        MouseEvent::Down(MouseButton::Right, x, y, _) |
-          MouseEvent::Drag(MouseButton::Right, x, y, _) => {
+            let grabbed = log.pointer_drag((x, y));
+            if !grabbed {
+              log.drag_brush((x, y));
+            }
+          },
+          MouseEvent::Down(MouseButton::Right, x, y, _) => {
             log.erase((x, y));
-        },
-        _ => {}
+          },
+          MouseEvent::Drag(MouseButton::Right, x, y, _) => {
+            log.drag_erase((x, y));
+          },
+          MouseEvent::Up(_, x, y, _) => {
+            log.pointer_release((x, y));
+          },
+          MouseEvent::ScrollDown(_, _, modifiers) => {
+            if modifiers.contains(KeyModifiers::SHIFT) {
+              log.gridscroll_scrollright();
+            } else {
+              log.gridscroll_scrolldown();
+            }
+          },
+          MouseEvent::ScrollUp(_, _, modifiers) => {
+            if modifiers.contains(KeyModifiers::SHIFT) {
+              log.gridscroll_scrollleft();
+            } else {
+              log.gridscroll_scrollup();
+            }
+          },
+          _ => {}
+        }
       },
       Event::Resize(_, _) => {
         if log.overflow() {
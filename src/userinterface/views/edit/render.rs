@@ -3,22 +3,24 @@ use tui::{Frame, Terminal};
 use tui::backend::Backend;
 use tui::layout::{Layout, Constraint, Direction, Rect};
 
+use crate::config::keys::{Action, Keys};
 use crate::log;
 
 use crate::userinterface::widgets::{brushselector, grid,
-  shortcuts, gauge::{self, Rgb}};
+  shortcuts, statusbar, gauge::{self, Rgb}};
 use crate::userinterface::views::{MIN_BRUSH_AREA_WIDTH,
   MIN_SHORTCUTS_AREA_WIDTH, ratio};
 
-const NB_BRUSH_SHORTCUTS: usize = 3;
-const NB_GRID_SHORTCUTS: usize = 4;
-const NB_WORKSPACE_SHORTCUTS: usize = 3;
+const NB_BRUSH_SHORTCUTS: usize = 4;
+const NB_CURSOR_SHORTCUTS: usize = 5;
+const NB_GRID_SHORTCUTS: usize = 5;
+const NB_WORKSPACE_SHORTCUTS: usize = 5;
 const NB_GENERAL_SHORTCUTS: usize = 4;
 
 const SHORTCUTS_AREAS: usize = 4;
 
 pub fn render<B: Backend>(terminal: &mut Terminal<B>, log: &mut log::Log,
-  (nb_todo_tasks, nb_max_tasks): (usize, usize)) {
+  (nb_todo_tasks, nb_max_tasks): (usize, usize), keys: &Keys) {
 
     terminal.draw(|frame| {
       let frame_area = frame.size();
@@ -42,7 +44,11 @@ pub fn render<B: Backend>(terminal: &mut Terminal<B>, log: &mut log::Log,
 
       let brush_area = Rect::new(0, 0, MIN_BRUSH_AREA_WIDTH, height);
       let workspace_area = Rect::new(brush_area.right(), 0,
-        width - MIN_BRUSH_AREA_WIDTH - MIN_SHORTCUTS_AREA_WIDTH, height);
+        width - MIN_BRUSH_AREA_WIDTH - MIN_SHORTCUTS_AREA_WIDTH,
+        height - statusbar::STATUS_BAR_HEIGHT);
+      let status_bar_area = Rect::new(workspace_area.left(),
+        workspace_area.bottom(), workspace_area.width,
+        statusbar::STATUS_BAR_HEIGHT);
       let mut shortcuts_areas = Vec::<Rect>::with_capacity(SHORTCUTS_AREAS);
       for i in 0..SHORTCUTS_AREAS {
         shortcuts_areas.push(Rect::new(workspace_area.right(), top,
@@ -56,11 +62,17 @@ pub fn render<B: Backend>(terminal: &mut Terminal<B>, log: &mut log::Log,
         gauge_tasks_area.bottom(), MIN_SHORTCUTS_AREA_WIDTH,
         gauge::GAUGE_HEIGHT);
 
+      log.pointer_setbrushes(brushselector::get_entries_area(
+        (brush_area.left(), brush_area.top())),
+        usize::from(brushselector::NB_BRUSHES));
       frame.render_widget(
         brushselector::BrushSelectorWidget::new(log), brush_area);
       frame.render_widget(grid::GridWidget::new(log), workspace_area);
+      frame.render_widget(
+        statusbar::StatusBarWidget::new(log), status_bar_area);
 
-      render_shortcuts(frame, shortcuts_areas);
+      render_shortcuts(frame, shortcuts_areas, log.cursor_isenabled(),
+        log.zoom_ishalfblock(), keys);
 
       render_tasks_gauge(
         frame, gauge_tasks_area, (nb_todo_tasks, nb_max_tasks));
@@ -70,28 +82,82 @@ pub fn render<B: Backend>(terminal: &mut Terminal<B>, log: &mut log::Log,
     }).unwrap();
 }
 
-fn render_shortcuts<B: Backend>(frame: &mut Frame<B>, areas: Vec<Rect>) {
-
-  let mut brush_shortcuts = shortcuts::ShortcutsWidget::new(
-    NB_BRUSH_SHORTCUTS, String::from(" Brush Shortcuts "));
-  brush_shortcuts.push_action("Brush | Eraser");
-  brush_shortcuts.push_action("Next Brush | Previous Brush");
-  brush_shortcuts.push_action("Set Brush Color");
-  brush_shortcuts.push_instruction("< L-Click | R-Click >");
-  brush_shortcuts.push_instruction("< q | a >");
-  brush_shortcuts.push_instruction("< c >");
+/// The mouse does not paint at the half block zoom, so the brush shortcuts
+/// point to the cursor mode
+fn render_shortcuts<B: Backend>(frame: &mut Frame<B>, areas: Vec<Rect>,
+  cursor_mode: bool, halfblock: bool, keys: &Keys) {
+
+  let mut brush_shortcuts;
+  if cursor_mode {
+    brush_shortcuts = shortcuts::ShortcutsWidget::new(
+      NB_CURSOR_SHORTCUTS, String::from(" Cursor Shortcuts "));
+    brush_shortcuts.push_action("Move Cursor");
+    brush_shortcuts.push_action("Brush | Eraser | Cycle Pixel");
+    brush_shortcuts.push_action("Next Brush | Previous Brush");
+    brush_shortcuts.push_action("Set Brush Color");
+    brush_shortcuts.push_action("Mouse Mode");
+    brush_shortcuts.push_instruction(keys.get_instruction(&[
+      Action::CursorLeft, Action::CursorDown, Action::CursorUp,
+      Action::CursorRight]));
+    brush_shortcuts.push_instruction(keys.get_instruction(&[
+      Action::CursorBrush, Action::CursorErase, Action::CursorCyclePixel]));
+    brush_shortcuts.push_instruction(
+      keys.get_instruction(&[Action::NextBrush, Action::PreviousBrush]));
+    brush_shortcuts.push_instruction(
+      keys.get_instruction(&[Action::SetBrushColor]));
+    brush_shortcuts.push_instruction(
+      keys.get_instruction(&[Action::ToggleCursor]));
+  } else {
+    brush_shortcuts = shortcuts::ShortcutsWidget::new(
+      NB_BRUSH_SHORTCUTS, String::from(" Brush Shortcuts "));
+    brush_shortcuts.push_action("Brush | Eraser");
+    brush_shortcuts.push_action("Next Brush | Previous Brush");
+    brush_shortcuts.push_action("Set Brush Color");
+    brush_shortcuts.push_action("Cursor Mode");
+    brush_shortcuts.push_instruction(if halfblock {
+      "< Zoom In | Cursor Mode >"
+    } else {
+      "< L-Click | R-Click >"
+    });
+    brush_shortcuts.push_instruction(
+      keys.get_instruction(&[Action::NextBrush, Action::PreviousBrush]));
+    brush_shortcuts.push_instruction(
+      keys.get_instruction(&[Action::SetBrushColor]));
+    brush_shortcuts.push_instruction(
+      keys.get_instruction(&[Action::ToggleCursor]));
+  }
   frame.render_widget(brush_shortcuts, areas[0]);
 
   let mut grid_shortcuts = shortcuts::ShortcutsWidget::new(
     NB_GRID_SHORTCUTS, String::from(" Grid Shortcuts "));
-  grid_shortcuts.push_action("(+|-) Width | (+|-) Height");
-  grid_shortcuts.push_action("Scroll Grid");
+  if cursor_mode {
+    grid_shortcuts.push_action("(+|-) Width | (-) Height");
+  } else {
+    grid_shortcuts.push_action("(+|-) Width | (+|-) Height");
+    grid_shortcuts.push_action("Scroll Grid");
+  }
+  grid_shortcuts.push_action("Zoom In | Zoom Out");
   grid_shortcuts.push_action("Clear Grid");
-  grid_shortcuts.push_action("Rename Grid");
-  grid_shortcuts.push_instruction("< w | W > | < h | H >");
-  grid_shortcuts.push_instruction("< ←  | ↑  | ↓  | →  >");
-  grid_shortcuts.push_instruction("< C >");
-  grid_shortcuts.push_instruction("< r >");
+  grid_shortcuts.push_action("Rename | Resize Grid");
+  if cursor_mode {
+    grid_shortcuts.push_instruction(format!("{} | {}",
+      keys.get_instruction(&[Action::IncrementWidth, Action::DecrementWidth]),
+      keys.get_instruction(&[Action::DecrementHeight])));
+  } else {
+    grid_shortcuts.push_instruction(format!("{} | {}",
+      keys.get_instruction(&[Action::IncrementWidth, Action::DecrementWidth]),
+      keys.get_instruction(&[Action::IncrementHeight,
+        Action::DecrementHeight])));
+    grid_shortcuts.push_instruction(keys.get_instruction(&[
+      Action::ScrollLeft, Action::ScrollUp, Action::ScrollDown,
+      Action::ScrollRight]));
+  }
+  grid_shortcuts.push_instruction(
+    keys.get_instruction(&[Action::ZoomIn, Action::ZoomOut]));
+  grid_shortcuts.push_instruction(
+    keys.get_instruction(&[Action::ClearGrid]));
+  grid_shortcuts.push_instruction(
+    keys.get_instruction(&[Action::RenameGrid, Action::ResizeGrid]));
   frame.render_widget(grid_shortcuts, areas[1]);
 
   let mut workspace_shortcuts = shortcuts::ShortcutsWidget::new(
@@ -99,21 +165,34 @@ fn render_shortcuts<B: Backend>(frame: &mut Frame<B>, areas: Vec<Rect>) {
   workspace_shortcuts.push_action("Add | Delete Grid");
   workspace_shortcuts.push_action("Next Grid | Previous Grid");
   workspace_shortcuts.push_action("Switch Grid Order");
-  workspace_shortcuts.push_instruction("< + | - >");
-  workspace_shortcuts.push_instruction("< n | N >");
-  workspace_shortcuts.push_instruction("< S >");
+  workspace_shortcuts.push_action("Hide Grid | Lock Grid");
+  workspace_shortcuts.push_action("Onion Skin | Composite");
+  workspace_shortcuts.push_instruction(
+    keys.get_instruction(&[Action::AddGrid, Action::DeleteGrid]));
+  workspace_shortcuts.push_instruction(
+    keys.get_instruction(&[Action::NextGrid, Action::PreviousGrid]));
+  workspace_shortcuts.push_instruction(
+    keys.get_instruction(&[Action::SwitchGridsOrder]));
+  workspace_shortcuts.push_instruction(keys.get_instruction(&[
+    Action::ToggleVisibility, Action::ToggleLock]));
+  workspace_shortcuts.push_instruction(keys.get_instruction(&[
+    Action::ToggleOnionSkin, Action::ToggleComposite]));
   frame.render_widget(workspace_shortcuts, areas[2]);
 
   let mut general_shortcuts = shortcuts::ShortcutsWidget::new(
     NB_GENERAL_SHORTCUTS, String::from(" General Shortcuts "));
-  general_shortcuts.push_action("Undo | Redo");
-  general_shortcuts.push_action("Generate Texture");
+  general_shortcuts.push_action("Undo | Redo | History");
+  general_shortcuts.push_action("Generate | Export | Tasks");
   general_shortcuts.push_action("Switch View");
   general_shortcuts.push_action("Exit");
-  general_shortcuts.push_instruction("< u | U >");
-  general_shortcuts.push_instruction("< g >");
-  general_shortcuts.push_instruction("< s >");
-  general_shortcuts.push_instruction("< Esc >");
+  general_shortcuts.push_instruction(keys.get_instruction(&[Action::Undo,
+    Action::Redo, Action::History]));
+  general_shortcuts.push_instruction(keys.get_instruction(&[
+    Action::Generate, Action::Export, Action::Tasks]));
+  general_shortcuts.push_instruction(
+    keys.get_instruction(&[Action::SwitchView]));
+  general_shortcuts.push_instruction(
+    keys.get_instruction(&[Action::Exit]));
   frame.render_widget(general_shortcuts, areas[3]);
 }
 
@@ -138,7 +217,7 @@ fn render_tasks_gauge<B: Backend>(frame: &mut Frame<B>, area: Rect,
 fn render_map_gauge<B: Backend>(frame: &mut Frame<B>, log: &log::Log,
   area: Rect) {
     let (map_length, map_capacity) = log.grids_getlengthcapacity();
-    let gauge_map_ratio = ratio(map_length, map_capacity);
+    let gauge_map_ratio = ratio(map_length, map_capacity).min(1.);
     let gauge_map_label =
       format!("Memory: {}/{}", map_length, map_capacity);
     let gauge_map;
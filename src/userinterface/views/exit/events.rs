@@ -13,9 +13,15 @@ pub fn catch_events() -> (UserEvent, UserError) {
       Event::Key(key_event) => {
         if key_event.modifiers.is_empty() {
           match key_event.code {
-            KeyCode::Char('y') => {
+            KeyCode::Char('y') | KeyCode::Char('f') => {
               return (UserEvent::Shutdown, UserError::ResizeCheck)
             },
+            KeyCode::Char('c') => {
+              return (UserEvent::CancelAndShutdown, UserError::NoneError)
+            },
+            KeyCode::Char('w') => {
+              return (UserEvent::FinishWithoutInterface, UserError::NoneError)
+            },
             KeyCode::Char('n') => {
               return (UserEvent::Continue, UserError::ResizeCheck)
             },
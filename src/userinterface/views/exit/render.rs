@@ -8,12 +8,12 @@ use tui::widgets::{Block, Borders, Paragraph};
 use crate::userinterface::widgets::{BORDERS, EMPTY_SPACES};
 
 const EXTRA_SPACES: u16 = 3;
-const LARGER_ROW: u16 = 13;
+const LARGER_ROW: u16 = 39;
 
 pub const WIN_WIDTH: u16 = LARGER_ROW + (EMPTY_SPACES + EXTRA_SPACES) * 2
   + BORDERS;
 
-const TEXT_HEIGHT: u16 = 4;
+const TEXT_HEIGHT: u16 = 6;
 
 pub const WIN_HEIGHT: u16 = TEXT_HEIGHT + EMPTY_SPACES + BORDERS;
 
@@ -52,8 +52,10 @@ fn render_text<B: Backend>(frame: &mut Frame<B>, area: &Rect) {
 
     let exit_text = Paragraph::new(Text::from("Exit ?
 
-< y > for YES
-< n > for NO")).alignment(Alignment::Center);
+< f > to finish tasks and exit
+< c > to cancel tasks and exit
+< w > to finish tasks without interface
+< n > to go back")).alignment(Alignment::Center);
 
     frame.render_widget(exit_text, *area);
 }
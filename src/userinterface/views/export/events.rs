@@ -0,0 +1,41 @@
+extern crate crossterm;
+use crossterm::event::{poll, read, Event, KeyCode};
+
+extern crate std;
+use std::time::Duration;
+
+use crate::log;
+
+use crate::events::UserEvent;
+use crate::errors::UserError;
+
+pub fn catch_events(log: &mut log::Log) -> (UserEvent, UserError) {
+  if poll(Duration::from_nanos(1)).unwrap() {
+    match read().unwrap() {
+      Event::Key(key_event) => {
+        if key_event.modifiers.is_empty() {
+          match key_event.code {
+            KeyCode::Enter => {
+              return (UserEvent::ExportGrids, UserError::ResizeCheck)
+            },
+            KeyCode::Esc => {
+              return (UserEvent::Continue, UserError::ResizeCheck)
+            },
+            KeyCode::Up => {
+              log.exportsettings_nextoption();
+            },
+            KeyCode::Down => {
+              log.exportsettings_previousoption();
+            },
+            _ => (),
+          }
+        }
+      },
+      Event::Resize(_, _) => {
+        return (UserEvent::Export, UserError::ResizeCheck)
+      },
+      _ => {},
+    }
+  }
+  (UserEvent::Export, UserError::NoneError)
+}
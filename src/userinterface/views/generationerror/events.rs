@@ -7,14 +7,18 @@ use std::time::Duration;
 use crate::events::UserEvent;
 use crate::errors::UserError;
 
-pub fn catch_events() -> (UserEvent, UserError) {
+/// <i>can_retry</i> allows to retry the lost textures of a task
+pub fn catch_events(can_retry: bool) -> (UserEvent, UserError) {
   if poll(Duration::from_nanos(1)).unwrap() {
     match read().unwrap() {
       Event::Key(key_event) => {
         if key_event.modifiers.is_empty() {
           match key_event.code {
             KeyCode::Enter => {
-              return (UserEvent::Continue, UserError::ResizeCheck)
+              return (UserEvent::DismissError, UserError::NoneError)
+            },
+            KeyCode::Char('r') if can_retry => {
+              return (UserEvent::RetryTask, UserError::NoneError)
             },
             _ => (),
           }
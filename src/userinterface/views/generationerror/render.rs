@@ -2,23 +2,31 @@ extern crate tui;
 use tui::{Frame, Terminal};
 use tui::backend::Backend;
 use tui::layout::{Alignment, Rect};
-use tui::style::{Color, Style};
+use tui::style::Style;
 use tui::text::{Span, Spans};
 use tui::widgets::{Block, Borders, Paragraph};
 
+use liriodendron::error::Error;
+
+use crate::task::tasksmanager::TaskFailure;
+
+use crate::userinterface::theme;
 use crate::userinterface::widgets::{BORDERS, EMPTY_SPACES};
 
 const EXTRA_SPACES: u16 = 3;
-const LARGER_ROW: u16 = 37;
+const LARGER_ROW: u16 = 52;
 
 pub const WIN_WIDTH: u16 = LARGER_ROW + (EMPTY_SPACES + EXTRA_SPACES) * 2
   + BORDERS;
 
-const TEXT_HEIGHT: u16 = 3;
+const TEXT_HEIGHT: u16 = 6;
 
 pub const WIN_HEIGHT: u16 = TEXT_HEIGHT + EMPTY_SPACES + BORDERS;
 
-pub fn render<B: Backend>(terminal: &mut Terminal<B>, text_error: &String) {
+/// Renders <i>error</i>, or the lost textures of <i>failure</i> when there
+/// is no error
+pub fn render<B: Backend>(terminal: &mut Terminal<B>, error: Option<&Error>,
+  failure: Option<&TaskFailure>) {
   terminal.draw(|frame| {
 
     let frame_area = frame.size();
@@ -35,33 +43,86 @@ pub fn render<B: Backend>(terminal: &mut Terminal<B>, text_error: &String) {
     let window_width = window_area.right() - window_left;
     let window_height = window_area.bottom() - window_top;
 
+    let text = match (error, failure) {
+      (Some(error), _) => error_text(error),
+      (None, Some(failure)) => failure_text(failure),
+      (None, None) => Vec::new(),
+    };
+    let text_height = text.len() as u16;
     let text_area =
-      Rect::new(window_left + 1, window_top + 1 + window_height/2 - BORDERS,
-        window_width - BORDERS, window_height - BORDERS);
+      Rect::new(window_left + 1, window_top + window_height/2 - text_height/2,
+        window_width - BORDERS, text_height);
 
     render_borders(frame, &window_area);
-    render_text(frame, &text_area, &text_error);
+    render_text(frame, &text_area, text);
 
   }).unwrap();
 }
 
 fn render_borders<B: Backend>(frame: &mut Frame<B>, area: &Rect) {
   let block = Block::default().borders(Borders::ALL)
-    .border_style(Style::default().fg(Color::Red));
+    .border_style(Style::default().fg(theme::get().get_error_color()));
   frame.render_widget(block, *area);
 }
 
-fn render_text<B: Backend>(frame: &mut Frame<B>, area: &Rect,
-  text: &str) {
+/// Keeps the first characters of <i>text</i> fitting in the window
+fn truncate(text: String) -> String {
+  let width = usize::from(LARGER_ROW);
+  if text.chars().count() > width {
+    format!("{}…", text.chars().take(width - 1).collect::<String>())
+  } else {
+    text
+  }
+}
 
-    let generate_text = vec![
-      Spans::from(Span::styled(text, Style::default().fg(Color::Red))),
-      Spans::from(Span::raw("")),
-      Spans::from(Span::raw("< Enter > to exit")),
-    ];
+fn error_text(error: &Error) -> Vec<Spans<'static>> {
+  vec![
+    Spans::from(Span::styled(truncate(format!("Error: {}", error)),
+      Style::default().fg(theme::get().get_error_color()))),
+    Spans::from(Span::raw("")),
+    Spans::from(Span::raw("< Enter > to exit")),
+  ]
+}
+
+/// Lists the lost textures of <i>failure</i>, truncated to the window
+fn failure_text(failure: &TaskFailure) -> Vec<Spans<'static>> {
+  let width = usize::from(LARGER_ROW);
+  let error = if failure.can_retry() {
+    format!("Task {} lost {} textures: {}", failure.id,
+      failure.names.len(), failure.error)
+  } else {
+    format!("Task {} output is not closed: {}", failure.id, failure.error)
+  };
+  let output = format!("Output: {}", failure.output);
+  let output = if output.chars().count() > width {
+    format!("…{}", output.chars().skip(output.chars().count() - width + 1)
+      .collect::<String>())
+  } else {
+    output
+  };
+  let names = truncate(format!("Textures: {}", failure.names.join(", ")));
+  let mut text = vec![
+    Spans::from(Span::styled(truncate(error),
+      Style::default().fg(theme::get().get_error_color()))),
+    Spans::from(Span::raw(output)),
+  ];
+  if failure.can_retry() {
+    text.push(Spans::from(Span::raw(names)));
+  }
+  text.push(Spans::from(Span::raw("")));
+  if failure.can_retry() {
+    text.push(Spans::from(Span::raw("< r > to retry them, < Enter > to exit")));
+  } else {
+    text.push(Spans::from(Span::raw("< Enter > to exit")));
+  }
+  text
+}
+
+fn render_text<B: Backend>(frame: &mut Frame<B>, area: &Rect,
+  text: Vec<Spans>) {
 
     let generate_paragraph =
-      Paragraph::new(generate_text).alignment(Alignment::Center);
+      Paragraph::new(text).alignment(Alignment::Center);
 
     frame.render_widget(generate_paragraph, *area);
 }
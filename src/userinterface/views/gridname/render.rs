@@ -2,11 +2,12 @@ extern crate tui;
 use tui::Terminal;
 use tui::backend::Backend;
 use tui::layout::Rect;
-use tui::style::{Color, Style};
+use tui::style::Style;
 use tui::text::{Span, Spans, Text};
 
 use crate::log;
 
+use crate::userinterface::theme;
 use crate::userinterface::widgets::{BORDERS, EMPTY_SPACES, querierpopup};
 
 pub const MAX_BOX_HEIGHT: u16 = BORDERS + 3;
@@ -33,12 +34,13 @@ pub fn render<B: Backend>(terminal: &mut Terminal<B>, log: &mut log::Log) {
     let window_area = Rect::new(frame_left + frame_width/2 - WIN_WIDTH/2,
       frame_top + frame_height/2 - WIN_HEIGHT/2, WIN_WIDTH, WIN_HEIGHT);
 
-    let keyboard_instructions = Text::from("Choose the name of your grid
-Max size: 16
+    let instructions = format!("Choose the name of your grid
+Max size: {}
 Authorized characters:
 < a-z >, < A-Z > and < 0-9 >
 < Enter > when name is chosen
-< Esc > to exit");
+< Esc > to exit", log.gridname_getmaxlength());
+    let keyboard_instructions = Text::from(instructions.as_str());
 
     let gridname;
     if !log.grids_erroroccured() {
@@ -47,8 +49,9 @@ Authorized characters:
       gridname = vec![
         Spans::from(vec![Span::raw(log.gridname_getvalue())]),
         Spans::from(vec![Span::raw("")]),
-        Spans::from(vec![Span::styled(format!("{}", log.grids_geterror()),
-          Style::default().fg(Color::Red))]),
+        Spans::from(vec![Span::styled(log.grids_geterror()
+          .map(|error| error.to_string()).unwrap_or_default(),
+          Style::default().fg(theme::get().get_error_color()))]),
       ];
     }
 
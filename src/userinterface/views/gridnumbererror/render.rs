@@ -2,10 +2,11 @@ extern crate tui;
 use tui::{Frame, Terminal};
 use tui::backend::Backend;
 use tui::layout::{Alignment, Rect};
-use tui::style::{Color, Style};
+use tui::style::Style;
 use tui::text::{Span, Spans};
 use tui::widgets::{Block, Borders, Paragraph};
 
+use crate::userinterface::theme;
 use crate::userinterface::widgets::{BORDERS, EMPTY_SPACES};
 
 const EXTRA_SPACES: u16 = 3;
@@ -47,7 +48,7 @@ pub fn render<B: Backend>(terminal: &mut Terminal<B>) {
 
 fn render_borders<B: Backend>(frame: &mut Frame<B>, area: &Rect) {
   let block = Block::default().borders(Borders::ALL)
-    .border_style(Style::default().fg(Color::Red));
+    .border_style(Style::default().fg(theme::get().get_error_color()));
   frame.render_widget(block, *area);
 }
 
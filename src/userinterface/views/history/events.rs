@@ -0,0 +1,42 @@
+extern crate crossterm;
+use crossterm::event::{poll, read, Event, KeyCode};
+
+extern crate std;
+use std::time::Duration;
+
+use crate::log;
+
+use crate::events::UserEvent;
+use crate::errors::UserError;
+
+pub fn catch_events(log: &mut log::Log) -> (UserEvent, UserError) {
+  if poll(Duration::from_nanos(1)).unwrap() {
+    match read().unwrap() {
+      Event::Key(key_event) => {
+        if key_event.modifiers.is_empty() {
+          match key_event.code {
+            KeyCode::Enter => {
+              log.history_goto();
+              return (UserEvent::Continue, UserError::ResizeCheck)
+            },
+            KeyCode::Esc => {
+              return (UserEvent::Continue, UserError::ResizeCheck)
+            },
+            KeyCode::Up => {
+              log.history_previouscursor();
+            },
+            KeyCode::Down => {
+              log.history_nextcursor();
+            },
+            _ => (),
+          }
+        }
+      },
+      Event::Resize(_, _) => {
+        return (UserEvent::History, UserError::ResizeCheck)
+      },
+      _ => {},
+    }
+  }
+  (UserEvent::History, UserError::NoneError)
+}
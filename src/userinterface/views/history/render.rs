@@ -0,0 +1,68 @@
+extern crate std;
+use std::cmp::min;
+
+extern crate tui;
+use tui::Terminal;
+use tui::backend::Backend;
+use tui::layout::Rect;
+use tui::text::Text;
+
+use crate::log;
+
+use crate::userinterface::widgets::{BORDERS, EMPTY_SPACES, selectorpopup};
+
+pub const BOX_HEIGHT: u16 = 11;
+pub const TEXT_HEIGHT: u16 = 6;
+
+const EXTRA_SPACES: u16 = 3;
+const LARGER_ROW: u16 = 34;
+
+pub const WIN_WIDTH: u16 = LARGER_ROW + (EXTRA_SPACES + EMPTY_SPACES) * 2 +
+  BORDERS;
+
+pub const WIN_HEIGHT: u16 = TEXT_HEIGHT + EMPTY_SPACES + BORDERS +
+  BOX_HEIGHT + 1;
+
+pub fn render<B: Backend>(terminal: &mut Terminal<B>, log: &mut log::Log) {
+  terminal.draw(|frame| {
+    let frame_area = frame.size();
+
+    let frame_left = frame_area.left();
+    let frame_top = frame_area.top();
+    let frame_width = frame_area.right() - frame_left;
+    let frame_height = frame_area.bottom() - frame_top;
+
+    let window_area = Rect::new(frame_left + frame_width/2 - WIN_WIDTH/2,
+      frame_top + frame_height/2 - WIN_HEIGHT/2, WIN_WIDTH, WIN_HEIGHT);
+
+    let keyboard_instructions = Text::from("Which state of your grids
+do you want to restore ?
+( * ) is the current state
+< ↑  | ↓  > to move,
+< Enter > to make your choice,
+< Esc > to exit");
+
+    let position = log.history_getposition();
+    let cursor = log.history_getcursor();
+    let entries = log.history_getentries();
+
+    // Only a window of BOX_HEIGHT entries around the cursor is displayed
+    let nb_rows = usize::from(BOX_HEIGHT);
+    let first_row = if entries.len() <= nb_rows {
+      0
+    } else {
+      min(cursor.saturating_sub(nb_rows / 2), entries.len() - nb_rows)
+    };
+    let entries = entries.into_iter().enumerate().skip(first_row)
+      .take(nb_rows).map(|(index, entry)| {
+        if index == position {
+          format!("( * ) {}", entry)
+        } else {
+          entry
+        }
+      }).collect::<Vec<String>>();
+
+    frame.render_widget(selectorpopup::SelectorPopupWidget::new(
+      keyboard_instructions, entries, cursor - first_row), window_area);
+  }).unwrap();
+}
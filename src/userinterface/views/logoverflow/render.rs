@@ -2,10 +2,11 @@ extern crate tui;
 use tui::{Frame, Terminal};
 use tui::backend::Backend;
 use tui::layout::{Alignment, Rect};
-use tui::style::{Color, Style};
+use tui::style::Style;
 use tui::text::{Span, Spans};
 use tui::widgets::{Block, Borders, Paragraph};
 
+use crate::userinterface::theme;
 use crate::userinterface::widgets::{BORDERS, EMPTY_SPACES};
 
 const EXTRA_SPACES: u16 = 3;
@@ -47,20 +48,20 @@ pub fn render<B: Backend>(terminal: &mut Terminal<B>) {
 
 fn render_borders<B: Backend>(frame: &mut Frame<B>, area: &Rect) {
   let block = Block::default().borders(Borders::ALL)
-    .border_style(Style::default().fg(Color::Red));
+    .border_style(Style::default().fg(theme::get().get_warning_color()));
   frame.render_widget(block, *area);
 }
 
 fn render_text<B: Backend>(frame: &mut Frame<B>, area: &Rect) {
 
   let text = vec![
-    Spans::from(Span::raw("Error: Not enough memory.")),
-    Spans::from(Span::raw("You can't brush your grids anymore.")),
-    Spans::from(Span::raw("Erase content in your grids or save your")),
-    Spans::from(Span::raw("workspace and restart Liriodendron with")),
-    Spans::from(Span::raw("better memory parameters.")),
+    Spans::from(Span::raw("Warning: High memory usage.")),
+    Spans::from(Span::raw("Your grids have more brushed cells than")),
+    Spans::from(Span::raw("the maximum filled cells parameter.")),
+    Spans::from(Span::raw("You can keep brushing, but Liriodendron")),
+    Spans::from(Span::raw("will use more memory.")),
     Spans::from(Span::raw("")),
-    Spans::from(Span::raw("< Enter > to exit")),
+    Spans::from(Span::raw("< Enter > to continue")),
   ];
 
   let paragraph = Paragraph::new(text).alignment(Alignment::Center);
@@ -1,61 +1,61 @@
 extern crate crossterm;
-use crossterm::event::{poll, read, Event, KeyCode, KeyModifiers};
+use crossterm::event::{poll, read, Event};
 
 extern crate std;
 use std::time::Duration;
 
+use crate::config::keys::{Action, Context, Keys};
 use crate::log;
 
 use crate::events::UserEvent;
 use crate::errors::UserError;
 use crate::views::View;
 
-pub fn catch_events(log: &mut log::Log) -> (UserEvent, UserError, View) {
+pub fn catch_events(log: &mut log::Log, keys: &Keys)
+  -> (UserEvent, UserError, View) {
   if poll(Duration::from_nanos(1)).unwrap() {
     match read().unwrap() {
       Event::Key(key_event) => {
-        if key_event.modifiers.is_empty() {
-          match key_event.code {
-            KeyCode::Esc => {
-              return (UserEvent::Exit, UserError::ResizeCheck,
-                View::Parameters)
-            },
-            KeyCode::Char('g') => {
-              return (UserEvent::SetGenerationNumber,
-                UserError::ResizeCheck, View::Parameters)
-            },
-            KeyCode::Char('s') => {
-              return (UserEvent::Continue,
-                UserError::ResizeCheck, View::Edit)
-            },
-            KeyCode::Char('b') => {
-              log.brush_incrbodyderatio();
-            },
-            KeyCode::Char('n') => {
-              log.brush_incrbodydrratio();
-            },
-            KeyCode::Char('f') => {
-              return (UserEvent::SetTextureFormat, UserError::ResizeCheck,
-                View::Parameters)
-            },
-            KeyCode::Char('p') => {
-              log.texturessettings_incrpixelratio();
-            },
-            _ => (),
-          }
-        } else if key_event.modifiers == KeyModifiers::SHIFT {
-          match key_event.code {
-            KeyCode::Char('B') => {
-              log.brush_decrbodyderatio();
-            },
-            KeyCode::Char('N') => {
-              log.brush_decrbodydrratio();
-            },
-            KeyCode::Char('P') => {
-              log.texturessettings_decrpixelratio();
-            },
-            _ => (),
-          }
+        match keys.get_action(Context::Parameters, key_event) {
+          Some(Action::Exit) => {
+            return (UserEvent::Exit, UserError::ResizeCheck,
+              View::Parameters)
+          },
+          Some(Action::Generate) => {
+            return (UserEvent::SetGenerationNumber,
+              UserError::ResizeCheck, View::Parameters)
+          },
+          Some(Action::Tasks) => {
+            return (UserEvent::Tasks, UserError::ResizeCheck,
+              View::Parameters)
+          },
+          Some(Action::SwitchView) => {
+            return (UserEvent::Continue,
+              UserError::ResizeCheck, View::Edit)
+          },
+          Some(Action::IncrementBodyEmptyRatio) => {
+            log.brush_incrbodyderatio();
+          },
+          Some(Action::IncrementBodyBorderRatio) => {
+            log.brush_incrbodydrratio();
+          },
+          Some(Action::SetTextureFormat) => {
+            return (UserEvent::SetTextureFormat, UserError::ResizeCheck,
+              View::Parameters)
+          },
+          Some(Action::IncrementPixelRatio) => {
+            log.texturessettings_incrpixelratio();
+          },
+          Some(Action::DecrementBodyEmptyRatio) => {
+            log.brush_decrbodyderatio();
+          },
+          Some(Action::DecrementBodyBorderRatio) => {
+            log.brush_decrbodydrratio();
+          },
+          Some(Action::DecrementPixelRatio) => {
+            log.texturessettings_decrpixelratio();
+          },
+          _ => (),
         }
       },
       Event::Resize(_, _) => {
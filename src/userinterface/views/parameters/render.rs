@@ -5,12 +5,16 @@ extern crate tui;
 use tui::{Frame, Terminal};
 use tui::backend::Backend;
 use tui::layout::{Layout, Constraint, Direction, Rect};
-use tui::style::{Color, Style};
+use tui::style::Style;
 use tui::text::{Span, Spans};
 use tui::widgets::{Block, Borders, Paragraph};
 
+use crate::config::keys::{Action, Keys};
 use crate::log;
 
+use liriodendron::utils::FullPixel;
+
+use crate::userinterface::theme;
 use crate::userinterface::views::{BORDERS, MIN_SHORTCUTS_AREA_WIDTH, ratio};
 use crate::userinterface::widgets::{shortcuts, gauge::{self, Rgb}};
 
@@ -33,7 +37,7 @@ pub const LARGER_BRUSH_PARAM_ROW: u16 = 43;
 const BRUSH_SHORTCUTS_HEIGHT: usize = NB_BRUSH_SHORTCUTS + BORDERS as usize;
 
 pub fn render<B: Backend>(terminal: &mut Terminal<B>, log: &mut log::Log,
-  (nb_todo_tasks, nb_max_tasks): (usize, usize)) {
+  (nb_todo_tasks, nb_max_tasks): (usize, usize), keys: &Keys) {
     terminal.draw(|frame| {
       let frame_area = frame.size();
 
@@ -105,7 +109,7 @@ pub fn render<B: Backend>(terminal: &mut Terminal<B>, log: &mut log::Log,
       render_texture(frame, texture_area, texture_text_area, log);
       render_pixel(frame, pixel_area);
 
-      render_shortcuts(frame, shortcuts_areas);
+      render_shortcuts(frame, shortcuts_areas, keys);
       render_tasks_gauge(
         frame, gauge_tasks_area, (nb_todo_tasks, nb_max_tasks));
   }).unwrap();
@@ -136,13 +140,15 @@ fn render_brush<B: Backend>(frame: &mut Frame<B>, log: &log::Log,
     let brush_text = Paragraph::new(vec![
       Spans::from(vec![
         Span::styled(" D|E Pixel Ratio ",
-          Style::default().bg(Color::Green)),
+          Style::default().bg(theme::get()
+            .get_pixel_color(FullPixel::BodyEmpty))),
         Span::raw(format!(" Body = {}% | Empty = {}%", 100 - body_de_ratio,
           body_de_ratio)),
       ]),
       Spans::from(vec![
         Span::styled(" D|R Pixel Ratio ",
-          Style::default().bg(Color::Blue)),
+          Style::default().bg(theme::get()
+            .get_pixel_color(FullPixel::BodyBorder))),
         Span::raw(format!(" Body = {}% | Border = {}%", 100 - body_dr_ratio,
           body_dr_ratio)),
       ]),
@@ -179,15 +185,19 @@ fn render_pixel<B:Backend>(frame: &mut Frame<B>, area: Rect) {
   frame.render_widget(pixel_block, area);
 }
 
-fn render_shortcuts<B:Backend>(frame: &mut Frame<B>, areas: Vec<Rect>) {
+fn render_shortcuts<B:Backend>(frame: &mut Frame<B>, areas: Vec<Rect>,
+  keys: &Keys) {
   let mut general_shortcuts = shortcuts::ShortcutsWidget::new(
     NB_GENERAL_SHORTCUTS, String::from(" General Shortcuts "));
-  general_shortcuts.push_action("Generate Texture");
+  general_shortcuts.push_action("Generate Texture | Tasks");
   general_shortcuts.push_action("Switch View");
   general_shortcuts.push_action("Exit");
-  general_shortcuts.push_instruction("< g >");
-  general_shortcuts.push_instruction("< s >");
-  general_shortcuts.push_instruction("< Esc >");
+  general_shortcuts.push_instruction(
+    keys.get_instruction(&[Action::Generate, Action::Tasks]));
+  general_shortcuts.push_instruction(
+    keys.get_instruction(&[Action::SwitchView]));
+  general_shortcuts.push_instruction(
+    keys.get_instruction(&[Action::Exit]));
   frame.render_widget(general_shortcuts, areas[0]);
 
   let /* mut */ grids_shortcuts = shortcuts::ShortcutsWidget::new(
@@ -198,8 +208,10 @@ fn render_shortcuts<B:Backend>(frame: &mut Frame<B>, areas: Vec<Rect>) {
     NB_TEXTURE_SHORTCUTS, String::from(" Texture Shortcuts "));
   texture_shortcuts.push_action("Set Format");
   texture_shortcuts.push_action("(+|-) Pixel Ratio");
-  texture_shortcuts.push_instruction("< f >");
-  texture_shortcuts.push_instruction("< p | P >");
+  texture_shortcuts.push_instruction(
+    keys.get_instruction(&[Action::SetTextureFormat]));
+  texture_shortcuts.push_instruction(keys.get_instruction(&[
+    Action::IncrementPixelRatio, Action::DecrementPixelRatio]));
   frame.render_widget(texture_shortcuts, areas[2]);
 
   let /* mut */ color_shortcuts = shortcuts::ShortcutsWidget::new(
@@ -214,8 +226,10 @@ fn render_shortcuts<B:Backend>(frame: &mut Frame<B>, areas: Vec<Rect>) {
     NB_BRUSH_SHORTCUTS, String::from(" Brush Shortcuts "));
   brush_shortcuts.push_action("(+|-) BodyEmpty Pixel Ratio");
   brush_shortcuts.push_action("(+|-) BodyBorder Pixel Ratio");
-  brush_shortcuts.push_instruction("< b | B >");
-  brush_shortcuts.push_instruction("< n | N >");
+  brush_shortcuts.push_instruction(keys.get_instruction(&[
+    Action::IncrementBodyEmptyRatio, Action::DecrementBodyEmptyRatio]));
+  brush_shortcuts.push_instruction(keys.get_instruction(&[
+    Action::IncrementBodyBorderRatio, Action::DecrementBodyBorderRatio]));
   frame.render_widget(brush_shortcuts, areas[5]);
 }
 
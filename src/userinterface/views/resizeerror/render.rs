@@ -2,10 +2,11 @@ extern crate tui;
 use tui::{Terminal, Frame};
 use tui::backend::Backend;
 use tui::layout::{Alignment, Rect};
-use tui::style::{Color, Style};
+use tui::style::Style;
 use tui::text::Text;
 use tui::widgets::Paragraph;
 
+use crate::userinterface::theme;
 use crate::userinterface::widgets::{BORDERS, EMPTY_SPACES};
 
 const TEXT_HEIGHT: u16 = 6;
@@ -42,7 +43,7 @@ size, this message will be erased but the
 application will still run until your
 terminal will be bigger."))
       .alignment(Alignment::Center)
-      .style(Style::default().fg(Color::Red));
+      .style(Style::default().fg(theme::get().get_error_color()));
 
     frame.render_widget(exit_text, *area);
 }
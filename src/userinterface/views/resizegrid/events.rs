@@ -0,0 +1,51 @@
+extern crate crossterm;
+use crossterm::event::{poll, read, Event, KeyCode};
+
+extern crate std;
+use std::time::Duration;
+
+use crate::log;
+
+use crate::events::UserEvent;
+use crate::errors::UserError;
+
+pub fn catch_events(log: &mut log::Log) -> (UserEvent, UserError) {
+  if poll(Duration::from_nanos(1)).unwrap() {
+    match read().unwrap() {
+      Event::Key(key_event) => {
+        if key_event.modifiers.is_empty() {
+          match key_event.code {
+            KeyCode::Enter => {
+              log.resize();
+              return (UserEvent::Continue, UserError::ResizeCheck)
+            },
+            KeyCode::Esc => {
+              return (UserEvent::Continue, UserError::ResizeCheck)
+            },
+            KeyCode::Left => {
+              log.gridsize_cursorleft();
+            },
+            KeyCode::Right => {
+              log.gridsize_cursorright();
+            },
+            KeyCode::Up => {
+              log.gridsize_previousanchor();
+            },
+            KeyCode::Down => {
+              log.gridsize_nextanchor();
+            },
+            KeyCode::Char(n) if n.is_ascii_digit() => {
+              log.gridsize_setvalue(n);
+            },
+            _ => (),
+          }
+        }
+      },
+      Event::Resize(_, _) => {
+        return (UserEvent::ResizeGrid, UserError::ResizeCheck)
+      },
+      _ => {},
+    }
+  }
+  (UserEvent::ResizeGrid, UserError::NoneError)
+}
@@ -0,0 +1,54 @@
+extern crate tui;
+use tui::Terminal;
+use tui::backend::Backend;
+use tui::layout::Rect;
+use tui::text::{Span, Spans, Text};
+
+use crate::log;
+
+use crate::userinterface::widgets::{BORDERS, EMPTY_SPACES, querierpopup};
+
+/// The size, then the side where the cells stay
+pub const SIZE_BOX_HEIGHT: u16 = BORDERS + 2;
+pub const TEXT_HEIGHT: u16 = 7;
+
+const EXTRA_SPACES: u16 = 3;
+const LARGER_ROW: u16 = 43;
+
+pub const WIN_WIDTH: u16 = LARGER_ROW + (EXTRA_SPACES + EMPTY_SPACES) * 2 +
+  BORDERS;
+
+pub const WIN_HEIGHT: u16 = TEXT_HEIGHT + EMPTY_SPACES + BORDERS +
+  SIZE_BOX_HEIGHT + 1;
+
+pub fn render<B: Backend>(terminal: &mut Terminal<B>, log: &mut log::Log) {
+  terminal.draw(|frame| {
+    let frame_area = frame.size();
+
+    let frame_left = frame_area.left();
+    let frame_top = frame_area.top();
+    let frame_width = frame_area.right() - frame_left;
+    let frame_height = frame_area.bottom() - frame_top;
+
+    let window_area = Rect::new(frame_left + frame_width/2 - WIN_WIDTH/2,
+      frame_top + frame_height/2 - WIN_HEIGHT/2, WIN_WIDTH, WIN_HEIGHT);
+
+    let instructions = format!("Size of the grids? (min: 1, max: {})
+
+< 0-9 > to modify the width and the height,
+< ←  | →  > to move,
+< ↑  | ↓  > to choose where the cells stay,
+< Enter > to resize the grids,
+< Esc > to exit.", log.grids_getsizelimit());
+    let keyboard_instructions = Text::from(instructions.as_str());
+
+    let (width, height) = log.gridsize_getvalue();
+    let size = vec![
+      Spans::from(vec![Span::raw(format!("{}×{}", width, height))]),
+      Spans::from(vec![Span::raw(log.gridsize_getanchor())]),
+    ];
+
+    frame.render_widget(querierpopup::QuerierPopupWidget::new(
+      keyboard_instructions, size, log.gridsize_getcursor()), window_area);
+  }).unwrap();
+}
@@ -5,6 +5,7 @@ use tui::layout::Rect;
 use tui::text::{Span, Spans, Text};
 
 use crate::log;
+use crate::task::generation::MAX_NUMBER_GENERATIONS;
 
 use crate::userinterface::widgets::{BORDERS, EMPTY_SPACES, querierpopup};
 
@@ -32,13 +33,13 @@ pub fn render<B: Backend>(terminal: &mut Terminal<B>, log: &mut log::Log) {
     let window_area = Rect::new(frame_left + frame_width/2 - WIN_WIDTH/2,
       frame_top + frame_height/2 - WIN_HEIGHT/2, WIN_WIDTH, WIN_HEIGHT);
 
-    let keyboard_instructions =
-      Text::from("How many textures? (min: 1, max: 500)
+    let instructions = format!("How many textures? (min: 1, max: {})
 
 < 0-9 > to modify number of generated textures,
 < ←  | →  > to move,
 < Enter > to start generation,
-< Esc > to exit.");
+< Esc > to exit.", MAX_NUMBER_GENERATIONS);
+    let keyboard_instructions = Text::from(instructions.as_str());
 
     let number = log.generationnumber_getvalue();
     let number_value = vec![Spans::from(vec![
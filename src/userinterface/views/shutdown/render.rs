@@ -24,7 +24,7 @@ pub const MAX_WIN_HEIGHT: u16 = EMPTY_SPACES + BORDERS + TEXT_HEIGHT +
   gauge::GAUGE_HEIGHT * (GAUGE_NUMBERS + 1);
 
 pub fn render<B: Backend>(terminal: &mut Terminal<B>,
-  threads: &HashMap<ThreadId, (u16, u16)>, tasks: (usize, usize)) {
+  threads: &HashMap<ThreadId, (u32, u32)>, tasks: (usize, usize)) {
     terminal.draw(|frame| {
       let frame_area = frame.size();
 
@@ -64,7 +64,7 @@ tasks before to exit").alignment(Alignment::Center);
 }
 
 fn render_gauges<B: Backend>(frame: &mut Frame<B>, area: &Rect,
-  threads: &HashMap<ThreadId, (u16, u16)>,
+  threads: &HashMap<ThreadId, (u32, u32)>,
   (tasks_done, tasks_todo): (usize, usize)) {
 
     let mut gauge_label = format!("Tasks: {}/{}", tasks_done, tasks_todo);
@@ -0,0 +1,46 @@
+extern crate crossterm;
+use crossterm::event::{poll, read, Event, KeyCode};
+
+extern crate std;
+use std::time::Duration;
+
+use crate::events::UserEvent;
+use crate::errors::UserError;
+
+pub fn catch_events(cursor: &mut usize, nb_tasks: usize)
+  -> (UserEvent, UserError) {
+    if *cursor >= nb_tasks {
+      *cursor = nb_tasks.saturating_sub(1);
+    }
+    if poll(Duration::from_nanos(1)).unwrap() {
+      match read().unwrap() {
+        Event::Key(key_event) => {
+          if key_event.modifiers.is_empty() {
+            match key_event.code {
+              KeyCode::Esc => {
+                return (UserEvent::Continue, UserError::ResizeCheck)
+              },
+              KeyCode::Char('c') if nb_tasks > 0 => {
+                return (UserEvent::CancelTask, UserError::NoneError)
+              },
+              KeyCode::Char('p') => {
+                return (UserEvent::PauseTasks, UserError::NoneError)
+              },
+              KeyCode::Up => {
+                *cursor = cursor.saturating_sub(1);
+              },
+              KeyCode::Down if *cursor + 1 < nb_tasks => {
+                *cursor += 1;
+              },
+              _ => (),
+            }
+          }
+        },
+        Event::Resize(_, _) => {
+          return (UserEvent::Tasks, UserError::ResizeCheck)
+        },
+        _ => {},
+      }
+    }
+    (UserEvent::Tasks, UserError::NoneError)
+}
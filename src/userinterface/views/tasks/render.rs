@@ -0,0 +1,96 @@
+extern crate std;
+use std::cmp::min;
+use std::time::Duration;
+
+extern crate tui;
+use tui::Terminal;
+use tui::backend::Backend;
+use tui::layout::Rect;
+use tui::text::Text;
+
+use crate::task::tasksmanager::TaskInfo;
+
+use crate::userinterface::widgets::{BORDERS, EMPTY_SPACES, selectorpopup};
+
+pub const BOX_HEIGHT: u16 = 7;
+pub const TEXT_HEIGHT: u16 = 7;
+
+const EXTRA_SPACES: u16 = 3;
+const LARGER_ROW: u16 = 60;
+
+pub const WIN_WIDTH: u16 = LARGER_ROW + (EXTRA_SPACES + EMPTY_SPACES) * 2 +
+  BORDERS;
+
+pub const WIN_HEIGHT: u16 = TEXT_HEIGHT + EMPTY_SPACES + BORDERS +
+  BOX_HEIGHT + 1;
+
+pub fn render<B: Backend>(terminal: &mut Terminal<B>, tasks: &[TaskInfo],
+  cursor: usize, is_paused: bool) {
+    terminal.draw(|frame| {
+      let frame_area = frame.size();
+
+      let frame_left = frame_area.left();
+      let frame_top = frame_area.top();
+      let frame_width = frame_area.right() - frame_left;
+      let frame_height = frame_area.bottom() - frame_top;
+
+      let window_area = Rect::new(frame_left + frame_width/2 - WIN_WIDTH/2,
+        frame_top + frame_height/2 - WIN_HEIGHT/2, WIN_WIDTH, WIN_HEIGHT);
+
+      let output = tasks.get(cursor).map(|task| task.output.clone())
+        .unwrap_or_default();
+      let output_width = usize::from(LARGER_ROW) - "Output: ".len();
+      let output = if output.chars().count() > output_width {
+        format!("…{}", output.chars()
+          .skip(output.chars().count() - output_width + 1)
+          .collect::<String>())
+      } else {
+        output
+      };
+      let instructions = format!("Queue: {}
+Output: {}
+
+< ↑  | ↓  > to move,
+< c > to cancel the task,
+< p > to pause or resume the queue,
+< Esc > to quit", if is_paused { "PAUSED" } else { "RUNNING" }, output);
+      let keyboard_instructions = Text::from(instructions.as_str());
+
+      // Only a window of BOX_HEIGHT tasks around the cursor is displayed
+      let nb_rows = usize::from(BOX_HEIGHT);
+      let first_row = if tasks.len() <= nb_rows {
+        0
+      } else {
+        min(cursor.saturating_sub(nb_rows / 2), tasks.len() - nb_rows)
+      };
+      let mut rows = tasks.iter().skip(first_row).take(nb_rows)
+        .map(|task| format!("#{:<3} {:<9} {:>11} {:>8} ETA {:>8}", task.id,
+          format!("{:?}", task.state).to_uppercase(),
+          format!("{}/{}", task.done_subtasks, task.nb_subtasks),
+          format_duration(task.elapsed), format_duration(task.eta)))
+        .collect::<Vec<String>>();
+      if rows.is_empty() {
+        rows.push(String::from("NO TASK"));
+      }
+
+      frame.render_widget(selectorpopup::SelectorPopupWidget::new(
+        keyboard_instructions, rows, cursor - first_row), window_area);
+    }).unwrap();
+}
+
+/// Formats <i>duration</i> as hours, minutes and seconds
+fn format_duration(duration: Option<Duration>) -> String {
+  match duration {
+    Some(duration) => {
+      let seconds = duration.as_secs();
+      if seconds >= 3600 {
+        format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60,
+          seconds % 60)
+      } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+      }
+    },
+    None => String::from("-"),
+  }
+}
+
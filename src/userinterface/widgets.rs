@@ -6,7 +6,7 @@ pub mod querierpopup;
 pub mod scroller;
 pub mod selectorpopup;
 pub mod shortcuts;
+pub mod statusbar;
 
 pub const BORDERS: u16 = 2;
 pub const EMPTY_SPACES: u16 = 2;
-pub const RGB_SUM_DIVIDED_BY_2: u16 = 255 * 3 / 2;
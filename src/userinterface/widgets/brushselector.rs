@@ -4,11 +4,11 @@ use tui::layout::Rect;
 use tui::style::{Color, Style};
 use tui::widgets::{Block, Borders, Widget};
 
-use crate::userinterface::widgets::{BORDERS, EMPTY_SPACES,
-  RGB_SUM_DIVIDED_BY_2};
+use crate::userinterface::theme;
+use crate::userinterface::widgets::{BORDERS, EMPTY_SPACES};
 
 use crate::log;
-use crate::utils::FullPixel;
+use liriodendron::utils::FullPixel;
 
 const RGB_LABEL_HEIGHT: u16 = 3;
 pub const LABEL_WIDTH: u16 = 3;
@@ -19,6 +19,13 @@ pub const SELECTOR_WIDTH: u16 = BORDERS + EMPTY_SPACES + LABEL_WIDTH;
 pub const RGB_BOX_WIDTH: u16 = BORDERS + EMPTY_SPACES + LABEL_WIDTH;
 pub const RGB_BOX_HEIGHT: u16 = BORDERS + RGB_LABEL_HEIGHT;
 
+/// Returns the area <i>(left, right, top, bottom)</i> of the brush entries
+/// of a brush selector drawn from <i>(left, top)</i>
+pub fn get_entries_area((left, top): (u16, u16)) -> (u16, u16, u16, u16) {
+  (left + 1, left + 1 + SELECTOR_WIDTH, top + 1,
+    top + 1 + SELECTOR_HEIGHT * NB_BRUSHES)
+}
+
 /// Widget to render a brush selector section. The boxed area is the current
 /// brush action. The boxed numbers are the RGB values of the Specific Color
 /// brush action.
@@ -35,28 +42,25 @@ impl<'a> BrushSelectorWidget<'a> {
 
   fn render_brush(&self, buf: &mut Buffer, (left, top): &(u16, u16)) {
 
-    let (red, green, blue) = self.log.brush_getcolor::<u16>();
-    let fg_color = if red + green + blue > RGB_SUM_DIVIDED_BY_2 {
-      Color::Black
-    } else {
-      Color::White
-    };
+    let theme = theme::get();
     let (red, green, blue) = self.log.brush_getcolor::<u8>();
 
     buf.set_string(left + BORDERS + 1, top + TITLE_HEIGHT + 1, "D|E",
-      Style::default().bg(Color::Green));
+      Style::default().bg(theme.get_pixel_color(FullPixel::BodyEmpty)));
     buf.set_string(left + BORDERS + 1,
       top + TITLE_HEIGHT + 1 + SELECTOR_HEIGHT, "D|R",
-      Style::default().bg(Color::Blue));
+      Style::default().bg(theme.get_pixel_color(FullPixel::BodyBorder)));
     buf.set_string(left + BORDERS + 1,
       top + TITLE_HEIGHT + 1 + SELECTOR_HEIGHT * 2, " R ",
-      Style::default().bg(Color::Red));
+      Style::default().bg(theme.get_pixel_color(FullPixel::Border)));
     buf.set_string(left + BORDERS + 1,
       top + TITLE_HEIGHT + 1 + SELECTOR_HEIGHT * 3, " D ",
-      Style::default().bg(Color::Yellow));
+      Style::default().bg(theme.get_pixel_color(FullPixel::Body)));
     buf.set_string(left + BORDERS + 1,
       top + TITLE_HEIGHT + 1 + SELECTOR_HEIGHT * 4, " C ",
-      Style::default().bg(Color::Rgb(red, green, blue)).fg(fg_color));
+      Style::default().bg(theme.get_pixel_color(
+        FullPixel::SpecificColor(red, green, blue)))
+        .fg(theme.get_text_color((red, green, blue))));
   }
 
   fn render_color(&self, buf: &mut Buffer, (left, top): &(u16, u16)) {
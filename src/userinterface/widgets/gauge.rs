@@ -5,6 +5,8 @@ use tui::style::{Color, Style};
 use tui::text::Span;
 use tui::widgets::{Block, Borders, Gauge, Widget};
 
+use crate::userinterface::theme;
+
 pub const GAUGE_HEIGHT: u16 = 3;
 
 /// Color C/C++ style enum
@@ -46,16 +48,18 @@ impl Widget for GaugeWidget {
   fn render(self, area: Rect, buf: &mut Buffer) {
     let gauge_area = Rect::new(area.left(), area.top(),
       area.right() - area.left(), GAUGE_HEIGHT);
-    let gauge_color = Color::Rgb(
+    let theme = theme::get();
+    let gauge_color = theme.get_color(Color::Rgb(
       (self.init_color.0 * (1. - self.color_ratio) +
         self.ongoing_color.0 * self.color_ratio) as u8,
       (self.init_color.1 * (1. - self.color_ratio) +
         self.ongoing_color.1 * self.color_ratio) as u8,
       (self.init_color.2 * (1. - self.color_ratio) +
-        self.ongoing_color.2 * self.color_ratio) as u8);
+        self.ongoing_color.2 * self.color_ratio) as u8));
     let gauge = Gauge::default()
       .block(Block::default().borders(Borders::ALL))
-      .gauge_style(Style::default().fg(gauge_color).bg(Color::Black))
+      .gauge_style(Style::default().fg(gauge_color)
+        .bg(theme.get_background_color()))
       .ratio(self.filled_ratio)
       .label(Span::styled(self.label, Style::default()));
     gauge.render(gauge_area, buf);
@@ -1,23 +1,38 @@
 extern crate std;
 use std::cmp::{min, max};
+use std::collections::HashMap;
 use std::convert::TryFrom;
 
 extern crate tui;
 use tui::buffer::Buffer;
 use tui::layout::{Direction, Rect};
-use tui::style::{Color, Modifier, Style};
+use tui::style::{Modifier, Style};
 use tui::text::{Span, Spans};
 use tui::widgets::{Block, Borders, Paragraph, Widget};
 
-use crate::userinterface::widgets::{BORDERS, RGB_SUM_DIVIDED_BY_2};
-use crate::userinterface::widgets::scroller::{self, SCROLLER};
+use crate::userinterface::theme;
+use crate::userinterface::widgets::BORDERS;
+use crate::userinterface::widgets::scroller::{self, ARROWS, SCROLLER};
 
-use crate::log;
-use crate::utils::FullPixel;
+use crate::log::{self, Axis, Scroller};
+use liriodendron::utils::FullPixel;
 
 pub const TAB_WIDTH: u16 = 17;
 
-const LIGHT_GREY: Color = Color::Rgb(200, 200, 200);
+/// Width of the visibility and lock marks before a grid name
+const MARKS_WIDTH: usize = 2;
+
+/// Pads a grid name with spaces to fill its tab or truncates it when the name
+/// is wider than the tab
+fn tab_label(name: &str, width: usize) -> String {
+  if name.chars().count() > width {
+    let mut label = name.chars().take(width - 1).collect::<String>();
+    label.push('…');
+    label
+  } else {
+    format!("{:<width$}", name, width = width)
+  }
+}
 
 /// Widget to render a colorizable grid
 pub struct GridWidget<'a> {
@@ -31,6 +46,23 @@ impl<'a> GridWidget<'a> {
     }
   }
 
+  /// Returns the cells shown in the grid area, with <i>true</i> for a cell
+  /// of another grid dimmed by the onion skin
+  fn get_cells(&self) -> HashMap<(u16, u16), (FullPixel, bool)> {
+    let mut cells = HashMap::new();
+    let composite = self.log.overlay_iscomposite();
+    if composite || self.log.overlay_isonionskin() {
+      cells.extend(self.log.grids_getvisiblegrids::<u16>().into_iter()
+        .map(|(position, (_, pixel))| (position, (pixel, !composite))));
+    }
+    let current_grid = self.log.grids_getcurrentgridid();
+    if !composite && !self.log.grids_gethidden()[current_grid] {
+      cells.extend(self.log.grids_getcurrentgrid().into_iter()
+        .map(|(pixel, x, y)| ((x, y), (pixel, false))));
+    }
+    cells
+  }
+
   fn render_inner(&mut self, buf: &mut Buffer,
     (left, top, width, height): &(u16, u16, u16, u16)) {
 
@@ -40,47 +72,69 @@ impl<'a> GridWidget<'a> {
       let (inner_left, inner_right, inner_top, inner_bottom) =
         (inner.left(), inner.right(), inner.top(), inner.bottom());
 
-      buf.set_style(inner, Style::default().bg(Color::Black));
+      let theme = theme::get();
+      let background = theme.get_background_color();
+      buf.set_style(inner, Style::default().bg(background));
 
       let (scroll_x, scroll_y) = self.log.gridscroll_getscroll();
       self.log.check_last_action(
-        (inner_left, inner_right + scroll_x,
-          inner_top, inner_bottom + scroll_y));
-      let current_grid = self.log.grids_getcurrentgrid();
-
-      let mut row;
-      let mut col;
-      for (pixel, x, y) in current_grid {
-        if (x + inner_left < scroll_x) ||
-          (x + inner_left - scroll_x >= inner_right) {
-            row = *left;
-        } else {
-          row = x + inner_left - scroll_x;
-        }
-        if (y + inner_top < scroll_y) ||
-          (y + inner_top - scroll_y >= inner_bottom) {
-          col = *top;
-        } else {
-          col = y + inner_top - scroll_y;
+        (inner_left, inner_right, inner_top, inner_bottom));
+      self.log.pointer_setarea(
+        (inner_left, inner_right, inner_top, inner_bottom));
+      let cells = self.get_cells();
+      let ((columns, _), (rows, row_cells)) = self.log.zoom_getscale();
+      let halfblock = self.log.zoom_ishalfblock();
+
+      // Each character shows the cell under it, or the two cells under it
+      // with a half block when zoomed out
+      for row in inner_top..inner_bottom {
+        for column in inner_left..inner_right {
+          let (x, y) = ((column - inner_left) / columns + scroll_x,
+            (row - inner_top) / rows * row_cells + scroll_y);
+          let color = |cell: Option<&(FullPixel, bool)>| cell
+            .map_or(background, |&(pixel, dimmed)| if dimmed {
+              theme.get_dimmed_color(pixel)
+            } else {
+              theme.get_pixel_color(pixel)
+            });
+          if halfblock {
+            let (upper, lower) = (cells.get(&(x, y)), cells.get(&(x, y + 1)));
+            if upper.is_some() || lower.is_some() {
+              buf.get_mut(column, row).set_symbol("▀").set_fg(color(upper))
+                .set_bg(color(lower));
+            }
+          } else if let Some(&(pixel, dimmed)) = cells.get(&(x, y)) {
+            let cell =
+              buf.get_mut(column, row).set_bg(color(Some(&(pixel, dimmed))));
+            let first_character = ((column - inner_left) % columns == 0) &&
+              ((row - inner_top) % rows == 0);
+            if let FullPixel::SpecificColor(red, green, blue) = pixel {
+              if first_character && !dimmed {
+                cell.set_symbol("C")
+                  .set_fg(theme.get_text_color((red, green, blue)));
+              }
+            }
+          }
         }
-        if (row > *left) && (col > *top) {
-          match pixel {
-            FullPixel::Body => buf.get_mut(row, col).set_bg(Color::Yellow),
-            FullPixel::Border => buf.get_mut(row, col).set_bg(Color::Red),
-            FullPixel::BodyBorder => buf.get_mut(row, col).set_bg(Color::Blue),
-            FullPixel::BodyEmpty => buf.get_mut(row, col).set_bg(Color::Green),
-            FullPixel::SpecificColor(red, green, blue) => {
-              let rgb_sum =
-                [red, green, blue].iter().map(|&x| x as u16).sum::<u16>();
-              let fg_color = if rgb_sum > RGB_SUM_DIVIDED_BY_2 {
-                  Color::Black
-                } else {
-                  Color::White
-              };
-              buf.get_mut(row, col).set_symbol("C")
-                .set_bg(Color::Rgb(red, green, blue)).set_fg(fg_color)
-            },
-          };
+      }
+
+      if self.log.cursor_isenabled() {
+        let (x, y) = self.log.cursor_getposition();
+        if (x >= scroll_x) && (y >= scroll_y) {
+          let (column, row) = (inner_left + (x - scroll_x) * columns,
+            inner_top + (y - scroll_y) / row_cells * rows);
+          let cursor = Rect::new(column, row, columns, rows)
+            .intersection(inner);
+          for column in cursor.left()..cursor.right() {
+            for row in cursor.top()..cursor.bottom() {
+              let cell = buf.get_mut(column, row);
+              if cell.symbol == " " {
+                cell.set_symbol("+");
+              }
+              cell.set_style(Style::default()
+                .add_modifier(Modifier::REVERSED | Modifier::BOLD));
+            }
+          }
         }
       }
   }
@@ -94,49 +148,88 @@ impl<'a> GridWidget<'a> {
         (self.log.grids_getwidth(), self.log.grids_getheight());
       let (inner_width, inner_height) =
         (borders_width - BORDERS, borders_height - BORDERS);
+      let (visible_width, visible_height) =
+        self.log.zoom_tocells((inner_width, inner_height));
 
-      if grid_width > inner_width {
+      let mut horizontal = None;
+      if grid_width > visible_width {
         let hozizontal_scroller = scroller::ScrollerWidget::new(
           Direction::Horizontal, scroll_x, grid_width);
         let hscroller_area = Rect::new(
           *borders_left, borders_top + borders_height, inner_width, SCROLLER);
+        horizontal = Some(Scroller { line: hscroller_area.top(),
+          thumb: hozizontal_scroller.get_thumb(hscroller_area),
+          cells: grid_width, track: inner_width });
         hozizontal_scroller.render(hscroller_area, buf);
       }
+      self.log.pointer_setscroller(Axis::Horizontal, horizontal);
 
-      if grid_height > inner_height {
+      let mut vertical = None;
+      if grid_height > visible_height {
         let vertical_scroller = scroller::ScrollerWidget::new(
           Direction::Vertical, scroll_y, grid_height);
         let vscroller_area =
           Rect::new(borders_left - 1, *borders_top, SCROLLER, inner_height);
+        vertical = Some(Scroller { line: vscroller_area.left(),
+          thumb: vertical_scroller.get_thumb(vscroller_area),
+          cells: grid_height, track: inner_height });
         vertical_scroller.render(vscroller_area, buf);
       }
+      self.log.pointer_setscroller(Axis::Vertical, vertical);
   }
 
   fn render_tabs(&mut self, buf: &mut Buffer,
     (left, top, width, height): (u16, u16, u16, u16)) {
 
       let inside_workspace = Block::default()
-        .style(Style::default().bg(LIGHT_GREY));
+        .style(Style::default().bg(theme::get().get_workspace_color()));
       let inside_workspace_area = Rect::new(left + 1, top + 1,
         width - TAB_WIDTH + 1, height);
       inside_workspace.render(inside_workspace_area, buf);
 
-      let (names, current_grid) =
-        (self.log.grids_getnames(), self.log.grids_getcurrentgridid());
-      let tabs_height = u16::try_from(names.len()).unwrap();
+      let nb_grids = self.log.grids_getnb();
+      let tabs_height = min(u16::try_from(nb_grids).unwrap(), height);
+      self.log.grids_checktabsscroll(usize::from(tabs_height));
+
+      let (names, current_grid, tabs_scroll) = (self.log.grids_getnames(),
+        self.log.grids_getcurrentgridid(), self.log.grids_gettabsscroll());
+      let (hidden, locked) =
+        (self.log.grids_gethidden(), self.log.grids_getlocked());
+      let is_scrollable = usize::from(tabs_height) < nb_grids;
+      let labels_width = if is_scrollable {
+        TAB_WIDTH - 1 - SCROLLER
+      } else {
+        TAB_WIDTH - 1
+      };
       let tabs_area = Rect::new(left + width + BORDERS - TAB_WIDTH, top + 1,
-        TAB_WIDTH - 1, tabs_height);
-      let names = names.iter().enumerate().map(|(index, key)| {
-        if index == current_grid {
-          Spans::from(vec![Span::styled(key,
-            Style::default().fg(LIGHT_GREY)
-              .add_modifier(Modifier::REVERSED))])
-        } else {
-          Spans::from(vec![Span::styled(key,
-            Style::default().add_modifier(Modifier::UNDERLINED))])
-        }
+        labels_width, tabs_height);
+      let names = names.iter().enumerate().skip(tabs_scroll)
+        .take(usize::from(tabs_height)).map(|(index, key)| {
+          let label = format!("{}{}{}",
+            if hidden[index] { '○' } else { '●' },
+            if locked[index] { '▣' } else { ' ' },
+            tab_label(key, usize::from(labels_width) - MARKS_WIDTH));
+          if index == current_grid {
+            Spans::from(vec![Span::styled(label,
+              Style::default().fg(theme::get().get_workspace_color())
+                .add_modifier(Modifier::REVERSED))])
+          } else {
+            Spans::from(vec![Span::styled(label,
+              Style::default().add_modifier(Modifier::UNDERLINED))])
+          }
       }).collect::<Vec<Spans>>();
       Paragraph::new(names).render(tabs_area, buf);
+      self.log.pointer_settabs((tabs_area.left(), tabs_area.right(),
+        tabs_area.top(), tabs_area.bottom()), tabs_scroll);
+
+      if is_scrollable && (tabs_height > ARROWS) {
+        let vertical_scroller = scroller::ScrollerWidget::new(
+          Direction::Vertical, u16::try_from(tabs_scroll).unwrap(),
+          u16::try_from(nb_grids).unwrap());
+        let vscroller_area = Rect::new(tabs_area.right(), tabs_area.top(),
+          SCROLLER, tabs_height - ARROWS);
+        vertical_scroller.render(vscroller_area, buf);
+      }
     }
 }
 
@@ -144,7 +237,14 @@ impl<'a> Widget for GridWidget<'a> {
 
   fn render(mut self, area: Rect, buf: &mut Buffer) {
 
-    let workspace_borders = Block::default().title(" Grids ")
+    let title = if self.log.overlay_iscomposite() {
+      " Grids: Composite "
+    } else if self.log.overlay_isonionskin() {
+      " Grids: Onion Skin "
+    } else {
+      " Grids "
+    };
+    let workspace_borders = Block::default().title(title)
       .borders(Borders::ALL);
 
     workspace_borders.render(area, buf);
@@ -157,7 +257,7 @@ impl<'a> Widget for GridWidget<'a> {
     let workspace_height = workspace_bottom - workspace_top - BORDERS;
 
     let (grid_width, grid_height) =
-      (self.log.grids_getwidth::<u16>(), self.log.grids_getheight::<u16>());
+      self.log.zoom_tocharacters(self.log.pointer_getgridsize());
 
     let left_borders;
     if (buf.area.right() - buf.area.left()) % 2 == 0 {
@@ -200,16 +300,19 @@ impl<'a> Widget for GridWidget<'a> {
       }
     }
 
-    let max_width =
-      workspace_right - workspace_left - BORDERS - SCROLLER - TAB_WIDTH;
-    let max_height = workspace_bottom - workspace_top - BORDERS - SCROLLER;
-    let width = min(self.log.grids_getwidth::<u16>() + BORDERS, max_width);
-    let height =
-      min(self.log.grids_getheight::<u16>() + BORDERS, max_height);
+    // The grid area only shows whole cells, except the lower half of the
+    // last row of an odd grid drawn with half blocks
+    let max_cells = self.log.zoom_tocells((
+      workspace_right - workspace_left - BORDERS * 2 - SCROLLER - TAB_WIDTH,
+      workspace_bottom - workspace_top - BORDERS * 2 - SCROLLER));
+    let (max_width, max_height) = self.log.zoom_tocharacters(max_cells);
+    let width = min(grid_width, max_width) + BORDERS;
+    let height = min(grid_height, max_height) + BORDERS;
     let borders_area = (left_borders, top_borders, width, height);
 
-    self.log.gridscroll_checkscroll(
-      &(max_width - BORDERS, max_height - BORDERS));
+    self.log.gridscroll_checkscroll(&max_cells);
+    self.log.pointer_setcorner(
+      (left_borders + width - 1, top_borders + height - 1));
 
     self.render_tabs(buf, (workspace_left, workspace_top, workspace_width,
       workspace_height));
@@ -4,13 +4,12 @@ use tui::layout::{Direction, Rect};
 use tui::style::{Color, Modifier, Style};
 use tui::widgets::Widget;
 
+use crate::userinterface::theme;
 use crate::userinterface::widgets::BORDERS;
 
 pub const SCROLLER: u16 = 1;
 pub const ARROWS: u16 = 2;
 
-const GREY: Color = Color::Rgb(150, 150, 150);
-
 /// Widget to render a vertical of horizotal scroller
 pub struct ScrollerWidget {
   direction: tui::layout::Direction,
@@ -34,36 +33,52 @@ impl ScrollerWidget {
       .round() as u16
   }
 
+  /// Returns the first character of the thumb along the scroller drawn in
+  /// <i>scroller</i>, and the one after it
+  pub fn get_thumb(&self, scroller: Rect) -> (u16, u16) {
+    let (start, size) = match self.direction {
+      Direction::Vertical => (scroller.top(), scroller.height),
+      Direction::Horizontal => (scroller.left(), scroller.width),
+    };
+    let first_size = self.compute_size(self.scroll, size);
+    let second_size = self.compute_size(size, size);
+    (start + 1 + first_size, start + 1 + first_size + second_size)
+  }
+
   fn render_vertical_scroller(&self, scroller: Rect, buf: &mut Buffer) {
+    let (scroller_color, background_color) = (theme::get().get_scroller_color(),
+      theme::get().get_background_color());
     let (scroller_left, scroller_top, scroller_bottom) =
       (scroller.left(), scroller.top(), scroller.bottom());
     let scroller_height = scroller_bottom - scroller_top;
     let borders_scroller_bottom = scroller_bottom + BORDERS;
 
     buf.get_mut(scroller_left, scroller_top).set_symbol("▴")
-      .set_style(Style::default().bg(Color::Reset).fg(GREY));
+      .set_style(Style::default().bg(Color::Reset).fg(scroller_color));
     buf.get_mut(scroller_left, borders_scroller_bottom - 1).set_symbol("▾")
-      .set_style(Style::default().bg(Color::Reset).fg(GREY));
+      .set_style(Style::default().bg(Color::Reset).fg(scroller_color));
 
     let first_height = self.compute_size(self.scroll, scroller_height);
     let second_height = self.compute_size(scroller_height, scroller_height);
 
     let first_area =
       Rect::new(scroller_left, scroller_top + 1, SCROLLER, first_height);
-    buf.set_style(first_area, Style::default().bg(Color::Black));
+    buf.set_style(first_area, Style::default().bg(background_color));
 
     let second_area =
       Rect::new(scroller_left, scroller_top + 1 + first_height,
         SCROLLER, second_height);
-    buf.set_style(second_area, Style::default().bg(GREY));
+    buf.set_style(second_area, Style::default().bg(scroller_color));
 
     let third_area =
       Rect::new(scroller_left, scroller_top + 1 + first_height + second_height,
         SCROLLER, scroller_height - first_height - second_height);
-    buf.set_style(third_area, Style::default().bg(Color::Black));
+    buf.set_style(third_area, Style::default().bg(background_color));
   }
 
   fn render_horizontal_scroller(&self, scroller: Rect, buf: &mut Buffer) {
+    let (scroller_color, background_color) = (theme::get().get_scroller_color(),
+      theme::get().get_background_color());
     let (scroller_left, scroller_right, scroller_top) =
       (scroller.left(), scroller.right(), scroller.top());
     let scroller_width = scroller_right - scroller_left;
@@ -71,30 +86,30 @@ impl ScrollerWidget {
 
     buf.get_mut(scroller_left - 1, scroller_top).set_symbol("◂")
       .set_style(Style::default()
-        .add_modifier(Modifier::REVERSED).bg(Color::Reset).fg(GREY));
+        .add_modifier(Modifier::REVERSED).bg(Color::Reset).fg(scroller_color));
     buf.get_mut(scroller_left, scroller_top).set_symbol("▌")
-      .set_style(Style::default().fg(GREY));
+      .set_style(Style::default().fg(scroller_color));
     buf.get_mut(borders_scroller_right, scroller_top).set_symbol("▸")
       .set_style(Style::default()
-        .add_modifier(Modifier::REVERSED).bg(Color::Reset).fg(GREY));
+        .add_modifier(Modifier::REVERSED).bg(Color::Reset).fg(scroller_color));
     buf.get_mut(borders_scroller_right - 1, scroller_top).set_symbol("▐")
-      .set_style(Style::default().fg(GREY));
+      .set_style(Style::default().fg(scroller_color));
 
     let first_width = self.compute_size(self.scroll, scroller_width);
     let second_width = self.compute_size(scroller_width, scroller_width);
 
     let first_area =
       Rect::new(scroller_left + 1, scroller_top, first_width, SCROLLER);
-    buf.set_style(first_area, Style::default().bg(Color::Black));
+    buf.set_style(first_area, Style::default().bg(background_color));
 
     let second_area = Rect::new(scroller_left + 1 + first_width, scroller_top,
       second_width, SCROLLER);
-    buf.set_style(second_area, Style::default().bg(GREY));
+    buf.set_style(second_area, Style::default().bg(scroller_color));
 
     let third_area =
       Rect::new(scroller_left + 1 + first_width + second_width, scroller_top,
         scroller_width - first_width - second_width, SCROLLER);
-    buf.set_style(third_area, Style::default().bg(Color::Black));
+    buf.set_style(third_area, Style::default().bg(background_color));
   }
 }
 
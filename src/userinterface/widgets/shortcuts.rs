@@ -1,14 +1,16 @@
 extern crate std;
+use std::borrow::Cow;
 use std::convert::TryFrom;
 use std::cmp::min;
 
 extern crate tui;
 use tui::buffer::Buffer;
 use tui::layout::{Alignment, Direction, Rect};
-use tui::style::{Color, Style};
+use tui::style::Style;
 use tui::text::{Span, Spans};
 use tui::widgets::{Block, Borders, Paragraph, Widget};
 
+use crate::userinterface::theme;
 use crate::userinterface::widgets::BORDERS;
 use crate::userinterface::widgets::scroller::{self, ARROWS, SCROLLER};
 
@@ -47,19 +49,25 @@ impl<'a> ShortcutsWidget<'a> {
           span.push(' ');
         }
       }
-      let style = Style::default().bg(Color::Black);
+      let style = Style::default()
+        .bg(theme::get().get_background_color());
       self.actions.push(Spans::<'a>::from(vec![Span::styled(span, style)]));
     }
   }
 
-  pub fn push_instruction(&mut self, line: &'a str) {
+  /// Instructions are built from the user's key bindings, so they can be
+  /// owned and wider than the area
+  pub fn push_instruction<S>(&mut self, line: S)
+    where S: Into<Cow<'a, str>>,
+  {
+    let line = line.into();
     if self.keyboard_instructions.len() % 2 == 0 {
       self.keyboard_instructions.push(Spans::<'a>::from(
         vec![Span::raw(line)]));
     } else {
-      let count = KEYBOARD_AREA_WIDTH -
-        u16::try_from(line.chars().collect::<Vec<char>>().len()).unwrap();
-      let mut span = String::from(line);
+      let count = KEYBOARD_AREA_WIDTH.saturating_sub(
+        u16::try_from(line.chars().count()).unwrap_or(u16::MAX));
+      let mut span = line.into_owned();
       for i in 0..=count {
         if i < count / 2 {
           span.insert(0, ' ');
@@ -67,7 +75,8 @@ impl<'a> ShortcutsWidget<'a> {
           span.push(' ');
         }
       }
-      let style = Style::default().bg(Color::Black);
+      let style = Style::default()
+        .bg(theme::get().get_background_color());
       self.keyboard_instructions.push(
         Spans::<'a>::from(vec![Span::styled(span, style)]));
     }
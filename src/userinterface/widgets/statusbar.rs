@@ -0,0 +1,76 @@
+extern crate tui;
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::style::Style;
+use tui::widgets::Widget;
+
+use crate::userinterface::theme;
+
+use crate::log;
+use liriodendron::utils::FullPixel;
+
+pub const STATUS_BAR_HEIGHT: u16 = 1;
+
+const SEPARATOR: &str = " │ ";
+
+/// Widget to render a status line: the cell of the last click, or under the
+/// cell cursor in cursor mode, and its content, the filled cells of the
+/// grids, the number of actions to undo and the unsaved changes
+pub struct StatusBarWidget<'a> {
+  log: &'a log::Log,
+}
+
+impl<'a> StatusBarWidget<'a> {
+  pub fn new(log: &'a log::Log) -> StatusBarWidget<'a> {
+    StatusBarWidget {
+      log,
+    }
+  }
+
+  fn get_cell_label(&self) -> String {
+    let (label, cell) = if self.log.cursor_isenabled() {
+      ("Cursor", Some(self.log.cursor_getposition()))
+    } else {
+      ("Last click", self.log.pointer_getcell())
+    };
+    match cell {
+      Some((x, y)) => {
+        let pixel = match self.log.grids_getcell((x, y)) {
+          Some(FullPixel::Body) => String::from("Body"),
+          Some(FullPixel::Border) => String::from("Border"),
+          Some(FullPixel::BodyBorder) => String::from("Body | Border"),
+          Some(FullPixel::BodyEmpty) => String::from("Body | Empty"),
+          Some(FullPixel::SpecificColor(red, green, blue)) =>
+            format!("Color #{:02x}{:02x}{:02x}", red, green, blue),
+          None => String::from("Empty"),
+        };
+        format!("{}: ({}, {}) {}", label, x, y, pixel)
+      },
+      None => format!("{}: -", label),
+    }
+  }
+}
+
+impl<'a> Widget for StatusBarWidget<'a> {
+
+  fn render(self, area: Rect, buf: &mut Buffer) {
+    let theme = theme::get();
+    let style = Style::default().bg(theme.get_background_color());
+    buf.set_style(area, style);
+
+    let (length, capacity) = self.log.grids_getlengthcapacity();
+    let status = format!(" {}{}Cells: {}/{}{}Undo: {}{}",
+      self.get_cell_label(), SEPARATOR, length, capacity, SEPARATOR,
+      self.log.history_getposition(), SEPARATOR);
+    let (column, _) = buf.set_stringn(area.left(), area.top(), &status,
+      usize::from(area.width), style);
+
+    let (saved, saved_style) = if self.log.history_issaved() {
+      ("Saved", style)
+    } else {
+      ("Unsaved changes", style.fg(theme.get_warning_color()))
+    };
+    buf.set_stringn(column, area.top(), saved,
+      usize::from(area.right() - column), saved_style);
+  }
+}